@@ -0,0 +1,134 @@
+// Exposes window management to SnapToWindow over D-Bus. Wayland doesn't let
+// clients see or move other clients' windows, so the app asks GNOME Shell to
+// do it instead. Must stay in sync with the `Shell` proxy in
+// src-tauri/src/window_manager/linux.rs.
+
+import Gio from 'gi://Gio';
+import GLib from 'gi://GLib';
+import Meta from 'gi://Meta';
+
+import * as Main from 'resource:///org/gnome/shell/ui/main.js';
+import {Extension} from 'resource:///org/gnome/shell/extensions/extension.js';
+
+const BUS_NAME = 'org.snaptowindow.Shell';
+const OBJECT_PATH = '/org/snaptowindow/Shell';
+
+const INTERFACE = `
+<node>
+  <interface name="org.snaptowindow.Shell">
+    <method name="GetFocusedWindow">
+      <arg type="(tssiiuu)" direction="out" name="window"/>
+    </method>
+    <method name="GetWindows">
+      <arg type="a(tssiiuu)" direction="out" name="windows"/>
+    </method>
+    <method name="SetWindowFrame">
+      <arg type="t" direction="in" name="id"/>
+      <arg type="i" direction="in" name="x"/>
+      <arg type="i" direction="in" name="y"/>
+      <arg type="u" direction="in" name="width"/>
+      <arg type="u" direction="in" name="height"/>
+    </method>
+    <method name="GetMonitors">
+      <arg type="a(sbiiuuiiuud)" direction="out" name="monitors"/>
+    </method>
+  </interface>
+</node>`;
+
+function serializeWindow(window) {
+    const frame = window.get_frame_rect();
+    return [
+        window.get_id(),
+        window.get_wm_class() ?? '',
+        window.get_title() ?? '',
+        frame.x,
+        frame.y,
+        frame.width,
+        frame.height,
+    ];
+}
+
+function isSnappable(window) {
+    return window.get_window_type() === Meta.WindowType.NORMAL &&
+        !window.minimized &&
+        !window.skip_taskbar;
+}
+
+class ShellService {
+    GetFocusedWindow() {
+        const window = global.display.focus_window;
+        if (!window)
+            throw new GLib.Error(Gio.DBusError, Gio.DBusError.FAILED, 'No focused window');
+
+        return serializeWindow(window);
+    }
+
+    GetWindows() {
+        const workspace = global.workspace_manager.get_active_workspace();
+        const windows = global.display
+            .sort_windows_by_stacking(workspace.list_windows())
+            .filter(isSnappable)
+            .reverse();
+
+        return windows.map(serializeWindow);
+    }
+
+    SetWindowFrame(id, x, y, width, height) {
+        const window = global.display
+            .list_all_windows()
+            .find(w => w.get_id() === id);
+        if (!window)
+            throw new GLib.Error(Gio.DBusError, Gio.DBusError.INVALID_ARGS, 'No such window');
+
+        // Maximized windows ignore move/resize requests
+        if (window.get_maximized())
+            window.unmaximize(Meta.MaximizeFlags.BOTH);
+
+        window.move_resize_frame(true, x, y, width, height);
+    }
+
+    GetMonitors() {
+        const workspace = global.workspace_manager.get_active_workspace();
+        const primary = global.display.get_primary_monitor();
+
+        return Main.layoutManager.monitors.map(monitor => {
+            const workArea = workspace.get_work_area_for_monitor(monitor.index);
+
+            // Shell doesn't expose connector or product names to extensions
+            return [
+                `Monitor ${monitor.index + 1}`,
+                monitor.index === primary,
+                monitor.x,
+                monitor.y,
+                monitor.width,
+                monitor.height,
+                workArea.x,
+                workArea.y,
+                workArea.width,
+                workArea.height,
+                global.display.get_monitor_scale(monitor.index),
+            ];
+        });
+    }
+}
+
+export default class SnapToWindowExtension extends Extension {
+    enable() {
+        this._service = Gio.DBusExportedObject.wrapJSObject(INTERFACE, new ShellService());
+        this._service.export(Gio.DBus.session, OBJECT_PATH);
+        this._ownerId = Gio.bus_own_name(
+            Gio.BusType.SESSION,
+            BUS_NAME,
+            Gio.BusNameOwnerFlags.NONE,
+            null,
+            null,
+            null
+        );
+    }
+
+    disable() {
+        Gio.bus_unown_name(this._ownerId);
+        this._service.unexport();
+        this._service = null;
+    }
+}
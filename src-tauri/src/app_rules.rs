@@ -0,0 +1,55 @@
+//! Default positions per app: a window of an app in `app_rules` is snapped to
+//! the rule's position the first time it is focused.
+
+use crate::config::{AppRule, Config};
+use crate::window_manager::{WindowHandle, WindowManager};
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::AppHandle;
+
+// How often to check which window is focused
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+// App rules from the config, kept here so polling doesn't re-read the file
+static RULES: Mutex<Vec<AppRule>> = Mutex::new(Vec::new());
+
+/// Pick up the app rules from a freshly loaded config, or from the display
+/// profile for the connected displays if it has its own.
+pub fn reload_rules(config: &Config) {
+    *RULES.lock().unwrap() = WindowManager::with_display_profile(config.clone()).app_rules;
+}
+
+/// Watch for newly focused windows and apply their app's rule to them.
+pub fn start_app_rules_watcher(app: AppHandle) {
+    reload_rules(&Config::load().unwrap_or_default());
+
+    std::thread::spawn(move || {
+        // Windows that have been focused before, whether or not they had a rule
+        let mut seen: HashSet<WindowHandle> = HashSet::new();
+
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+
+            let rules = RULES.lock().unwrap().clone();
+            if rules.is_empty() {
+                continue;
+            }
+
+            let Ok(window) = WindowManager::frontmost_window() else {
+                continue;
+            };
+            if !seen.insert(window.handle) {
+                continue;
+            }
+
+            // Windows must be moved on the main thread on macOS
+            app.run_on_main_thread(move || {
+                if let Err(e) = WindowManager::new().apply_app_rule(&window, &rules) {
+                    eprintln!("Failed to apply app rule: {}", e);
+                }
+            })
+            .ok();
+        }
+    });
+}
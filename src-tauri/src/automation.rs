@@ -0,0 +1,146 @@
+//! `snaptowindow://` URLs, so snaps can be driven from Shortcuts.app, AppleScript
+//! (`open location`), or any other tool that can open a URL:
+//!
+//! - `snaptowindow://snap/right_half` snaps the focused window
+//! - `snaptowindow://snap/right_half?app=Safari` snaps Safari's frontmost window
+//! - `snaptowindow://snap/left_half?display=2` snaps onto display 2
+//! - `snaptowindow://move-to-display/2` moves the focused window to display 2
+//! - `snaptowindow://next-display` moves the focused window to the next display
+//! - `snaptowindow://restore` restores the focused window's previous frame
+
+use crate::i18n::tr_args;
+use crate::window_manager::{DisplayDirection, SnapPosition, WindowManager};
+use tauri::Url;
+
+/// An action requested by a URL.
+#[derive(Debug, PartialEq)]
+pub enum Action {
+    Snap {
+        position: SnapPosition,
+        app: Option<String>,
+        display: Option<usize>,
+    },
+    MoveToDisplay(usize),
+    NextDisplay,
+    Restore,
+}
+
+/// Run the action for an opened URL.
+pub fn handle_url(url: &Url) {
+    if let Err(e) = parse(url).and_then(run) {
+        eprintln!("Failed to handle {}: {}", url, e);
+    }
+}
+
+/// Work out which action a URL asks for.
+pub fn parse(url: &Url) -> Result<Action, String> {
+    // The action is the URL's host, e.g. "snap" in snaptowindow://snap/left_half
+    let action = url.host_str().unwrap_or_default();
+    let argument = url.path().trim_start_matches('/');
+    let query = |key: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.into_owned())
+    };
+    let display_number = |display: &str| {
+        display
+            .parse()
+            .map_err(|_| tr_args("error.invalid_display", &[&display]))
+    };
+
+    match action {
+        "snap" => {
+            let position = argument.parse()?;
+            let app = query("app");
+            // The app's window stays on its own display
+            let display = match (&app, query("display")) {
+                (None, Some(display)) => Some(display_number(&display)?),
+                _ => None,
+            };
+            Ok(Action::Snap {
+                position,
+                app,
+                display,
+            })
+        }
+        "move-to-display" => Ok(Action::MoveToDisplay(display_number(argument)?)),
+        "next-display" => Ok(Action::NextDisplay),
+        "restore" => Ok(Action::Restore),
+        _ => Err(tr_args("error.unknown_action", &[&action])),
+    }
+}
+
+fn run(action: Action) -> Result<(), String> {
+    let manager = WindowManager::new();
+    let result = match action {
+        Action::Snap {
+            position,
+            app: Some(app),
+            ..
+        } => manager.snap_app(&app, position),
+        Action::Snap {
+            position,
+            display: Some(number),
+            ..
+        } => manager.snap_to_display(position, number),
+        Action::Snap { position, .. } => manager.snap_to(position),
+        Action::MoveToDisplay(number) => manager.move_to_display_number(number),
+        Action::NextDisplay => manager.move_to_display(DisplayDirection::Next),
+        Action::Restore => manager.restore(),
+    };
+
+    result.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(url: &str) -> Result<Action, String> {
+        parse(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn parses_documented_urls() {
+        assert_eq!(
+            parse_str("snaptowindow://snap/right_half"),
+            Ok(Action::Snap {
+                position: SnapPosition::RightHalf,
+                app: None,
+                display: None
+            })
+        );
+        assert_eq!(
+            parse_str("snaptowindow://snap/right_half?app=Safari"),
+            Ok(Action::Snap {
+                position: SnapPosition::RightHalf,
+                app: Some("Safari".into()),
+                display: None
+            })
+        );
+        assert_eq!(
+            parse_str("snaptowindow://snap/left_half?display=2"),
+            Ok(Action::Snap {
+                position: SnapPosition::LeftHalf,
+                app: None,
+                display: Some(2)
+            })
+        );
+        assert_eq!(
+            parse_str("snaptowindow://move-to-display/2"),
+            Ok(Action::MoveToDisplay(2))
+        );
+        assert_eq!(
+            parse_str("snaptowindow://next-display"),
+            Ok(Action::NextDisplay)
+        );
+        assert_eq!(parse_str("snaptowindow://restore"), Ok(Action::Restore));
+    }
+
+    #[test]
+    fn rejects_unknown_actions_and_positions() {
+        assert!(parse_str("snaptowindow://fling/left_half").is_err());
+        assert!(parse_str("snaptowindow://snap/next_display").is_err());
+        assert!(parse_str("snaptowindow://move-to-display/two").is_err());
+    }
+}
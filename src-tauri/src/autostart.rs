@@ -0,0 +1,90 @@
+//! Launch at login. macOS and Windows go through the autostart plugin; on Linux
+//! we write an XDG autostart entry, which every freedesktop session honors.
+
+use tauri::AppHandle;
+
+#[cfg(not(target_os = "linux"))]
+use tauri_plugin_autostart::ManagerExt;
+
+#[cfg(target_os = "linux")]
+use crate::i18n::tr;
+
+#[cfg(target_os = "linux")]
+const DESKTOP_FILE: &str = "snaptowindow.desktop";
+
+/// Enable or disable launching the app at login.
+#[cfg(not(target_os = "linux"))]
+pub fn set_enabled(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    let autostart = app.autolaunch();
+    let result = if enabled {
+        autostart.enable()
+    } else {
+        autostart.disable()
+    };
+
+    result.map_err(|e| format!("{:?}", e))
+}
+
+/// Whether the app is currently set to launch at login.
+#[cfg(not(target_os = "linux"))]
+pub fn is_enabled(app: &AppHandle) -> bool {
+    app.autolaunch().is_enabled().unwrap_or(false)
+}
+
+/// Enable or disable launching the app at login.
+#[cfg(target_os = "linux")]
+pub fn set_enabled(_app: &AppHandle, enabled: bool) -> Result<(), String> {
+    let path = desktop_file_path().ok_or_else(|| tr("error.no_config_dir"))?;
+
+    if !enabled {
+        return match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        };
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(&path, desktop_entry()?).map_err(|e| e.to_string())
+}
+
+/// Whether the app is currently set to launch at login.
+#[cfg(target_os = "linux")]
+pub fn is_enabled(_app: &AppHandle) -> bool {
+    desktop_file_path().is_some_and(|path| path.exists())
+}
+
+#[cfg(target_os = "linux")]
+fn desktop_file_path() -> Option<std::path::PathBuf> {
+    // dirs::config_dir() honors $XDG_CONFIG_HOME
+    dirs::config_dir().map(|dir| dir.join("autostart").join(DESKTOP_FILE))
+}
+
+#[cfg(target_os = "linux")]
+fn desktop_entry() -> Result<String, String> {
+    // An AppImage's executable lives in a temporary mount, so launch the
+    // AppImage itself
+    let exe = match std::env::var_os("APPIMAGE") {
+        Some(appimage) => std::path::PathBuf::from(appimage),
+        None => std::env::current_exe().map_err(|e| e.to_string())?,
+    };
+
+    // Keep using the same config when launched at login
+    let config_dir = crate::config::config_dir_flag()
+        .map(|dir| format!(" --config-dir \"{}\"", dir.display()))
+        .unwrap_or_default();
+
+    Ok(format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=SnapToWindow\n\
+         Comment=Snap windows into place with keyboard shortcuts\n\
+         Exec=\"{}\" --minimized{}\n\
+         Icon=snaptowindow\n\
+         Terminal=false\n\
+         X-GNOME-Autostart-enabled=true\n",
+        exe.display(),
+        config_dir
+    ))
+}
@@ -1,7 +1,15 @@
-use crate::config::Config;
+use crate::autostart;
+use crate::config::{Config, UpdateChannel};
+use crate::hotkeys;
+use crate::import;
+use crate::reload;
+use crate::settings_file;
+use crate::validate::{self, ConfigIssue};
 use crate::tray;
-use crate::window_manager::{DisplayDirection, SnapPosition, WindowManager};
-use tauri_plugin_autostart::ManagerExt;
+use crate::window_manager::{
+    Display, DisplayDirection, PresetSize, ResizeAction, SnapPosition, ThrowDirection,
+    Window, WindowHandle, WindowManager, WindowSelector,
+};
 
 #[tauri::command]
 pub fn snap_window(position: SnapPosition) -> Result<(), String> {
@@ -9,32 +17,172 @@ pub fn snap_window(position: SnapPosition) -> Result<(), String> {
     manager.snap_to(position).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub fn snap_to_display(position: SnapPosition, display_id: usize) -> Result<(), String> {
+    let manager = WindowManager::new();
+    manager
+        .snap_to_display(position, display_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn snap_window_to_preset(preset: PresetSize) -> Result<(), String> {
+    let manager = WindowManager::new();
+    manager.snap_to_preset(&preset).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn resize_window(action: ResizeAction) -> Result<(), String> {
+    let manager = WindowManager::new();
+    manager.resize(action).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn fill_window() -> Result<(), String> {
+    let manager = WindowManager::new();
+    manager.fill().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn mirror_previous_window() -> Result<(), String> {
+    let manager = WindowManager::new();
+    manager.mirror_previous().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn cascade_app_windows() -> Result<(), String> {
+    let manager = WindowManager::new();
+    manager.cascade_app_windows().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_app_window_titles() -> Result<Vec<String>, String> {
+    let manager = WindowManager::new();
+    manager.app_window_titles().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn snap_app_window(position: SnapPosition, window: WindowSelector) -> Result<(), String> {
+    let manager = WindowManager::new();
+    manager
+        .snap_app_window(position, &window)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn snap_all_app_windows(position: SnapPosition) -> Result<(), String> {
+    let manager = WindowManager::new();
+    manager.snap_all_app_windows(position).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_visible_windows() -> Result<Vec<Window>, String> {
+    let manager = WindowManager::new();
+    manager.visible_windows().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn snap_window_by_handle(handle: WindowHandle, position: SnapPosition) -> Result<(), String> {
+    let manager = WindowManager::new();
+    manager
+        .snap_window_by_handle(handle, position)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn restore_window() -> Result<(), String> {
+    let manager = WindowManager::new();
+    manager.restore().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn toggle_ignore_window() -> Result<bool, String> {
+    let manager = WindowManager::new();
+    manager.toggle_ignore_focused().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn apply_app_rules() -> Result<usize, String> {
+    let config = Config::load()
+        .map(WindowManager::with_display_profile)
+        .map_err(|e| e.to_string())?;
+    let manager = WindowManager::new();
+    manager.apply_app_rules(&config.app_rules).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn move_window_to_display(direction: DisplayDirection) -> Result<(), String> {
     let manager = WindowManager::new();
     manager.move_to_display(direction).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub fn move_window_to_display_number(number: usize) -> Result<(), String> {
+    let manager = WindowManager::new();
+    manager.move_to_display_number(number).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn throw_window_to_display(direction: ThrowDirection) -> Result<(), String> {
+    let manager = WindowManager::new();
+    manager.throw_to_display(direction).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn span_displays() -> Result<(), String> {
+    let manager = WindowManager::new();
+    manager.span_displays().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn swap_displays(a: usize, b: usize) -> Result<(), String> {
+    let manager = WindowManager::new();
+    manager.swap_displays(a, b).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_all_displays() -> Result<Vec<Display>, String> {
+    let manager = WindowManager::new();
+    manager.displays().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_display_fingerprints() -> Result<Vec<String>, String> {
+    let manager = WindowManager::new();
+    let displays = manager.connected_displays().map_err(|e| e.to_string())?;
+    Ok(displays.iter().map(|d| d.fingerprint()).collect())
+}
+
 #[tauri::command]
 pub fn get_config() -> Result<Config, String> {
     Config::load().map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub fn validate_config() -> Vec<ConfigIssue> {
+    validate::validate_config()
+}
+
 #[tauri::command]
 pub fn save_config(app: tauri::AppHandle, config: Config) -> Result<(), String> {
     // Update autostart state
-    let autostart_manager = app.autolaunch();
-    if config.launch_at_login {
-        autostart_manager
-            .enable()
-            .map_err(|e| format!("{:?}", e))?;
-    } else {
-        autostart_manager
-            .disable()
-            .map_err(|e| format!("{:?}", e))?;
-    }
+    autostart::set_enabled(&app, config.launch_at_login)?;
+
+    config.save().map_err(|e| e.to_string())?;
+
+    // New bindings take effect now rather than on the next launch
+    hotkeys::reregister_hotkeys(&app).map_err(|e| e.to_string())
+}
 
-    config.save().map_err(|e| e.to_string())
+#[tauri::command]
+pub fn set_shortcut_profile(app: tauri::AppHandle, name: Option<String>) -> Result<(), String> {
+    hotkeys::switch_shortcut_profile(&app, name).map_err(|e| e.to_string())?;
+    tray::rebuild_tray(&app).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn reload_hotkeys(app: tauri::AppHandle) -> Result<(), String> {
+    hotkeys::reregister_hotkeys(&app).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -56,6 +204,11 @@ pub fn check_accessibility() -> bool {
     }
 }
 
+#[tauri::command]
+pub fn request_accessibility() -> bool {
+    tray::request_accessibility()
+}
+
 #[tauri::command]
 pub fn open_accessibility_settings() -> Result<(), String> {
     #[cfg(target_os = "macos")]
@@ -77,6 +230,69 @@ pub fn open_accessibility_settings() -> Result<(), String> {
     Ok(())
 }
 
+#[tauri::command]
+pub fn snap_visible_window(position: SnapPosition, window: WindowSelector) -> Result<(), String> {
+    let manager = WindowManager::new();
+    manager
+        .snap_visible_window(position, &window)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn import_fancyzones_layouts(app: tauri::AppHandle, path: Option<String>) -> Result<usize, String> {
+    let count = import::import_fancyzones(path.map(Into::into))?;
+
+    // Show the new positions in the tray
+    tray::rebuild_tray(&app).map_err(|e| e.to_string())?;
+    Ok(count)
+}
+
+#[tauri::command]
+pub fn import_mac_shortcuts(app: tauri::AppHandle, path: Option<String>) -> Result<usize, String> {
+    let count = import::import_mac_shortcuts(path.map(Into::into))?;
+
+    hotkeys::reregister_hotkeys(&app).map_err(|e| e.to_string())?;
+    Ok(count)
+}
+
+#[tauri::command]
+pub fn export_settings(path: Option<String>) -> Result<String, String> {
+    settings_file::export_settings(path.map(Into::into)).map(|p| p.display().to_string())
+}
+
+#[tauri::command]
+pub fn import_settings(app: tauri::AppHandle, path: Option<String>) -> Result<(), String> {
+    settings_file::import_settings(path.map(Into::into))?;
+
+    // Register the imported shortcuts and show the imported positions
+    reload::apply_config(&app)
+}
+
+#[tauri::command]
+pub async fn check_for_update(app: tauri::AppHandle, include_skipped: bool) -> Result<Option<String>, String> {
+    tray::check_for_update(&app, include_skipped).await
+}
+
+#[tauri::command]
+pub fn skip_update(app: tauri::AppHandle) -> Result<(), String> {
+    tray::skip_update(&app).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn install_update(app: tauri::AppHandle) -> Result<(), String> {
+    tray::install_update(&app).await
+}
+
+#[tauri::command]
+pub fn set_update_channel(app: tauri::AppHandle, channel: UpdateChannel) -> Result<(), String> {
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    config.update_channel = channel;
+    config.save().map_err(|e| e.to_string())?;
+
+    // Show the channel in the tray
+    tray::rebuild_tray(&app).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn refresh_tray(app: tauri::AppHandle) -> Result<(), String> {
     tray::refresh_tray(&app).map_err(|e| e.to_string())
@@ -1,11 +1,258 @@
+use crate::window_manager::{
+    CustomFrame, Display, DisplaySelector, Insets, LayoutOptions, PresetSize, ResizeAnchor, ResizeStep,
+    SnapPosition,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
+
+// Largest margin or gap used, so a typo can't squeeze windows to nothing
+const MAX_SPACING: u32 = 400;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub shortcuts: ShortcutConfig,
+    /// Alternative sets of shortcuts to switch between from the tray.
+    pub shortcut_profiles: Vec<ShortcutProfile>,
+    /// Name of the shortcut profile in use. Unset uses `shortcuts`.
+    pub active_shortcut_profile: Option<String>,
+    /// Apps that keep some shortcuts for themselves while they are frontmost.
+    pub shortcut_passthrough: Vec<ShortcutPassthrough>,
     pub launch_at_login: bool,
+    /// Language of the tray menu and messages, e.g. "de". Unset follows the
+    /// system language.
+    pub language: Option<String>,
+    /// Check for updates at startup and every `update_check_interval_hours`.
+    pub auto_update_check: bool,
+    /// Hours between automatic update checks.
+    pub update_check_interval_hours: u32,
+    /// Download updates found by automatic checks in the background, so
+    /// installing one from the tray doesn't wait for the download. When off,
+    /// updates are only downloaded on request.
+    pub download_updates: bool,
+    /// Release channel to update from.
+    pub update_channel: UpdateChannel,
+    /// Update version the user chose to skip. Automatic checks don't offer it,
+    /// only newer ones.
+    pub skipped_update_version: Option<String>,
+    /// Fraction of the screen given to the left/top half (e.g. 0.62 for a 62/38 split).
+    pub split_ratio: f64,
+    /// Pixels left between snapped windows and the screen edges.
+    pub outer_margin: u32,
+    /// Extra pixels left at individual screen edges, on top of `outer_margin`.
+    pub edge_margins: Insets,
+    /// Pixels left between adjacent snapped windows.
+    pub inner_gap: u32,
+    /// How much grow/shrink actions change the window by.
+    pub resize_step: ResizeStep,
+    /// Whether grow/shrink expands around the center or the nearest screen edge.
+    pub resize_anchor: ResizeAnchor,
+    /// Warp the mouse cursor to the window after moving it to another display.
+    pub cursor_follows_window: bool,
+    /// Bring snapped windows to the front and focus them. Only needed on
+    /// Windows, where snapping otherwise leaves focus where it was (e.g. on the
+    /// taskbar after using the tray).
+    pub activate_on_snap: bool,
+    /// Take windows out of the snap group Windows 11 put them in (via Snap
+    /// Layouts or Win+Arrow) when snapping them. When off, such windows keep
+    /// the OS's idea of their layout.
+    pub leave_os_snap_groups: bool,
+    /// Fixed-size window presets, shown in the tray and bindable to shortcuts.
+    pub presets: Vec<PresetConfig>,
+    /// Named positions as fractions of the work area, shown in the tray.
+    pub custom_positions: Vec<CustomPositionConfig>,
+    /// Move windows back to a display when it is reconnected, at the position
+    /// they were last snapped to there.
+    pub restore_on_reconnect: bool,
+    /// Put windows back where they were snapped when the app starts, e.g.
+    /// after a reboot.
+    pub restore_layout_on_startup: bool,
+    /// Extra space to keep clear on specific displays (e.g. for a status bar).
+    pub display_overrides: Vec<DisplayOverride>,
+    /// Positions calculated over all displays combined instead of the current one.
+    pub global_positions: Vec<SnapPosition>,
+    /// Keep windows out of the area beside the camera notch on MacBooks, even
+    /// when the menu bar is hidden.
+    pub avoid_notch: bool,
+    /// Default positions for apps' windows, applied the first time each
+    /// window is focused.
+    pub app_rules: Vec<AppRule>,
+    /// Apps whose windows are never moved or resized (e.g. games or virtual
+    /// machines), by name, or executable name on Windows.
+    pub excluded_apps: Vec<String>,
+    /// Displays skipped when moving windows between displays (e.g. a TV).
+    pub excluded_displays: Vec<DisplaySelector>,
+    /// Displays to divide into side-by-side virtual displays (e.g. an ultrawide).
+    pub display_splits: Vec<DisplaySplit>,
+    /// Settings to use instead while a particular set of displays is connected.
+    pub profiles: Vec<DisplayProfile>,
+    /// Modifier that, while held, lets a left-drag move and a right-drag resize
+    /// any window from anywhere inside it. Off when unset.
+    pub drag_modifier: Option<DragModifier>,
+    /// What shortcuts act on when no window is focused, e.g. right after
+    /// using the tray.
+    pub no_focus_fallback: NoFocusFallback,
+    /// Snap positions triggered by double-tapping a modifier key on its own.
+    pub double_tap: Vec<DoubleTapBinding>,
+    /// On-screen confirmation shown after snapping a window.
+    pub osd: OsdConfig,
+    /// Format to write the config file in. Unset keeps the format of the
+    /// existing file.
+    pub config_format: Option<ConfigFormat>,
+}
+
+/// Snaps to `position` when `key` is pressed and released twice in quick
+/// succession, with no other key in between.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoubleTapBinding {
+    pub key: ModifierKey,
+    pub position: SnapPosition,
+}
+
+/// One specific modifier key, telling left and right apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModifierKey {
+    LeftAlt,
+    RightAlt,
+    LeftControl,
+    RightControl,
+    LeftShift,
+    RightShift,
+    LeftSuper,
+    RightSuper,
+}
+
+/// A modifier key for modifier-drag.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DragModifier {
+    /// Option on macOS
+    Alt,
+    Control,
+    Shift,
+    /// Command on macOS, the Windows key on Windows
+    Super,
+}
+
+/// Settings that take over from the top-level ones while exactly the listed
+/// displays are connected, e.g. different gaps when docked. Unset fields keep
+/// the top-level value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisplayProfile {
+    pub name: String,
+    /// Fingerprints of the displays this profile is for, as `"name@widthxheight"`.
+    pub displays: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub split_ratio: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outer_margin: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edge_margins: Option<Insets>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inner_gap: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor_follows_window: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_overrides: Option<Vec<DisplayOverride>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_splits: Option<Vec<DisplaySplit>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_positions: Option<Vec<CustomPositionConfig>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_rules: Option<Vec<AppRule>>,
+}
+
+impl DisplayProfile {
+    /// Whether the connected displays are exactly the ones this profile is for.
+    pub fn matches(&self, displays: &[Display]) -> bool {
+        let mut wanted = self.displays.clone();
+        let mut connected: Vec<String> = displays.iter().map(|d| d.fingerprint()).collect();
+        wanted.sort();
+        connected.sort();
+
+        wanted == connected
+    }
+}
+
+/// A named set of shortcuts, e.g. for a laptop keyboard without arrow keys
+/// in easy reach.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShortcutProfile {
+    pub name: String,
+    pub shortcuts: ShortcutConfig,
+}
+
+/// Where an app's windows go by default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppRule {
+    /// App name, or executable name on Windows.
+    pub app: String,
+    pub position: SnapPosition,
+    /// Display number to put the windows on. Unset keeps them on their display.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<usize>,
+}
+
+impl AppRule {
+    /// Whether this rule is for the given app.
+    pub fn matches(&self, app: &str) -> bool {
+        same_app(&self.app, app)
+    }
+}
+
+/// Shortcuts left to an app while it is frontmost.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShortcutPassthrough {
+    /// App name, or executable name on Windows (e.g. "Code" or "devenv.exe").
+    pub app: String,
+    /// Shortcut setting names (e.g. "left_half"). Empty passes every shortcut through.
+    #[serde(default)]
+    pub shortcuts: Vec<String>,
+}
+
+impl ShortcutPassthrough {
+    /// Whether this rule is for the given app.
+    pub fn matches(&self, app: &str) -> bool {
+        same_app(&self.app, app)
+    }
+}
+
+/// Margins reserved on a specific display, on top of its OS-reported work area.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisplayOverride {
+    pub display: DisplaySelector,
+    #[serde(flatten)]
+    pub margins: Insets,
+}
+
+/// Divides a display into virtual displays that snap positions work within.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisplaySplit {
+    pub display: DisplaySelector,
+    /// Where to divide the display, as fractions of its width (e.g. `[0.5]`).
+    pub splits: Vec<f64>,
+}
+
+/// A named fixed-size preset with an optional shortcut.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresetConfig {
+    pub name: String,
+    #[serde(default)]
+    pub shortcut: String,
+    #[serde(flatten)]
+    pub size: PresetSize,
+}
+
+/// A named custom position.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomPositionConfig {
+    pub name: String,
+    #[serde(flatten)]
+    pub frame: CustomFrame,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,17 +271,145 @@ pub struct ShortcutConfig {
     pub right_third: String,
     pub left_two_thirds: String,
     pub right_two_thirds: String,
+    pub top_third: String,
+    pub middle_third: String,
+    pub bottom_third: String,
+    pub top_two_thirds: String,
+    pub bottom_two_thirds: String,
+    pub first_fourth: String,
+    pub second_fourth: String,
+    pub third_fourth: String,
+    pub last_fourth: String,
+    pub first_three_fourths: String,
+    pub last_three_fourths: String,
+    pub top_left_two_thirds: String,
+    pub top_right_two_thirds: String,
+    pub bottom_left_two_thirds: String,
+    pub bottom_right_two_thirds: String,
+    pub top_left_sixth: String,
+    pub top_center_sixth: String,
+    pub top_right_sixth: String,
+    pub bottom_left_sixth: String,
+    pub bottom_center_sixth: String,
+    pub bottom_right_sixth: String,
+    pub top_left_ninth: String,
+    pub top_center_ninth: String,
+    pub top_right_ninth: String,
+    pub middle_left_ninth: String,
+    pub middle_center_ninth: String,
+    pub middle_right_ninth: String,
+    pub bottom_left_ninth: String,
+    pub bottom_center_ninth: String,
+    pub bottom_right_ninth: String,
     pub center: String,
+    pub center_half: String,
     pub maximize: String,
+    pub maximize_height: String,
+    pub maximize_width: String,
+    pub restore: String,
+    pub toggle_ignore_window: String,
+    pub fill: String,
+    pub mirror_previous: String,
+    pub cascade_app_windows: String,
+    pub grow: String,
+    pub shrink: String,
+    pub grow_width: String,
+    pub shrink_width: String,
+    pub grow_height: String,
+    pub shrink_height: String,
     pub next_display: String,
     pub previous_display: String,
+    pub span_displays: String,
+    pub swap_displays: String,
+    pub throw_left: String,
+    pub throw_right: String,
+    pub throw_up: String,
+    pub throw_down: String,
+    pub display_1: String,
+    pub display_2: String,
+    pub display_3: String,
+    /// Actions whose shortcut is kept but not registered, by setting name
+    /// (e.g. "top_half"), so they don't claim a hotkey another app uses.
+    pub disabled: Vec<String>,
+    /// Shortcuts for custom positions, by position name.
+    pub custom_positions: BTreeMap<String, String>,
+}
+
+impl ShortcutConfig {
+    /// Every action's setting name and shortcut, e.g. ("left_half", "Control+Alt+Left").
+    pub fn bindings(&self) -> Vec<(String, String)> {
+        let Ok(serde_json::Value::Object(fields)) = serde_json::to_value(self) else {
+            return Vec::new();
+        };
+
+        fields
+            .into_iter()
+            .filter_map(|(name, value)| Some((name, value.as_str()?.to_string())))
+            .collect()
+    }
+
+    /// These shortcuts with the disabled ones left empty, so they are skipped
+    /// when registering.
+    pub fn enabled(&self) -> Self {
+        if self.disabled.is_empty() {
+            return self.clone();
+        }
+
+        let Ok(serde_json::Value::Object(mut fields)) = serde_json::to_value(self) else {
+            return self.clone();
+        };
+
+        for name in &self.disabled {
+            match fields.get_mut(name) {
+                Some(value) if value.is_string() => *value = String::new().into(),
+                _ => eprintln!("Unknown shortcut in disabled: {}", name),
+            }
+        }
+
+        serde_json::from_value(fields.into()).unwrap_or_else(|_| self.clone())
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             shortcuts: ShortcutConfig::default(),
+            shortcut_profiles: Vec::new(),
+            active_shortcut_profile: None,
+            shortcut_passthrough: Vec::new(),
             launch_at_login: false,
+            language: None,
+            auto_update_check: true,
+            update_check_interval_hours: 1,
+            download_updates: false,
+            update_channel: UpdateChannel::default(),
+            skipped_update_version: None,
+            split_ratio: 0.5,
+            outer_margin: 0,
+            edge_margins: Insets::default(),
+            inner_gap: 0,
+            resize_step: ResizeStep::default(),
+            resize_anchor: ResizeAnchor::default(),
+            cursor_follows_window: false,
+            activate_on_snap: false,
+            leave_os_snap_groups: true,
+            restore_on_reconnect: true,
+            restore_layout_on_startup: false,
+            presets: Vec::new(),
+            custom_positions: Vec::new(),
+            display_overrides: Vec::new(),
+            global_positions: Vec::new(),
+            avoid_notch: false,
+            app_rules: Vec::new(),
+            excluded_apps: Vec::new(),
+            excluded_displays: Vec::new(),
+            display_splits: Vec::new(),
+            profiles: Vec::new(),
+            drag_modifier: None,
+            double_tap: Vec::new(),
+            no_focus_fallback: NoFocusFallback::default(),
+            osd: OsdConfig::default(),
+            config_format: None,
         }
     }
 }
@@ -55,30 +430,186 @@ impl Default for ShortcutConfig {
             right_third: "Control+Alt+G".to_string(),
             left_two_thirds: "Control+Alt+E".to_string(),
             right_two_thirds: "Control+Alt+R".to_string(),
+            top_third: "Control+Alt+Shift+D".to_string(),
+            middle_third: "Control+Alt+Shift+F".to_string(),
+            bottom_third: "Control+Alt+Shift+G".to_string(),
+            top_two_thirds: "Control+Alt+Shift+E".to_string(),
+            bottom_two_thirds: "Control+Alt+Shift+R".to_string(),
+            // Fourths are unbound by default
+            first_fourth: String::new(),
+            second_fourth: String::new(),
+            third_fourth: String::new(),
+            last_fourth: String::new(),
+            first_three_fourths: String::new(),
+            last_three_fourths: String::new(),
+            // Corner two-thirds are unbound by default
+            top_left_two_thirds: String::new(),
+            top_right_two_thirds: String::new(),
+            bottom_left_two_thirds: String::new(),
+            bottom_right_two_thirds: String::new(),
+            top_left_sixth: "Control+Alt+Shift+U".to_string(),
+            top_center_sixth: "Control+Alt+Shift+I".to_string(),
+            top_right_sixth: "Control+Alt+Shift+O".to_string(),
+            bottom_left_sixth: "Control+Alt+Shift+J".to_string(),
+            bottom_center_sixth: "Control+Alt+Shift+K".to_string(),
+            bottom_right_sixth: "Control+Alt+Shift+L".to_string(),
+            // Ninths are unbound by default; set a shortcut in config.json to enable them
+            top_left_ninth: String::new(),
+            top_center_ninth: String::new(),
+            top_right_ninth: String::new(),
+            middle_left_ninth: String::new(),
+            middle_center_ninth: String::new(),
+            middle_right_ninth: String::new(),
+            bottom_left_ninth: String::new(),
+            bottom_center_ninth: String::new(),
+            bottom_right_ninth: String::new(),
             center: "Control+Alt+C".to_string(),
+            center_half: "Control+Alt+Shift+C".to_string(),
             maximize: "Control+Alt+Enter".to_string(),
+            maximize_height: "Control+Alt+Shift+Up".to_string(),
+            maximize_width: "Control+Alt+Shift+Right".to_string(),
+            restore: "Control+Alt+Backspace".to_string(),
+            toggle_ignore_window: String::new(),
+            fill: String::new(),
+            mirror_previous: String::new(),
+            cascade_app_windows: String::new(),
+            grow: "Control+Alt+=".to_string(),
+            shrink: "Control+Alt+-".to_string(),
+            grow_width: String::new(),
+            shrink_width: String::new(),
+            grow_height: String::new(),
+            shrink_height: String::new(),
             next_display: "Control+Alt+]".to_string(),
             previous_display: "Control+Alt+[".to_string(),
+            span_displays: String::new(),
+            swap_displays: String::new(),
+            throw_left: String::new(),
+            throw_right: String::new(),
+            throw_up: String::new(),
+            throw_down: String::new(),
+            display_1: "Control+Alt+1".to_string(),
+            display_2: "Control+Alt+2".to_string(),
+            display_3: "Control+Alt+3".to_string(),
+            disabled: Vec::new(),
+            custom_positions: BTreeMap::new(),
         }
     }
 }
 
 impl Config {
-    pub fn config_path() -> PathBuf {
-        let config_dir = dirs::config_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("snaptowindow");
+    /// Directory the config lives in: the `--config-dir` flag, else the
+    /// `SNAPTOWINDOW_CONFIG_DIR` environment variable, else a `config` folder
+    /// next to the executable in portable mode, else the user's config folder.
+    fn config_dir() -> PathBuf {
+        let config_dir = config_dir_flag()
+            .or_else(|| std::env::var_os("SNAPTOWINDOW_CONFIG_DIR").map(PathBuf::from))
+            .or_else(portable_config_dir)
+            .unwrap_or_else(|| {
+                dirs::config_dir()
+                    .unwrap_or_else(|| PathBuf::from("."))
+                    .join("snaptowindow")
+            });
 
         fs::create_dir_all(&config_dir).ok();
-        config_dir.join("config.json")
+        config_dir
+    }
+
+    /// The config file in use: config.toml if there is one, otherwise config.json.
+    pub fn config_path() -> PathBuf {
+        Self::config_dir().join(Self::file_format().file_name())
+    }
+
+    fn file_format() -> ConfigFormat {
+        if Self::config_dir().join(ConfigFormat::Toml.file_name()).exists() {
+            ConfigFormat::Toml
+        } else {
+            ConfigFormat::Json
+        }
+    }
+
+    /// Time between automatic update checks, at least an hour.
+    pub fn update_check_interval(&self) -> Duration {
+        Duration::from_secs(u64::from(self.update_check_interval_hours.max(1)) * 60 * 60)
+    }
+
+    /// Layout options derived from this config, clamped to sane values.
+    pub fn layout_options(&self) -> LayoutOptions {
+        LayoutOptions {
+            split_ratio: self.split_ratio.clamp(0.1, 0.9),
+            outer_margin: self.outer_margin.min(MAX_SPACING),
+            edge_margins: Insets {
+                top: self.edge_margins.top.min(MAX_SPACING),
+                right: self.edge_margins.right.min(MAX_SPACING),
+                bottom: self.edge_margins.bottom.min(MAX_SPACING),
+                left: self.edge_margins.left.min(MAX_SPACING),
+            },
+            inner_gap: self.inner_gap.min(MAX_SPACING),
+            resize_step: self.resize_step,
+            resize_anchor: self.resize_anchor,
+        }
+    }
+
+    /// The shortcuts of the active shortcut profile, or the top-level ones
+    /// when none is active.
+    pub fn active_shortcuts(&self) -> &ShortcutConfig {
+        self.active_shortcut_profile
+            .as_ref()
+            .and_then(|name| self.shortcut_profiles.iter().find(|p| &p.name == name))
+            .map_or(&self.shortcuts, |p| &p.shortcuts)
+    }
+
+    /// The profile for the given set of connected displays, if there is one.
+    pub fn active_profile(&self, displays: &[Display]) -> Option<&DisplayProfile> {
+        self.profiles.iter().find(|p| p.matches(displays))
+    }
+
+    /// This config with the settings of the profile for the given displays applied.
+    pub fn with_profile(mut self, displays: &[Display]) -> Self {
+        let Some(profile) = self.active_profile(displays).cloned() else {
+            return self;
+        };
+
+        if let Some(split_ratio) = profile.split_ratio {
+            self.split_ratio = split_ratio;
+        }
+        if let Some(outer_margin) = profile.outer_margin {
+            self.outer_margin = outer_margin;
+        }
+        if let Some(edge_margins) = profile.edge_margins {
+            self.edge_margins = edge_margins;
+        }
+        if let Some(inner_gap) = profile.inner_gap {
+            self.inner_gap = inner_gap;
+        }
+        if let Some(cursor_follows_window) = profile.cursor_follows_window {
+            self.cursor_follows_window = cursor_follows_window;
+        }
+        if let Some(display_overrides) = profile.display_overrides {
+            self.display_overrides = display_overrides;
+        }
+        if let Some(display_splits) = profile.display_splits {
+            self.display_splits = display_splits;
+        }
+        if let Some(custom_positions) = profile.custom_positions {
+            self.custom_positions = custom_positions;
+        }
+        if let Some(app_rules) = profile.app_rules {
+            self.app_rules = app_rules;
+        }
+
+        self
     }
 
     pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
-        let path = Self::config_path();
+        let format = Self::file_format();
+        let path = Self::config_dir().join(format.file_name());
 
         if path.exists() {
             let content = fs::read_to_string(&path)?;
-            let config: Config = serde_json::from_str(&content)?;
+            let config: Config = match format {
+                ConfigFormat::Json => serde_json::from_str(&content)?,
+                ConfigFormat::Toml => toml::from_str(&content)?,
+            };
             Ok(config)
         } else {
             let config = Config::default();
@@ -88,9 +619,156 @@ impl Config {
     }
 
     pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let path = Self::config_path();
-        let content = serde_json::to_string_pretty(self)?;
-        fs::write(path, content)?;
+        let format = self.config_format.unwrap_or_else(Self::file_format);
+        let content = match format {
+            ConfigFormat::Json => serde_json::to_string_pretty(self)?,
+            ConfigFormat::Toml => toml::to_string_pretty(self)?,
+        };
+        fs::write(Self::config_dir().join(format.file_name()), content)?;
+
+        // Only one config file may exist, or the other one would shadow it
+        // (or be left stale) on the next load
+        let other = match format {
+            ConfigFormat::Json => ConfigFormat::Toml,
+            ConfigFormat::Toml => ConfigFormat::Json,
+        };
+        let other_path = Self::config_dir().join(other.file_name());
+        if other_path.exists() {
+            fs::remove_file(other_path)?;
+        }
         Ok(())
     }
 }
+
+/// Whether two app names are the same app, ignoring case and ".exe", so
+/// config entries can be written either way.
+pub fn same_app(a: &str, b: &str) -> bool {
+    let name = |s: &str| s.to_lowercase().trim_end_matches(".exe").to_string();
+    name(a) == name(b)
+}
+
+/// The directory passed with `--config-dir <dir>` or `--config-dir=<dir>`.
+pub fn config_dir_flag() -> Option<PathBuf> {
+    let mut args = std::env::args_os().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--config-dir" {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(dir) = arg.to_str().and_then(|a| a.strip_prefix("--config-dir=")) {
+            return Some(PathBuf::from(dir));
+        }
+    }
+    None
+}
+
+/// The `config` folder next to the executable, when a file named `portable`
+/// sits beside it (e.g. when running from a USB stick).
+fn portable_config_dir() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let dir = exe.parent()?;
+    dir.join("portable").exists().then(|| dir.join("config"))
+}
+
+/// What to do when a shortcut is used with no window focused.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoFocusFallback {
+    /// Do nothing.
+    #[default]
+    Error,
+    /// Show a notification saying no window is focused.
+    Notify,
+    /// Act on the window snapped most recently.
+    LastSnapped,
+    /// Act on the frontmost window.
+    Topmost,
+}
+
+/// Which releases to update to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateChannel {
+    /// Stable releases only.
+    #[default]
+    Stable,
+    /// Beta builds as well as stable releases.
+    Beta,
+}
+
+/// The on-screen confirmation (HUD) shown after a snap, naming the position.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OsdConfig {
+    pub enabled: bool,
+    /// How long it stays up, in milliseconds.
+    pub duration_ms: u64,
+    pub size: OsdSize,
+    /// Where it appears on the display the window was snapped on.
+    pub position: OsdPosition,
+}
+
+impl Default for OsdConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            duration_ms: 800,
+            size: OsdSize::default(),
+            position: OsdPosition::default(),
+        }
+    }
+}
+
+impl OsdConfig {
+    /// How long the HUD stays up, clamped to between 0.1 and 10 seconds.
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(self.duration_ms.clamp(100, 10_000))
+    }
+}
+
+/// Size of the snap HUD.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OsdSize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+}
+
+impl OsdSize {
+    /// Width and height in logical pixels.
+    pub fn dimensions(self) -> (f64, f64) {
+        match self {
+            OsdSize::Small => (200.0, 48.0),
+            OsdSize::Medium => (280.0, 68.0),
+            OsdSize::Large => (380.0, 96.0),
+        }
+    }
+}
+
+/// Where the snap HUD appears on the display.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OsdPosition {
+    Top,
+    #[default]
+    Center,
+    Bottom,
+}
+
+/// File format of the config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+}
+
+impl ConfigFormat {
+    fn file_name(self) -> &'static str {
+        match self {
+            ConfigFormat::Json => "config.json",
+            ConfigFormat::Toml => "config.toml",
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! D-Bus control service for Linux, so the app can be scripted from the shell,
+//! keybinding daemons or other tools:
+//!
+//! ```bash
+//! busctl --user call org.snaptowindow.Control /org/snaptowindow/Control \
+//!     org.snaptowindow.Control Snap s left_half
+//! ```
+
+use crate::window_manager::{SnapPosition, WindowManager};
+use std::sync::OnceLock;
+use zbus::fdo;
+
+const BUS_NAME: &str = "org.snaptowindow.Control";
+const OBJECT_PATH: &str = "/org/snaptowindow/Control";
+
+// Kept alive for as long as the app runs; dropping it releases the bus name
+static CONNECTION: OnceLock<zbus::blocking::Connection> = OnceLock::new();
+
+struct Control;
+
+#[zbus::interface(name = "org.snaptowindow.Control")]
+impl Control {
+    /// Snap the focused window to a position, e.g. "left_half" or "top_right".
+    fn snap(&self, position: &str) -> fdo::Result<()> {
+        let position: SnapPosition = position.parse().map_err(fdo::Error::InvalidArgs)?;
+
+        let manager = WindowManager::new();
+        manager.snap_to(position).map_err(failed)
+    }
+
+    /// Move the focused window to the display with the given 1-based number.
+    fn move_to_display(&self, number: u32) -> fdo::Result<()> {
+        let manager = WindowManager::new();
+        manager
+            .move_to_display_number(number as usize)
+            .map_err(failed)
+    }
+
+    /// List displays as (number, name, is primary, x, y, width, height). The
+    /// parts of a split display share its number.
+    fn list_displays(&self) -> fdo::Result<Vec<(u32, String, bool, i32, i32, u32, u32)>> {
+        let manager = WindowManager::new();
+        let displays = manager.displays_by_number().map_err(failed)?;
+
+        Ok(displays
+            .into_iter()
+            .map(|(number, d)| {
+                (
+                    number as u32,
+                    d.name,
+                    d.is_primary,
+                    d.bounds.x,
+                    d.bounds.y,
+                    d.bounds.width,
+                    d.bounds.height,
+                )
+            })
+            .collect())
+    }
+}
+
+fn failed(e: impl std::fmt::Display) -> fdo::Error {
+    fdo::Error::Failed(e.to_string())
+}
+
+/// Claim the control service name on the session bus.
+pub fn start_control_service() {
+    std::thread::spawn(|| {
+        let connection = zbus::blocking::connection::Builder::session()
+            .and_then(|b| b.name(BUS_NAME))
+            .and_then(|b| b.serve_at(OBJECT_PATH, Control))
+            .and_then(|b| b.build());
+
+        match connection {
+            Ok(connection) => {
+                let _ = CONNECTION.set(connection);
+            }
+            Err(e) => eprintln!("Failed to start D-Bus control service: {}", e),
+        }
+    });
+}
@@ -0,0 +1,251 @@
+//! Double-tap modifier triggers, e.g. tapping Right Option twice to maximize.
+//! The global-shortcut plugin only handles chords, so this listens to raw key
+//! events through a platform hook and spots two quick, bare taps of the same
+//! modifier.
+
+use crate::config::{Config, DoubleTapBinding, ModifierKey};
+use crate::window_manager::WindowManager;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+// Longest gap between the two taps
+const DOUBLE_TAP_INTERVAL: Duration = Duration::from_millis(350);
+
+/// A key event as seen by the platform hook.
+enum KeyEvent {
+    ModifierDown(ModifierKey),
+    ModifierUp(ModifierKey),
+    /// Any other key, which makes a modifier press part of a chord
+    Other,
+}
+
+#[derive(Default)]
+struct TapState {
+    /// The modifier currently held, and whether it is still on its own
+    pressed: Option<(ModifierKey, bool)>,
+    /// The last bare tap
+    last_tap: Option<(ModifierKey, Instant)>,
+}
+
+static STATE: Mutex<TapState> = Mutex::new(TapState {
+    pressed: None,
+    last_tap: None,
+});
+
+/// Start listening for double taps if any are configured.
+pub fn start_double_tap_listener(app: AppHandle) {
+    let bindings = Config::load().map(|c| c.double_tap).unwrap_or_default();
+    if bindings.is_empty() {
+        return;
+    }
+
+    platform::start(move |key| on_double_tap(&app, &bindings, key));
+}
+
+/// Run the binding for a double-tapped key.
+fn on_double_tap(app: &AppHandle, bindings: &[DoubleTapBinding], key: ModifierKey) {
+    let Some(binding) = bindings.iter().find(|b| b.key == key) else {
+        return;
+    };
+
+    // Display APIs (NSScreen on macOS) must be used on the main thread, and
+    // the hook mustn't be held up anyway
+    let position = binding.position;
+    let _ = app.run_on_main_thread(move || {
+        let manager = WindowManager::new();
+        if let Err(e) = manager.snap_to(position) {
+            eprintln!("Failed to snap window: {}", e);
+        }
+    });
+}
+
+/// Feed a key event to the detector. Returns the modifier if this event
+/// completes a double tap.
+fn handle(event: KeyEvent) -> Option<ModifierKey> {
+    let mut state = STATE.lock().unwrap();
+
+    match event {
+        KeyEvent::ModifierDown(key) => match state.pressed {
+            // Auto-repeat while held
+            Some((pressed, _)) if pressed == key => {}
+            // Another modifier already held: a chord
+            Some(_) => {
+                state.pressed = Some((key, false));
+                state.last_tap = None;
+            }
+            None => state.pressed = Some((key, true)),
+        },
+        KeyEvent::ModifierUp(key) => {
+            let bare = state.pressed == Some((key, true));
+            state.pressed = None;
+
+            if !bare {
+                state.last_tap = None;
+                return None;
+            }
+
+            match state.last_tap {
+                Some((last, at)) if last == key && at.elapsed() <= DOUBLE_TAP_INTERVAL => {
+                    state.last_tap = None;
+                    return Some(key);
+                }
+                _ => state.last_tap = Some((key, Instant::now())),
+            }
+        }
+        KeyEvent::Other => {
+            if let Some((_, bare)) = state.pressed.as_mut() {
+                *bare = false;
+            }
+            state.last_tap = None;
+        }
+    }
+
+    None
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{handle, KeyEvent};
+    use crate::config::ModifierKey;
+    use core_foundation::runloop::{kCFRunLoopCommonModes, CFRunLoop};
+    use core_graphics::event::{
+        CGEvent, CGEventTap, CGEventTapLocation, CGEventTapOptions, CGEventTapPlacement,
+        CGEventType, EventField,
+    };
+
+    // Device-dependent flag bits (NX_DEVICE*KEYMASK), which tell left and
+    // right modifiers apart, with the virtual keycode of each key
+    const MODIFIERS: [(ModifierKey, u64, i64); 8] = [
+        (ModifierKey::LeftControl, 0x0001, 59),
+        (ModifierKey::LeftShift, 0x0002, 56),
+        (ModifierKey::RightShift, 0x0004, 60),
+        (ModifierKey::LeftSuper, 0x0008, 55),
+        (ModifierKey::RightSuper, 0x0010, 54),
+        (ModifierKey::LeftAlt, 0x0020, 58),
+        (ModifierKey::RightAlt, 0x0040, 61),
+        (ModifierKey::RightControl, 0x2000, 62),
+    ];
+
+    /// Install a listen-only event tap for key events on its own run loop thread.
+    pub fn start(on_double_tap: impl Fn(ModifierKey) + Send + 'static) {
+        std::thread::spawn(move || {
+            let tap = CGEventTap::new(
+                CGEventTapLocation::HID,
+                CGEventTapPlacement::HeadInsertEventTap,
+                CGEventTapOptions::ListenOnly,
+                vec![CGEventType::FlagsChanged, CGEventType::KeyDown],
+                move |_proxy, event_type, event: &CGEvent| {
+                    if let Some(key) = handle(key_event(event_type, event)) {
+                        on_double_tap(key);
+                    }
+                    None
+                },
+            );
+
+            let Ok(tap) = tap else {
+                eprintln!("Failed to create key event tap (is Accessibility enabled?)");
+                return;
+            };
+
+            let Ok(source) = tap.mach_port.create_runloop_source(0) else {
+                eprintln!("Failed to create run loop source for key event tap");
+                return;
+            };
+
+            unsafe {
+                CFRunLoop::get_current().add_source(&source, kCFRunLoopCommonModes);
+            }
+            tap.enable();
+            CFRunLoop::run_current();
+        });
+    }
+
+    fn key_event(event_type: CGEventType, event: &CGEvent) -> KeyEvent {
+        if !matches!(event_type, CGEventType::FlagsChanged) {
+            return KeyEvent::Other;
+        }
+
+        let keycode = event.get_integer_value_field(EventField::KEYBOARD_EVENT_KEYCODE);
+        let flags = event.get_flags().bits();
+
+        match MODIFIERS.iter().find(|(_, _, code)| *code == keycode) {
+            Some(&(key, mask, _)) if flags & mask != 0 => KeyEvent::ModifierDown(key),
+            Some(&(key, _, _)) => KeyEvent::ModifierUp(key),
+            // Caps Lock, Fn, etc.
+            None => KeyEvent::Other,
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{handle, KeyEvent};
+    use crate::config::ModifierKey;
+    use std::sync::OnceLock;
+    use windows::Win32::Foundation::{HINSTANCE, HWND, LPARAM, LRESULT, WPARAM};
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        VK_LCONTROL, VK_LMENU, VK_LSHIFT, VK_LWIN, VK_RCONTROL, VK_RMENU, VK_RSHIFT, VK_RWIN,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CallNextHookEx, GetMessageW, SetWindowsHookExW, HHOOK, KBDLLHOOKSTRUCT, MSG,
+        WH_KEYBOARD_LL, WM_KEYDOWN, WM_KEYUP, WM_SYSKEYDOWN, WM_SYSKEYUP,
+    };
+
+    // Called from the hook, which can't capture state
+    static ON_DOUBLE_TAP: OnceLock<Box<dyn Fn(ModifierKey) + Send + Sync>> = OnceLock::new();
+
+    /// Install a low-level keyboard hook on its own message loop thread.
+    pub fn start(on_double_tap: impl Fn(ModifierKey) + Send + Sync + 'static) {
+        if ON_DOUBLE_TAP.set(Box::new(on_double_tap)).is_err() {
+            return;
+        }
+
+        std::thread::spawn(|| unsafe {
+            if let Err(e) =
+                SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_hook), HINSTANCE::default(), 0)
+            {
+                eprintln!("Failed to install keyboard hook: {}", e);
+                return;
+            }
+
+            // Low-level hooks are called through this thread's message loop
+            let mut msg = MSG::default();
+            while GetMessageW(&mut msg, HWND::default(), 0, 0).as_bool() {}
+        });
+    }
+
+    fn modifier_key(vk_code: u32) -> Option<ModifierKey> {
+        let key = match vk_code as u16 {
+            k if k == VK_LMENU.0 => ModifierKey::LeftAlt,
+            k if k == VK_RMENU.0 => ModifierKey::RightAlt,
+            k if k == VK_LCONTROL.0 => ModifierKey::LeftControl,
+            k if k == VK_RCONTROL.0 => ModifierKey::RightControl,
+            k if k == VK_LSHIFT.0 => ModifierKey::LeftShift,
+            k if k == VK_RSHIFT.0 => ModifierKey::RightShift,
+            k if k == VK_LWIN.0 => ModifierKey::LeftSuper,
+            k if k == VK_RWIN.0 => ModifierKey::RightSuper,
+            _ => return None,
+        };
+        Some(key)
+    }
+
+    unsafe extern "system" fn keyboard_hook(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        // Negative codes must be passed straight on
+        if code >= 0 {
+            let info = unsafe { &*(lparam.0 as *const KBDLLHOOKSTRUCT) };
+
+            let event = match (modifier_key(info.vkCode), wparam.0 as u32) {
+                (Some(key), WM_KEYDOWN | WM_SYSKEYDOWN) => KeyEvent::ModifierDown(key),
+                (Some(key), WM_KEYUP | WM_SYSKEYUP) => KeyEvent::ModifierUp(key),
+                _ => KeyEvent::Other,
+            };
+
+            if let (Some(key), Some(on_double_tap)) = (handle(event), ON_DOUBLE_TAP.get()) {
+                on_double_tap(key);
+            }
+        }
+
+        unsafe { CallNextHookEx(HHOOK::default(), code, wparam, lparam) }
+    }
+}
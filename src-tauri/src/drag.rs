@@ -0,0 +1,376 @@
+//! Modifier-drag: while the configured modifier is held, left-dragging anywhere
+//! inside a window moves it and right-dragging resizes it from the nearest
+//! corner, like on most Linux window managers.
+//!
+//! Mouse events come from a platform hook, which only notes where a drag
+//! began and where the cursor is now; a worker thread looks up the window and
+//! applies the latest frame, so slow window lookups and moves never hold up
+//! the hook (Windows removes low-level hooks that take too long).
+
+use crate::config::{Config, DragModifier};
+use crate::window_manager::{Rect, Window, WindowHandle, WindowManager};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+
+// Windows are never resized smaller than this by dragging
+const MIN_DRAG_SIZE: u32 = 100;
+
+enum DragMode {
+    Move,
+    /// Which edges follow the cursor: the ones nearest to where the drag began
+    Resize { left: bool, top: bool },
+}
+
+/// A drag as the hook sees it: the window under the cursor when it began.
+struct DragStart {
+    handle: WindowHandle,
+    start: (i32, i32),
+    resize: bool,
+}
+
+/// A drag as the worker sees it, once the window has been looked up.
+struct Drag {
+    window: Window,
+    start: (i32, i32),
+    frame: Rect,
+    mode: DragMode,
+}
+
+/// What the hook has passed on that the worker hasn't handled yet.
+struct Pending {
+    begin: Option<DragStart>,
+    cursor: Option<(i32, i32)>,
+}
+
+// Whether a drag is in progress, as far as the hook knows
+static DRAGGING: AtomicBool = AtomicBool::new(false);
+
+// The latest drag start and cursor position waiting for the worker
+static PENDING: Mutex<Pending> = Mutex::new(Pending {
+    begin: None,
+    cursor: None,
+});
+static PENDING_READY: Condvar = Condvar::new();
+
+/// Start modifier-drag if it is enabled in the config.
+pub fn start_modifier_drag() {
+    let Some(modifier) = Config::load().ok().and_then(|c| c.drag_modifier) else {
+        return;
+    };
+
+    std::thread::spawn(apply_pending_frames);
+    platform::start(modifier);
+}
+
+/// Begin dragging the window under the cursor. Returns whether a drag started,
+/// in which case the hook swallows the click.
+fn begin(x: i32, y: i32, resize: bool) -> bool {
+    let Some(handle) = platform::hit_test(x, y) else {
+        return false;
+    };
+
+    let mut pending = PENDING.lock().unwrap();
+    pending.begin = Some(DragStart {
+        handle,
+        start: (x, y),
+        resize,
+    });
+    pending.cursor = None;
+    DRAGGING.store(true, Ordering::SeqCst);
+    PENDING_READY.notify_one();
+    true
+}
+
+/// Follow the cursor with the window being dragged, if any.
+fn update(x: i32, y: i32) {
+    if !DRAGGING.load(Ordering::SeqCst) {
+        return;
+    }
+
+    PENDING.lock().unwrap().cursor = Some((x, y));
+    PENDING_READY.notify_one();
+}
+
+/// Stop dragging. Returns whether a drag was in progress.
+fn end() -> bool {
+    DRAGGING.swap(false, Ordering::SeqCst)
+}
+
+impl Drag {
+    /// Look up the window a drag began on. `None` if it can't be snapped.
+    fn new(manager: &WindowManager, begin: DragStart) -> Option<Self> {
+        let window = manager.window_by_handle(&begin.handle).ok()?;
+        let (x, y) = begin.start;
+
+        let (center_x, center_y) = window.frame.center();
+        let mode = if begin.resize {
+            DragMode::Resize {
+                left: x < center_x,
+                top: y < center_y,
+            }
+        } else {
+            DragMode::Move
+        };
+
+        Some(Drag {
+            frame: window.frame,
+            window,
+            start: begin.start,
+            mode,
+        })
+    }
+
+    /// The window's frame with the cursor at `x`, `y`.
+    fn frame_at(&self, x: i32, y: i32) -> Rect {
+        let dx = x - self.start.0;
+        let dy = y - self.start.1;
+        let f = self.frame;
+
+        match self.mode {
+            DragMode::Move => Rect::new(f.x + dx, f.y + dy, f.width, f.height),
+            DragMode::Resize { left, top } => {
+                let (x, width) = resize_edge(f.x, f.width, dx, left);
+                let (y, height) = resize_edge(f.y, f.height, dy, top);
+                Rect::new(x, y, width, height)
+            }
+        }
+    }
+}
+
+/// Move one edge of a span by `delta`: the leading edge (keeping the far edge
+/// in place) or the trailing one.
+fn resize_edge(start: i32, length: u32, delta: i32, leading: bool) -> (i32, u32) {
+    if leading {
+        let end = start + length as i32;
+        let length = (length as i32 - delta).max(MIN_DRAG_SIZE as i32);
+        (end - length, length as u32)
+    } else {
+        (start, (length as i32 + delta).max(MIN_DRAG_SIZE as i32) as u32)
+    }
+}
+
+/// Look up the window when a drag begins, then apply the latest cursor
+/// position whenever one comes in, skipping any that were superseded while
+/// the previous move was in flight.
+fn apply_pending_frames() {
+    let manager = WindowManager::new();
+    let mut drag = None;
+
+    loop {
+        let (begin, cursor) = {
+            let mut pending = PENDING.lock().unwrap();
+            loop {
+                if pending.begin.is_some() || pending.cursor.is_some() {
+                    break (pending.begin.take(), pending.cursor.take());
+                }
+                pending = PENDING_READY.wait(pending).unwrap();
+            }
+        };
+
+        if let Some(begin) = begin {
+            drag = Drag::new(&manager, begin);
+        }
+
+        let (Some(drag), Some((x, y))) = (drag.as_ref(), cursor) else {
+            continue;
+        };
+        if let Err(e) = manager.set_frame(&drag.window, drag.frame_at(x, y)) {
+            eprintln!("Failed to drag window: {}", e);
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{begin, end, update};
+    use crate::config::DragModifier;
+    use crate::window_manager::{WindowHandle, WindowManager};
+    use core_foundation::runloop::{kCFRunLoopCommonModes, CFRunLoop};
+    use core_graphics::event::{
+        CGEvent, CGEventFlags, CGEventTap, CGEventTapLocation, CGEventTapOptions,
+        CGEventTapPlacement, CGEventType,
+    };
+
+    /// Get the window under the cursor. The window list comes from the window
+    /// server, which answers quickly enough for an event tap.
+    pub fn hit_test(x: i32, y: i32) -> Option<WindowHandle> {
+        WindowManager::new().window_at(x, y).ok().map(|w| w.handle)
+    }
+
+    /// Install an event tap for mouse events on its own run loop thread.
+    pub fn start(modifier: DragModifier) {
+        let flag = match modifier {
+            DragModifier::Alt => CGEventFlags::CGEventFlagAlternate,
+            DragModifier::Control => CGEventFlags::CGEventFlagControl,
+            DragModifier::Shift => CGEventFlags::CGEventFlagShift,
+            DragModifier::Super => CGEventFlags::CGEventFlagCommand,
+        };
+
+        std::thread::spawn(move || {
+            let events = vec![
+                CGEventType::LeftMouseDown,
+                CGEventType::LeftMouseDragged,
+                CGEventType::LeftMouseUp,
+                CGEventType::RightMouseDown,
+                CGEventType::RightMouseDragged,
+                CGEventType::RightMouseUp,
+            ];
+
+            let tap = CGEventTap::new(
+                CGEventTapLocation::HID,
+                CGEventTapPlacement::HeadInsertEventTap,
+                CGEventTapOptions::Default,
+                events,
+                move |_proxy, event_type, event: &CGEvent| {
+                    let location = event.location();
+                    let (x, y) = (location.x.round() as i32, location.y.round() as i32);
+
+                    let handled = match event_type {
+                        CGEventType::LeftMouseDown | CGEventType::RightMouseDown => {
+                            event.get_flags().contains(flag)
+                                && begin(x, y, matches!(event_type, CGEventType::RightMouseDown))
+                        }
+                        // Let the cursor itself keep moving
+                        CGEventType::LeftMouseDragged | CGEventType::RightMouseDragged => {
+                            update(x, y);
+                            false
+                        }
+                        CGEventType::LeftMouseUp | CGEventType::RightMouseUp => end(),
+                        _ => false,
+                    };
+
+                    // Swallow the clicks that start and end a drag: a null event
+                    // never reaches the window under the cursor
+                    if handled {
+                        event.set_type(CGEventType::Null);
+                    }
+                    None
+                },
+            );
+
+            let Ok(tap) = tap else {
+                eprintln!("Failed to create mouse event tap (is Accessibility enabled?)");
+                return;
+            };
+
+            let Ok(source) = tap.mach_port.create_runloop_source(0) else {
+                eprintln!("Failed to create run loop source for mouse event tap");
+                return;
+            };
+
+            unsafe {
+                CFRunLoop::get_current().add_source(&source, kCFRunLoopCommonModes);
+            }
+            tap.enable();
+            CFRunLoop::run_current();
+        });
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{begin, end, update};
+    use crate::config::DragModifier;
+    use crate::window_manager::{WindowHandle, WindowManager};
+    use std::sync::atomic::{AtomicU16, Ordering};
+    use windows::Win32::Foundation::{HINSTANCE, HWND, LPARAM, LRESULT, POINT, WPARAM};
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        GetAsyncKeyState, VK_CONTROL, VK_LWIN, VK_MENU, VK_RWIN, VK_SHIFT,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CallNextHookEx, GetAncestor, GetClassNameW, GetMessageW, SetWindowsHookExW, WindowFromPoint, GA_ROOT, HHOOK, MSG, MSLLHOOKSTRUCT, WH_MOUSE_LL, WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MOUSEMOVE, WM_RBUTTONDOWN, WM_RBUTTONUP,
+    };
+
+    // Virtual key of the drag modifier, read by the hook
+    static MODIFIER_KEY: AtomicU16 = AtomicU16::new(0);
+
+    /// Install a low-level mouse hook on its own message loop thread.
+    pub fn start(modifier: DragModifier) {
+        let key = match modifier {
+            DragModifier::Alt => VK_MENU,
+            DragModifier::Control => VK_CONTROL,
+            DragModifier::Shift => VK_SHIFT,
+            DragModifier::Super => VK_LWIN,
+        };
+        MODIFIER_KEY.store(key.0, Ordering::SeqCst);
+
+        std::thread::spawn(|| unsafe {
+            // Makes this thread per-monitor DPI aware, so hook coordinates
+            // match window frames
+            let _ = WindowManager::new();
+
+            if let Err(e) = SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_hook), HINSTANCE::default(), 0) {
+                eprintln!("Failed to install mouse hook: {}", e);
+                return;
+            }
+
+            // Low-level hooks are called through this thread's message loop
+            let mut msg = MSG::default();
+            while GetMessageW(&mut msg, HWND::default(), 0, 0).as_bool() {}
+        });
+    }
+
+    /// Get the top-level window under the cursor, leaving out the desktop and
+    /// the taskbar. Only cheap calls here: the worker looks the window up
+    /// properly, since anything slow in the hook gets it removed.
+    pub fn hit_test(x: i32, y: i32) -> Option<WindowHandle> {
+        let hwnd = unsafe { GetAncestor(WindowFromPoint(POINT { x, y }), GA_ROOT) };
+        if hwnd.is_invalid() {
+            return None;
+        }
+
+        let mut buffer = [0u16; 256];
+        let len = unsafe { GetClassNameW(hwnd, &mut buffer) };
+        let class = String::from_utf16_lossy(&buffer[..len.max(0) as usize]);
+        if matches!(
+            class.as_str(),
+            "Progman" | "WorkerW" | "Shell_TrayWnd" | "Shell_SecondaryTrayWnd"
+        ) {
+            return None;
+        }
+
+        Some(WindowHandle::Windows(hwnd.0 as isize))
+    }
+
+    fn is_key_down(key: u16) -> bool {
+        unsafe { GetAsyncKeyState(key as i32) < 0 }
+    }
+
+    fn modifier_down() -> bool {
+        let key = MODIFIER_KEY.load(Ordering::SeqCst);
+
+        // Either Windows key counts
+        if key == VK_LWIN.0 {
+            return is_key_down(VK_LWIN.0) || is_key_down(VK_RWIN.0);
+        }
+        is_key_down(key)
+    }
+
+    unsafe extern "system" fn mouse_hook(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        // Negative codes must be passed straight on
+        if code >= 0 {
+            let info = unsafe { &*(lparam.0 as *const MSLLHOOKSTRUCT) };
+            let (x, y) = (info.pt.x, info.pt.y);
+
+            let handled = match wparam.0 as u32 {
+                WM_LBUTTONDOWN | WM_RBUTTONDOWN => {
+                    modifier_down() && begin(x, y, wparam.0 as u32 == WM_RBUTTONDOWN)
+                }
+                // Let the cursor itself keep moving
+                WM_MOUSEMOVE => {
+                    update(x, y);
+                    false
+                }
+                WM_LBUTTONUP | WM_RBUTTONUP => end(),
+                _ => false,
+            };
+
+            // Swallow the clicks that start and end a drag
+            if handled {
+                return LRESULT(1);
+            }
+        }
+
+        unsafe { CallNextHookEx(HHOOK::default(), code, wparam, lparam) }
+    }
+}
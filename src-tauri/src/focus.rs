@@ -0,0 +1,19 @@
+//! Recording focus changes made outside the app (clicks, alt-tab), so the focus
+//! history mirror uses isn't limited to windows that were snapped.
+
+use crate::window_manager::WindowManager;
+use std::time::Duration;
+
+// How often to check which window is focused
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Watch the focused window and add it to the focus history whenever it changes.
+pub fn start_focus_watcher() {
+    std::thread::spawn(|| loop {
+        std::thread::sleep(POLL_INTERVAL);
+
+        if let Ok(window) = WindowManager::frontmost_window() {
+            WindowManager::remember_focus(window);
+        }
+    });
+}
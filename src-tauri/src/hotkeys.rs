@@ -1,11 +1,85 @@
 use crate::config::Config;
-use crate::window_manager::{DisplayDirection, SnapPosition, WindowManager};
+use crate::i18n::tr_args;
+use crate::passthrough;
+use crate::validate;
+use crate::window_manager::{
+    DisplayDirection, ResizeAction, SnapPosition, ThrowDirection, WindowManager,
+};
 use tauri::AppHandle;
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+/// Replace every registered hotkey with the ones in the current config, so
+/// changed bindings take effect right away.
+pub fn reregister_hotkeys(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the config first, so a bad file doesn't leave us with no hotkeys
+    check_shortcuts(&Config::load()?)?;
+
+    app.global_shortcut().unregister_all()?;
+    register_hotkeys(app)
+}
+
+/// Fail on the first binding that wouldn't register, before any registered
+/// hotkey is touched, so a bad or duplicate binding doesn't leave only some of
+/// them registered.
+fn check_shortcuts(config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    match validate::shortcut_errors(config).into_iter().next() {
+        Some(issue) => Err(format!("{}: {}", issue.setting, issue.message).into()),
+        None => Ok(()),
+    }
+}
+
+/// Switch to the named shortcut profile, or back to the top-level shortcuts
+/// for `None`, and register its hotkeys.
+pub fn switch_shortcut_profile(
+    app: &AppHandle,
+    name: Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut config = Config::load()?;
+    if let Some(name) = &name {
+        if !config.shortcut_profiles.iter().any(|p| &p.name == name) {
+            return Err(tr_args("error.no_shortcut_profile", &[&name]).into());
+        }
+    }
+
+    config.active_shortcut_profile = name;
+    check_shortcuts(&config)?;
+    config.save()?;
+    reregister_hotkeys(app)
+}
+
+/// Register a hotkey that runs `action` once per key press. Handlers are
+/// called on release as well, which would run toggles like Maximize twice.
+fn on_press<F>(
+    app: &AppHandle,
+    shortcut: Shortcut,
+    action: F,
+) -> Result<(), tauri_plugin_global_shortcut::Error>
+where
+    F: Fn() + Send + Sync + 'static,
+{
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |_app, _shortcut, event| {
+            if event.state() != ShortcutState::Pressed {
+                return;
+            }
+            action();
+        })
+}
 
 pub fn register_hotkeys(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    let config = Config::load()?;
-    let shortcuts = &config.shortcuts;
+    // Custom positions can come from the display profile
+    let config = WindowManager::with_display_profile(Config::load()?);
+
+    // Leave out the shortcuts the frontmost app keeps for itself
+    let passed_through = passthrough::passed_through();
+    if passed_through.iter().any(|p| p.shortcuts.is_empty()) {
+        return Ok(());
+    }
+    let mut shortcuts = config.active_shortcuts().clone();
+    shortcuts
+        .disabled
+        .extend(passed_through.into_iter().flat_map(|p| p.shortcuts));
+    let shortcuts = &shortcuts.enabled();
 
     let shortcut_mappings = [
         (&shortcuts.left_half, SnapPosition::LeftHalf),
@@ -21,15 +95,53 @@ pub fn register_hotkeys(app: &AppHandle) -> Result<(), Box<dyn std::error::Error
         (&shortcuts.right_third, SnapPosition::RightThird),
         (&shortcuts.left_two_thirds, SnapPosition::LeftTwoThirds),
         (&shortcuts.right_two_thirds, SnapPosition::RightTwoThirds),
+        (&shortcuts.top_third, SnapPosition::TopThird),
+        (&shortcuts.middle_third, SnapPosition::MiddleThird),
+        (&shortcuts.bottom_third, SnapPosition::BottomThird),
+        (&shortcuts.top_two_thirds, SnapPosition::TopTwoThirds),
+        (&shortcuts.bottom_two_thirds, SnapPosition::BottomTwoThirds),
+        (&shortcuts.first_fourth, SnapPosition::FirstFourth),
+        (&shortcuts.second_fourth, SnapPosition::SecondFourth),
+        (&shortcuts.third_fourth, SnapPosition::ThirdFourth),
+        (&shortcuts.last_fourth, SnapPosition::LastFourth),
+        (&shortcuts.first_three_fourths, SnapPosition::FirstThreeFourths),
+        (&shortcuts.last_three_fourths, SnapPosition::LastThreeFourths),
+        (&shortcuts.top_left_two_thirds, SnapPosition::TopLeftTwoThirds),
+        (&shortcuts.top_right_two_thirds, SnapPosition::TopRightTwoThirds),
+        (&shortcuts.bottom_left_two_thirds, SnapPosition::BottomLeftTwoThirds),
+        (&shortcuts.bottom_right_two_thirds, SnapPosition::BottomRightTwoThirds),
+        (&shortcuts.top_left_sixth, SnapPosition::TopLeftSixth),
+        (&shortcuts.top_center_sixth, SnapPosition::TopCenterSixth),
+        (&shortcuts.top_right_sixth, SnapPosition::TopRightSixth),
+        (&shortcuts.bottom_left_sixth, SnapPosition::BottomLeftSixth),
+        (&shortcuts.bottom_center_sixth, SnapPosition::BottomCenterSixth),
+        (&shortcuts.bottom_right_sixth, SnapPosition::BottomRightSixth),
+        (&shortcuts.top_left_ninth, SnapPosition::TopLeftNinth),
+        (&shortcuts.top_center_ninth, SnapPosition::TopCenterNinth),
+        (&shortcuts.top_right_ninth, SnapPosition::TopRightNinth),
+        (&shortcuts.middle_left_ninth, SnapPosition::MiddleLeftNinth),
+        (&shortcuts.middle_center_ninth, SnapPosition::MiddleCenterNinth),
+        (&shortcuts.middle_right_ninth, SnapPosition::MiddleRightNinth),
+        (&shortcuts.bottom_left_ninth, SnapPosition::BottomLeftNinth),
+        (&shortcuts.bottom_center_ninth, SnapPosition::BottomCenterNinth),
+        (&shortcuts.bottom_right_ninth, SnapPosition::BottomRightNinth),
         (&shortcuts.center, SnapPosition::Center),
+        (&shortcuts.center_half, SnapPosition::CenterHalf),
         (&shortcuts.maximize, SnapPosition::Maximize),
+        (&shortcuts.maximize_height, SnapPosition::MaximizeHeight),
+        (&shortcuts.maximize_width, SnapPosition::MaximizeWidth),
     ];
 
     for (shortcut_str, position) in shortcut_mappings {
+        // Empty shortcuts are left unbound
+        if shortcut_str.is_empty() {
+            continue;
+        }
+
         let shortcut: Shortcut = shortcut_str.parse()?;
         let pos = position.clone();
 
-        app.global_shortcut().on_shortcut(shortcut, move |_app, _shortcut, _event| {
+        on_press(app, shortcut, move || {
             let manager = WindowManager::new();
             if let Err(e) = manager.snap_to(pos.clone()) {
                 eprintln!("Failed to snap window: {}", e);
@@ -37,6 +149,154 @@ pub fn register_hotkeys(app: &AppHandle) -> Result<(), Box<dyn std::error::Error
         })?;
     }
 
+    // Register size preset shortcuts
+    for preset in &config.presets {
+        if preset.shortcut.is_empty() {
+            continue;
+        }
+
+        let shortcut: Shortcut = preset.shortcut.parse()?;
+        let size = preset.size;
+
+        on_press(app, shortcut, move || {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.snap_to_preset(&size) {
+                eprintln!("Failed to apply size preset: {}", e);
+            }
+        })?;
+    }
+
+    // Register custom position shortcuts, looked up by position name
+    for (name, binding) in &shortcuts.custom_positions {
+        if binding.is_empty() {
+            continue;
+        }
+
+        let Some(custom) = config.custom_positions.iter().find(|c| &c.name == name) else {
+            eprintln!("No custom position named {} for shortcut {}", name, binding);
+            continue;
+        };
+
+        let shortcut: Shortcut = binding.parse()?;
+        let frame = custom.frame;
+
+        on_press(app, shortcut, move || {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.snap_to_custom(&frame) {
+                eprintln!("Failed to snap to custom position: {}", e);
+            }
+        })?;
+    }
+
+    // Register grow/shrink shortcuts
+    let resize_mappings = [
+        (&shortcuts.grow, ResizeAction::Grow),
+        (&shortcuts.shrink, ResizeAction::Shrink),
+        (&shortcuts.grow_width, ResizeAction::GrowWidth),
+        (&shortcuts.shrink_width, ResizeAction::ShrinkWidth),
+        (&shortcuts.grow_height, ResizeAction::GrowHeight),
+        (&shortcuts.shrink_height, ResizeAction::ShrinkHeight),
+    ];
+
+    for (shortcut_str, action) in resize_mappings {
+        if shortcut_str.is_empty() {
+            continue;
+        }
+
+        let shortcut: Shortcut = shortcut_str.parse()?;
+
+        on_press(app, shortcut, move || {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.resize(action) {
+                eprintln!("Failed to resize window: {}", e);
+            }
+        })?;
+    }
+
+    // Register the restore shortcut
+    if !shortcuts.restore.is_empty() {
+        let shortcut: Shortcut = shortcuts.restore.parse()?;
+
+        on_press(app, shortcut, move || {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.restore() {
+                eprintln!("Failed to restore window: {}", e);
+            }
+        })?;
+    }
+
+    // Register the shortcut for turning snapping off for a window
+    if !shortcuts.toggle_ignore_window.is_empty() {
+        let shortcut: Shortcut = shortcuts.toggle_ignore_window.parse()?;
+
+        on_press(app, shortcut, move || {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.toggle_ignore_focused() {
+                eprintln!("Failed to toggle snapping for window: {}", e);
+            }
+        })?;
+    }
+
+    // Register the fill shortcut
+    if !shortcuts.fill.is_empty() {
+        let shortcut: Shortcut = shortcuts.fill.parse()?;
+
+        on_press(app, shortcut, move || {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.fill() {
+                eprintln!("Failed to fill empty space: {}", e);
+            }
+        })?;
+    }
+
+    // Register the mirror shortcut
+    if !shortcuts.mirror_previous.is_empty() {
+        let shortcut: Shortcut = shortcuts.mirror_previous.parse()?;
+
+        on_press(app, shortcut, move || {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.mirror_previous() {
+                eprintln!("Failed to mirror previous window: {}", e);
+            }
+        })?;
+    }
+
+    // Register the cascade shortcut
+    if !shortcuts.cascade_app_windows.is_empty() {
+        let shortcut: Shortcut = shortcuts.cascade_app_windows.parse()?;
+
+        on_press(app, shortcut, move || {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.cascade_app_windows() {
+                eprintln!("Failed to cascade app windows: {}", e);
+            }
+        })?;
+    }
+
+    // Register the span displays shortcut
+    if !shortcuts.span_displays.is_empty() {
+        let shortcut: Shortcut = shortcuts.span_displays.parse()?;
+
+        on_press(app, shortcut, move || {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.span_displays() {
+                eprintln!("Failed to span window across displays: {}", e);
+            }
+        })?;
+    }
+
+    // Register the swap displays shortcut
+    if !shortcuts.swap_displays.is_empty() {
+        let shortcut: Shortcut = shortcuts.swap_displays.parse()?;
+
+        on_press(app, shortcut, move || {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.swap_with_adjacent_display() {
+                eprintln!("Failed to swap displays: {}", e);
+            }
+        })?;
+    }
+
     // Register display movement shortcuts
     let display_mappings = [
         (&shortcuts.next_display, DisplayDirection::Next),
@@ -44,10 +304,14 @@ pub fn register_hotkeys(app: &AppHandle) -> Result<(), Box<dyn std::error::Error
     ];
 
     for (shortcut_str, direction) in display_mappings {
+        if shortcut_str.is_empty() {
+            continue;
+        }
+
         let shortcut: Shortcut = shortcut_str.parse()?;
         let dir = direction.clone();
 
-        app.global_shortcut().on_shortcut(shortcut, move |_app, _shortcut, _event| {
+        on_press(app, shortcut, move || {
             let manager = WindowManager::new();
             if let Err(e) = manager.move_to_display(dir.clone()) {
                 eprintln!("Failed to move window to display: {}", e);
@@ -55,5 +319,50 @@ pub fn register_hotkeys(app: &AppHandle) -> Result<(), Box<dyn std::error::Error
         })?;
     }
 
+    // Register shortcuts for throwing to the neighboring display
+    let throw_mappings = [
+        (&shortcuts.throw_left, ThrowDirection::Left),
+        (&shortcuts.throw_right, ThrowDirection::Right),
+        (&shortcuts.throw_up, ThrowDirection::Up),
+        (&shortcuts.throw_down, ThrowDirection::Down),
+    ];
+
+    for (shortcut_str, direction) in throw_mappings {
+        if shortcut_str.is_empty() {
+            continue;
+        }
+
+        let shortcut: Shortcut = shortcut_str.parse()?;
+
+        on_press(app, shortcut, move || {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.throw_to_display(direction) {
+                eprintln!("Failed to throw window to display: {}", e);
+            }
+        })?;
+    }
+
+    // Register shortcuts for moving to a specific display
+    let display_number_mappings = [
+        (&shortcuts.display_1, 1),
+        (&shortcuts.display_2, 2),
+        (&shortcuts.display_3, 3),
+    ];
+
+    for (shortcut_str, number) in display_number_mappings {
+        if shortcut_str.is_empty() {
+            continue;
+        }
+
+        let shortcut: Shortcut = shortcut_str.parse()?;
+
+        on_press(app, shortcut, move || {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.move_to_display_number(number) {
+                eprintln!("Failed to move window to display {}: {}", number, e);
+            }
+        })?;
+    }
+
     Ok(())
 }
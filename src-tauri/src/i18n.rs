@@ -0,0 +1,112 @@
+//! Translated text for the tray menu and error messages. Each language is a
+//! flat JSON map from key to text in `locales/`; `{0}`, `{1}`, ... stand for
+//! arguments. Keys a language is missing fall back to English.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{LazyLock, Mutex};
+
+// Bundled languages by code, English first
+const LOCALES: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.json")),
+    ("de", include_str!("../locales/de.json")),
+];
+
+static STRINGS: LazyLock<HashMap<&'static str, HashMap<String, String>>> = LazyLock::new(|| {
+    LOCALES
+        .iter()
+        .map(|(code, json)| {
+            let strings = serde_json::from_str(json)
+                .unwrap_or_else(|e| panic!("Invalid locale file {}.json: {}", code, e));
+            (*code, strings)
+        })
+        .collect()
+});
+
+// Language used for new text, set from the config
+static LANGUAGE: Mutex<&'static str> = Mutex::new("en");
+
+/// Find the bundled language for a code like "de", "de-AT" or "de_AT.UTF-8".
+fn bundled(code: &str) -> Option<&'static str> {
+    let code = code.to_lowercase();
+    let base = code.split(['-', '_', '.']).next().unwrap_or_default();
+    LOCALES.iter().map(|(c, _)| *c).find(|c| *c == code || *c == base)
+}
+
+/// Whether a language code has a translation.
+pub fn is_supported(code: &str) -> bool {
+    bundled(code).is_some()
+}
+
+/// Use the given language, or the system's when `None`. Falls back to English.
+pub fn set_language(code: Option<&str>) {
+    let language = code
+        .map(str::to_string)
+        .or_else(sys_locale::get_locale)
+        .and_then(|code| bundled(&code))
+        .unwrap_or("en");
+    *LANGUAGE.lock().unwrap() = language;
+}
+
+/// Text for a key in the current language.
+pub fn tr(key: &str) -> String {
+    let language = *LANGUAGE.lock().unwrap();
+    [language, "en"]
+        .iter()
+        .find_map(|code| STRINGS.get(code)?.get(key))
+        .cloned()
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// Text for a key with `{0}`, `{1}`, ... replaced by `args`.
+pub fn tr_args(key: &str, args: &[&dyn Display]) -> String {
+    args.iter()
+        .enumerate()
+        .fold(tr(key), |text, (i, arg)| text.replace(&format!("{{{}}}", i), &arg.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    // The `{0}`, `{1}`, ... placeholders used in a text
+    fn placeholders(text: &str) -> BTreeSet<&str> {
+        text.match_indices('{')
+            .filter_map(|(i, _)| Some(&text[i..=i + text[i..].find('}')?]))
+            .collect()
+    }
+
+    #[test]
+    fn locales_have_the_same_keys_as_english() {
+        let english = &STRINGS["en"];
+        for (code, _) in LOCALES {
+            let strings = &STRINGS[code];
+            let mut missing: Vec<_> = english.keys().filter(|k| !strings.contains_key(*k)).collect();
+            let mut extra: Vec<_> = strings.keys().filter(|k| !english.contains_key(*k)).collect();
+            missing.sort();
+            extra.sort();
+            assert!(missing.is_empty(), "{}.json is missing {:?}", code, missing);
+            assert!(extra.is_empty(), "{}.json has unknown keys {:?}", code, extra);
+        }
+    }
+
+    #[test]
+    fn translations_keep_placeholders() {
+        let english = &STRINGS["en"];
+        for (code, _) in LOCALES {
+            for (key, text) in &STRINGS[code] {
+                let Some(original) = english.get(key) else {
+                    continue;
+                };
+                assert_eq!(
+                    placeholders(text),
+                    placeholders(original),
+                    "{}.json changes the placeholders of {}",
+                    code,
+                    key
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,494 @@
+//! Importers for layouts and settings from other window managers, so people
+//! switching over don't have to set everything up again.
+
+use crate::config::{Config, CustomPositionConfig, ShortcutConfig};
+use crate::i18n::{tr, tr_args};
+use crate::window_manager::CustomFrame;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Where PowerToys keeps FancyZones' custom layouts: custom-layouts.json in
+/// recent versions, zones-settings.json before that.
+fn fancyzones_files() -> Vec<PathBuf> {
+    let Some(dir) = dirs::data_local_dir() else {
+        return Vec::new();
+    };
+
+    let dir = dir.join("Microsoft").join("PowerToys").join("FancyZones");
+    vec![
+        dir.join("custom-layouts.json"),
+        dir.join("zones-settings.json"),
+    ]
+}
+
+/// Import FancyZones custom layouts as custom positions, one per zone, named
+/// after the layout and numbered. Positions imported earlier under the same
+/// names are replaced. Returns how many positions were imported.
+pub fn import_fancyzones(path: Option<PathBuf>) -> Result<usize, String> {
+    let path = match path {
+        Some(path) => path,
+        None => fancyzones_files()
+            .into_iter()
+            .find(|p| p.exists())
+            .ok_or_else(|| tr("error.fancyzones_not_found"))?,
+    };
+
+    let contents = fs::read_to_string(&path)
+        .map_err(|e| tr_args("error.read_file", &[&path.display(), &e]))?;
+    let json: Value = serde_json::from_str(&contents)
+        .map_err(|e| tr_args("error.parse_file", &[&path.display(), &e]))?;
+
+    let layouts = json
+        .get("custom-layouts")
+        .or_else(|| json.get("custom-zone-sets"))
+        .and_then(Value::as_array)
+        .ok_or_else(|| tr("error.no_custom_layouts"))?;
+
+    let mut positions = Vec::new();
+    for layout in layouts {
+        let name = layout["name"].as_str().unwrap_or("FancyZones");
+        let zones = match layout["type"].as_str() {
+            Some("canvas") => canvas_zones(&layout["info"]),
+            Some("grid") => grid_zones(&layout["info"]),
+            _ => None,
+        }
+        .unwrap_or_default();
+
+        positions.extend(
+            zones
+                .into_iter()
+                .enumerate()
+                .map(|(i, frame)| CustomPositionConfig {
+                    name: format!("{} {}", name, i + 1),
+                    frame,
+                }),
+        );
+    }
+
+    if positions.is_empty() {
+        return Err(tr("error.no_fancyzones_zones"));
+    }
+
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    config
+        .custom_positions
+        .retain(|c| !positions.iter().any(|p| p.name == c.name));
+
+    let count = positions.len();
+    config.custom_positions.extend(positions);
+    config.save().map_err(|e| e.to_string())?;
+
+    Ok(count)
+}
+
+/// Where to look for shortcuts to import: Spectacle's own shortcuts file, then
+/// an export from Rectangle's preferences left in Downloads.
+fn mac_shortcut_files() -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    if let Some(dir) = dirs::data_dir() {
+        files.push(dir.join("Spectacle").join("Shortcuts.json"));
+    }
+    if let Some(dir) = dirs::download_dir() {
+        files.push(dir.join("RectangleConfig.json"));
+    }
+    files
+}
+
+/// Import keyboard shortcuts from Rectangle's exported config or Spectacle's
+/// Shortcuts.json. Actions without an equivalent here, or left unbound there,
+/// keep their current shortcut. Returns how many shortcuts were imported.
+pub fn import_mac_shortcuts(path: Option<PathBuf>) -> Result<usize, String> {
+    let path = match path {
+        Some(path) => path,
+        None => mac_shortcut_files()
+            .into_iter()
+            .find(|p| p.exists())
+            .ok_or_else(|| tr("error.mac_shortcuts_not_found"))?,
+    };
+
+    let contents = fs::read_to_string(&path)
+        .map_err(|e| tr_args("error.read_file", &[&path.display(), &e]))?;
+    let json: Value = serde_json::from_str(&contents)
+        .map_err(|e| tr_args("error.parse_file", &[&path.display(), &e]))?;
+
+    // Spectacle stores a list of bindings, Rectangle an object of them
+    let bindings: Vec<(&str, String)> = if let Some(list) = json.as_array() {
+        list.iter()
+            .filter_map(|binding| {
+                let name = binding["shortcut_name"].as_str()?;
+                let shortcut = spectacle_shortcut(binding["shortcut_key_binding"].as_str()?)?;
+                Some((name, shortcut))
+            })
+            .collect()
+    } else if let Some(shortcuts) = json["shortcuts"].as_object() {
+        shortcuts
+            .iter()
+            .filter_map(|(name, binding)| {
+                let shortcut = rectangle_shortcut(
+                    binding["keyCode"].as_u64()?,
+                    binding["modifierFlags"].as_u64()?,
+                )?;
+                Some((name.as_str(), shortcut))
+            })
+            .collect()
+    } else {
+        return Err(tr("error.not_shortcuts_file"));
+    };
+
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    let mut count = 0;
+    for (name, shortcut) in bindings {
+        if let Some(field) = shortcut_field(&mut config.shortcuts, name) {
+            *field = shortcut;
+            count += 1;
+        }
+    }
+
+    if count == 0 {
+        return Err(tr("error.no_shortcuts_to_import"));
+    }
+
+    config.save().map_err(|e| e.to_string())?;
+    Ok(count)
+}
+
+/// The shortcut setting for a Rectangle or Spectacle action name.
+fn shortcut_field<'a>(shortcuts: &'a mut ShortcutConfig, name: &str) -> Option<&'a mut String> {
+    let field = match name {
+        "leftHalf" | "MoveToLeftHalf" => &mut shortcuts.left_half,
+        "rightHalf" | "MoveToRightHalf" => &mut shortcuts.right_half,
+        "topHalf" | "MoveToTopHalf" => &mut shortcuts.top_half,
+        "bottomHalf" | "MoveToBottomHalf" => &mut shortcuts.bottom_half,
+        "centerHalf" => &mut shortcuts.center_half,
+        "topLeft" | "MoveToUpperLeft" => &mut shortcuts.top_left,
+        "topRight" | "MoveToUpperRight" => &mut shortcuts.top_right,
+        "bottomLeft" | "MoveToLowerLeft" => &mut shortcuts.bottom_left,
+        "bottomRight" | "MoveToLowerRight" => &mut shortcuts.bottom_right,
+        "firstThird" => &mut shortcuts.left_third,
+        "centerThird" => &mut shortcuts.center_third,
+        "lastThird" => &mut shortcuts.right_third,
+        "firstTwoThirds" => &mut shortcuts.left_two_thirds,
+        "lastTwoThirds" => &mut shortcuts.right_two_thirds,
+        "firstFourth" => &mut shortcuts.first_fourth,
+        "secondFourth" => &mut shortcuts.second_fourth,
+        "thirdFourth" => &mut shortcuts.third_fourth,
+        "lastFourth" => &mut shortcuts.last_fourth,
+        "firstThreeFourths" => &mut shortcuts.first_three_fourths,
+        "lastThreeFourths" => &mut shortcuts.last_three_fourths,
+        "topLeftSixth" => &mut shortcuts.top_left_sixth,
+        "topCenterSixth" => &mut shortcuts.top_center_sixth,
+        "topRightSixth" => &mut shortcuts.top_right_sixth,
+        "bottomLeftSixth" => &mut shortcuts.bottom_left_sixth,
+        "bottomCenterSixth" => &mut shortcuts.bottom_center_sixth,
+        "bottomRightSixth" => &mut shortcuts.bottom_right_sixth,
+        "topLeftNinth" => &mut shortcuts.top_left_ninth,
+        "topCenterNinth" => &mut shortcuts.top_center_ninth,
+        "topRightNinth" => &mut shortcuts.top_right_ninth,
+        "middleLeftNinth" => &mut shortcuts.middle_left_ninth,
+        "middleCenterNinth" => &mut shortcuts.middle_center_ninth,
+        "middleRightNinth" => &mut shortcuts.middle_right_ninth,
+        "bottomLeftNinth" => &mut shortcuts.bottom_left_ninth,
+        "bottomCenterNinth" => &mut shortcuts.bottom_center_ninth,
+        "bottomRightNinth" => &mut shortcuts.bottom_right_ninth,
+        "center" | "MoveToCenter" => &mut shortcuts.center,
+        "maximize" | "MoveToFullscreen" => &mut shortcuts.maximize,
+        "maximizeHeight" => &mut shortcuts.maximize_height,
+        "larger" | "MakeLarger" => &mut shortcuts.grow,
+        "smaller" | "MakeSmaller" => &mut shortcuts.shrink,
+        "restore" | "UndoLastMove" => &mut shortcuts.restore,
+        "nextDisplay" | "MoveToNextDisplay" => &mut shortcuts.next_display,
+        "previousDisplay" | "MoveToPreviousDisplay" => &mut shortcuts.previous_display,
+        _ => return None,
+    };
+    Some(field)
+}
+
+/// Turn a Spectacle binding like "alt+cmd+left" into a shortcut.
+fn spectacle_shortcut(binding: &str) -> Option<String> {
+    let mut parts = Vec::new();
+    let mut key = None;
+    for token in binding.split('+') {
+        match token.to_lowercase().as_str() {
+            "ctrl" | "control" => parts.push("Control"),
+            "alt" | "option" => parts.push("Alt"),
+            "shift" => parts.push("Shift"),
+            "cmd" | "command" => parts.push("Super"),
+            other => key = Some(spectacle_key(other)?),
+        }
+    }
+
+    // A shortcut needs at least one modifier to be usable globally
+    if parts.is_empty() {
+        return None;
+    }
+
+    Some(format!("{}+{}", parts.join("+"), key?))
+}
+
+fn spectacle_key(key: &str) -> Option<String> {
+    let name = match key {
+        "left" | "right" | "up" | "down" | "space" | "tab" | "home" | "end" => {
+            let mut chars = key.chars();
+            let first = chars.next()?.to_ascii_uppercase();
+            format!("{}{}", first, chars.as_str())
+        }
+        "pageup" => "PageUp".into(),
+        "pagedown" => "PageDown".into(),
+        "return" | "enter" => "Enter".into(),
+        "escape" | "esc" => "Escape".into(),
+        "delete" => "Backspace".into(),
+        "-" => "Minus".into(),
+        "=" => "Equal".into(),
+        "[" => "BracketLeft".into(),
+        "]" => "BracketRight".into(),
+        "\\" => "Backslash".into(),
+        ";" => "Semicolon".into(),
+        "'" => "Quote".into(),
+        "," => "Comma".into(),
+        "." => "Period".into(),
+        "/" => "Slash".into(),
+        "`" => "Backquote".into(),
+        _ if key.len() == 1 && key.chars().all(|c| c.is_ascii_alphanumeric()) => key.to_uppercase(),
+        _ if key.starts_with('f') && key[1..].parse::<u8>().is_ok() => key.to_uppercase(),
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Turn a Rectangle binding, a macOS virtual key code plus NSEvent modifier
+/// flags, into a shortcut.
+fn rectangle_shortcut(key_code: u64, modifier_flags: u64) -> Option<String> {
+    const SHIFT: u64 = 1 << 17;
+    const CONTROL: u64 = 1 << 18;
+    const OPTION: u64 = 1 << 19;
+    const COMMAND: u64 = 1 << 20;
+
+    let mut parts = Vec::new();
+    for (flag, name) in [
+        (CONTROL, "Control"),
+        (OPTION, "Alt"),
+        (SHIFT, "Shift"),
+        (COMMAND, "Super"),
+    ] {
+        if modifier_flags & flag != 0 {
+            parts.push(name);
+        }
+    }
+
+    // A shortcut needs at least one modifier to be usable globally
+    if parts.is_empty() {
+        return None;
+    }
+
+    parts.push(mac_key_name(key_code)?);
+    Some(parts.join("+"))
+}
+
+/// Name of the key with the given macOS virtual key code (US layout).
+fn mac_key_name(key_code: u64) -> Option<&'static str> {
+    let name = match key_code {
+        0x00 => "A",
+        0x01 => "S",
+        0x02 => "D",
+        0x03 => "F",
+        0x04 => "H",
+        0x05 => "G",
+        0x06 => "Z",
+        0x07 => "X",
+        0x08 => "C",
+        0x09 => "V",
+        0x0B => "B",
+        0x0C => "Q",
+        0x0D => "W",
+        0x0E => "E",
+        0x0F => "R",
+        0x10 => "Y",
+        0x11 => "T",
+        0x12 => "1",
+        0x13 => "2",
+        0x14 => "3",
+        0x15 => "4",
+        0x16 => "6",
+        0x17 => "5",
+        0x18 => "Equal",
+        0x19 => "9",
+        0x1A => "7",
+        0x1B => "Minus",
+        0x1C => "8",
+        0x1D => "0",
+        0x1E => "BracketRight",
+        0x1F => "O",
+        0x20 => "U",
+        0x21 => "BracketLeft",
+        0x22 => "I",
+        0x23 => "P",
+        0x24 => "Enter",
+        0x25 => "L",
+        0x26 => "J",
+        0x27 => "Quote",
+        0x28 => "K",
+        0x29 => "Semicolon",
+        0x2A => "Backslash",
+        0x2B => "Comma",
+        0x2C => "Slash",
+        0x2D => "N",
+        0x2E => "M",
+        0x2F => "Period",
+        0x30 => "Tab",
+        0x31 => "Space",
+        0x32 => "Backquote",
+        0x33 => "Backspace",
+        0x35 => "Escape",
+        0x60 => "F5",
+        0x61 => "F6",
+        0x62 => "F7",
+        0x63 => "F3",
+        0x64 => "F8",
+        0x65 => "F9",
+        0x67 => "F11",
+        0x6D => "F10",
+        0x6F => "F12",
+        0x73 => "Home",
+        0x74 => "PageUp",
+        0x75 => "Delete",
+        0x76 => "F4",
+        0x77 => "End",
+        0x78 => "F2",
+        0x79 => "PageDown",
+        0x7A => "F1",
+        0x7B => "Left",
+        0x7C => "Right",
+        0x7D => "Down",
+        0x7E => "Up",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Zones of a canvas layout, which are pixel rects on a reference screen.
+fn canvas_zones(info: &Value) -> Option<Vec<CustomFrame>> {
+    let width = info["ref-width"].as_f64().filter(|w| *w > 0.0)?;
+    let height = info["ref-height"].as_f64().filter(|h| *h > 0.0)?;
+
+    info["zones"]
+        .as_array()?
+        .iter()
+        .map(|zone| {
+            Some(CustomFrame {
+                x: zone["X"].as_f64()? / width,
+                y: zone["Y"].as_f64()? / height,
+                width: zone["width"].as_f64()? / width,
+                height: zone["height"].as_f64()? / height,
+            })
+        })
+        .collect()
+}
+
+/// Zones of a grid layout. Rows and columns are sized in ten-thousandths, and
+/// the cell map gives the zone each cell belongs to; a zone covers every cell
+/// with its number.
+fn grid_zones(info: &Value) -> Option<Vec<CustomFrame>> {
+    let rows = grid_edges(&info["rows-percentage"])?;
+    let columns = grid_edges(&info["columns-percentage"])?;
+
+    // Cells spanned by each zone, as (top, left, bottom, right), inclusive
+    let mut spans: BTreeMap<u64, (usize, usize, usize, usize)> = BTreeMap::new();
+    for (row, cells) in info["cell-child-map"].as_array()?.iter().enumerate() {
+        for (column, zone) in cells.as_array()?.iter().enumerate() {
+            let span = spans
+                .entry(zone.as_u64()?)
+                .or_insert((row, column, row, column));
+            *span = (
+                span.0.min(row),
+                span.1.min(column),
+                span.2.max(row),
+                span.3.max(column),
+            );
+        }
+    }
+
+    spans
+        .values()
+        .map(|&(top, left, bottom, right)| {
+            // The cell map may have more cells than there are rows or columns
+            let x = *columns.get(left)?;
+            let y = *rows.get(top)?;
+            Some(CustomFrame {
+                x,
+                y,
+                width: columns.get(right + 1)? - x,
+                height: rows.get(bottom + 1)? - y,
+            })
+        })
+        .collect()
+}
+
+/// Turn grid row or column sizes into the fractions where each one starts,
+/// plus a final 1.0.
+fn grid_edges(sizes: &Value) -> Option<Vec<f64>> {
+    let sizes: Vec<f64> = sizes
+        .as_array()?
+        .iter()
+        .map(Value::as_f64)
+        .collect::<Option<_>>()?;
+    let total: f64 = sizes.iter().sum();
+    if total <= 0.0 {
+        return None;
+    }
+
+    let mut edges = vec![0.0];
+    let mut start = 0.0;
+    for size in sizes {
+        start += size / total;
+        edges.push(start);
+    }
+
+    Some(edges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn frame(x: f64, y: f64, width: f64, height: f64) -> CustomFrame {
+        CustomFrame {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn grid_zones_cover_spanned_cells() {
+        let info = json!({
+            "rows-percentage": [5000, 5000],
+            "columns-percentage": [2500, 7500],
+            "cell-child-map": [[0, 1], [0, 2]],
+        });
+
+        assert_eq!(
+            grid_zones(&info),
+            Some(vec![
+                frame(0.0, 0.0, 0.25, 1.0),
+                frame(0.25, 0.0, 0.75, 0.5),
+                frame(0.25, 0.5, 0.75, 0.5),
+            ])
+        );
+    }
+
+    #[test]
+    fn grid_zones_reject_cell_map_larger_than_grid() {
+        // One more column and one more row of cells than the grid has
+        let info = json!({
+            "rows-percentage": [10000],
+            "columns-percentage": [5000, 5000],
+            "cell-child-map": [[0, 1, 2], [3, 3, 3]],
+        });
+
+        assert_eq!(grid_zones(&info), None);
+    }
+}
@@ -0,0 +1,97 @@
+//! The taskbar jump list on Windows, with the common snap actions as tasks.
+//!
+//! Each task launches the app again with a `snaptowindow://` URL, which the
+//! single-instance plugin forwards to the running instance (see
+//! [`automation`](crate::automation)).
+
+use crate::i18n::tr;
+use windows::core::{Interface, Result, HSTRING, PROPVARIANT};
+use windows::Win32::Storage::EnhancedStorage::PKEY_Title;
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED,
+};
+use windows::Win32::UI::Shell::Common::{IObjectArray, IObjectCollection};
+use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
+use windows::Win32::UI::Shell::{
+    DestinationList, EnumerableObjectCollection, ICustomDestinationList, IShellLinkW, ShellLink,
+};
+
+// Tasks shown in the jump list, as (title key, URL). The titles are the
+// tray's, so both read the same in every language
+const TASKS: [(&str, &str); 6] = [
+    ("tray.left_half", "snaptowindow://snap/left_half"),
+    ("tray.right_half", "snaptowindow://snap/right_half"),
+    ("tray.maximize", "snaptowindow://snap/maximize"),
+    ("tray.center", "snaptowindow://snap/center"),
+    ("tray.next_display", "snaptowindow://next-display"),
+    ("tray.restore", "snaptowindow://restore"),
+];
+
+/// Replace the app's jump list with the snap tasks, titled in the current
+/// language.
+pub fn register_jump_list() -> Result<()> {
+    let exe = std::env::current_exe().map_err(|e| {
+        windows::core::Error::new(windows::Win32::Foundation::E_FAIL, e.to_string())
+    })?;
+    let exe = HSTRING::from(exe.as_os_str());
+
+    unsafe {
+        // Already done on Tauri's main thread
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+
+        let list: ICustomDestinationList =
+            CoCreateInstance(&DestinationList, None, CLSCTX_INPROC_SERVER)?;
+        let mut min_slots = 0;
+        let _removed: IObjectArray = list.BeginList(&mut min_slots)?;
+
+        let tasks: IObjectCollection =
+            CoCreateInstance(&EnumerableObjectCollection, None, CLSCTX_INPROC_SERVER)?;
+        for (key, url) in TASKS {
+            tasks.AddObject(&task_link(&exe, &tr(key), url)?)?;
+        }
+
+        list.AddUserTasks(&tasks.cast::<IObjectArray>()?)?;
+        list.CommitList()
+    }
+}
+
+/// Build a shell link that runs the app with `url`. Jump lists take their
+/// label from the link's title property rather than its description.
+fn task_link(exe: &HSTRING, title: &str, url: &str) -> Result<IShellLinkW> {
+    unsafe {
+        let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
+        link.SetPath(exe)?;
+        link.SetArguments(&HSTRING::from(url))?;
+        link.SetIconLocation(exe, 0)?;
+
+        let properties: IPropertyStore = link.cast()?;
+        properties.SetValue(&PKEY_Title, &PROPVARIANT::from(title))?;
+        properties.Commit()?;
+
+        Ok(link)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automation;
+    use tauri::Url;
+
+    #[test]
+    fn tasks_open_known_actions() {
+        for (key, url) in TASKS {
+            let url = Url::parse(url).unwrap();
+            if let Err(e) = automation::parse(&url) {
+                panic!("{} opens {}: {}", key, url, e);
+            }
+        }
+    }
+
+    #[test]
+    fn task_titles_are_translated() {
+        for (key, _) in TASKS {
+            assert_ne!(tr(key), key, "no English text for {}", key);
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! Putting windows back where they were snapped when the app starts, e.g.
+//! after a reboot, if `restore_layout_on_startup` is on.
+
+use crate::config::Config;
+use crate::window_manager::{WindowHandle, WindowManager};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+// How long after startup to keep restoring windows, as apps launched at login
+// open theirs
+const RESTORE_PERIOD: Duration = Duration::from_secs(120);
+
+// How often to look for new windows in that time
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Restore the saved layout for windows open now and those opened shortly after.
+pub fn start_layout_restore(app: AppHandle) {
+    if !Config::load().is_ok_and(|c| c.restore_layout_on_startup) {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        let started = Instant::now();
+        let done: Arc<Mutex<HashSet<WindowHandle>>> = Arc::default();
+
+        while started.elapsed() < RESTORE_PERIOD {
+            // Windows must be moved on the main thread on macOS
+            let done = done.clone();
+            app.run_on_main_thread(move || {
+                let manager = WindowManager::new();
+                if let Err(e) = manager.restore_layout(&mut done.lock().unwrap()) {
+                    eprintln!("Failed to restore window layout: {}", e);
+                }
+            })
+            .ok();
+
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
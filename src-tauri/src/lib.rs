@@ -1,56 +1,157 @@
 #[cfg(target_os = "macos")]
 extern crate objc;
 
+mod app_rules;
+mod automation;
+mod autostart;
 mod commands;
 mod config;
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+mod drag;
+#[cfg(target_os = "linux")]
+mod dbus;
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+mod double_tap;
+mod focus;
 mod hotkeys;
+mod i18n;
+mod import;
+mod reload;
+mod settings_file;
+#[cfg(target_os = "windows")]
+mod jump_list;
+mod layout_restore;
+mod notify;
+mod overlay;
+mod passthrough;
 mod tray;
+mod validate;
 mod window_manager;
 
-use tauri_plugin_autostart::{MacosLauncher, ManagerExt};
+use tauri_plugin_deep_link::DeepLinkExt;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // Launching at login should use the same config as this launch
+    let mut autostart_args = vec!["--minimized".to_string()];
+    if let Some(dir) = config::config_dir_flag() {
+        autostart_args.push("--config-dir".to_string());
+        autostart_args.push(dir.to_string_lossy().into_owned());
+    }
+
     tauri::Builder::default()
+        // Must come first: on Windows and Linux, opening a snaptowindow:// URL
+        // starts a second instance, which forwards the URL here and exits
+        .plugin(tauri_plugin_single_instance::init(|_app, _argv, _cwd| {}))
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
-        .plugin(tauri_plugin_autostart::init(
-            MacosLauncher::LaunchAgent,
-            Some(vec!["--minimized"]),
-        ))
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_notification::init())
+        // The builder launches via a LaunchAgent on macOS by default
+        .plugin(
+            tauri_plugin_autostart::Builder::new()
+                .args(autostart_args)
+                .build(),
+        )
         .setup(|app| {
+            // Pick the language for the tray and messages before building either
+            let language = config::Config::load().ok().and_then(|c| c.language);
+            i18n::set_language(language.as_deref());
+
+            // Let failures show up as notifications
+            notify::init(app.handle().clone());
+
+            // Let snaps be confirmed on screen
+            overlay::init(app.handle().clone());
+
+            // Ask for accessibility access up front on first run (no-op once granted)
+            tray::request_accessibility();
+
             // Initialize the system tray
             tray::setup_tray(app.handle())?;
 
             // Start watching for Windows theme changes
             tray::start_theme_watcher(app.handle().clone());
 
+            // Keep the tray's display list in sync with connected displays
+            tray::start_display_watcher(app.handle().clone());
+
             // Register global hotkeys
             hotkeys::register_hotkeys(app.handle())?;
 
+            // Pick up edits to the config file without a relaunch
+            reload::start_config_watcher(app.handle().clone());
+
+            // Let apps keep their own shortcuts while they are frontmost
+            passthrough::start_passthrough_watcher(app.handle().clone());
+
+            // Put apps' windows in their default positions when first focused
+            app_rules::start_app_rules_watcher(app.handle().clone());
+
+            // Remember which windows were focused, for mirroring the previous one
+            focus::start_focus_watcher();
+
+            // Put windows back where they were before the app last quit
+            layout_restore::start_layout_restore(app.handle().clone());
+
+            // Alt-drag (or another modifier) to move and resize windows. Linux
+            // window managers already do this themselves
+            #[cfg(any(target_os = "macos", target_os = "windows"))]
+            drag::start_modifier_drag();
+
+            // Double-tap modifier triggers, which global shortcuts can't express
+            #[cfg(any(target_os = "macos", target_os = "windows"))]
+            double_tap::start_double_tap_listener(app.handle().clone());
+
+            // Handle snaptowindow:// URLs from Shortcuts, AppleScript, etc.
+            // Windows and Linux only know about the scheme once it is registered
+            #[cfg(any(target_os = "windows", target_os = "linux"))]
+            app.deep_link().register_all()?;
+            app.deep_link().on_open_url(|event| {
+                for url in event.urls() {
+                    automation::handle_url(&url);
+                }
+            });
+
+            // A URL the app was started with, e.g. from a jump list task while
+            // it wasn't running yet
+            if let Ok(Some(urls)) = app.deep_link().get_current() {
+                for url in urls {
+                    automation::handle_url(&url);
+                }
+            }
+
+            // Snap actions in the taskbar icon's jump list
+            #[cfg(target_os = "windows")]
+            if let Err(e) = jump_list::register_jump_list() {
+                eprintln!("Failed to register jump list: {}", e);
+            }
+
+            // Let other tools drive the app over D-Bus
+            #[cfg(target_os = "linux")]
+            dbus::start_control_service();
+
             // Sync autostart state with config
             if let Ok(config) = config::Config::load() {
-                let autostart_manager = app.autolaunch();
-                if config.launch_at_login {
-                    let _ = autostart_manager.enable();
-                } else {
-                    let _ = autostart_manager.disable();
-                }
+                let _ = autostart::set_enabled(app.handle(), config.launch_at_login);
             }
 
-            // Check for updates on startup (with delay) and periodically
+            // Check for updates on startup (with delay) and periodically,
+            // unless turned off. The config is read each time, so changes
+            // apply from the next check
             let app_handle = app.handle().clone();
             std::thread::spawn(move || {
                 // Small delay to let the app fully initialize
                 std::thread::sleep(std::time::Duration::from_secs(2));
-                tauri::async_runtime::block_on(tray::check_for_updates_startup(&app_handle));
 
-                // Check for updates every hour
                 loop {
-                    std::thread::sleep(std::time::Duration::from_secs(60 * 60));
-                    tauri::async_runtime::block_on(tray::check_for_updates_startup(&app_handle));
+                    let config = config::Config::load().unwrap_or_default();
+                    if config.auto_update_check {
+                        tauri::async_runtime::block_on(tray::check_for_updates_startup(&app_handle));
+                    }
+                    std::thread::sleep(config.update_check_interval());
                 }
             });
 
@@ -65,14 +166,53 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             commands::snap_window,
+            commands::snap_to_display,
+            commands::snap_window_to_preset,
+            commands::resize_window,
+            commands::fill_window,
+            commands::mirror_previous_window,
+            commands::cascade_app_windows,
+            commands::get_app_window_titles,
+            commands::snap_app_window,
+            commands::snap_all_app_windows,
+            commands::get_visible_windows,
+            commands::snap_window_by_handle,
+            commands::snap_visible_window,
+            commands::import_fancyzones_layouts,
+            commands::import_mac_shortcuts,
+            commands::export_settings,
+            commands::import_settings,
+            commands::restore_window,
+            commands::toggle_ignore_window,
+            commands::apply_app_rules,
             commands::move_window_to_display,
+            commands::move_window_to_display_number,
+            commands::throw_window_to_display,
+            commands::span_displays,
+            commands::swap_displays,
+            commands::get_all_displays,
+            commands::get_display_fingerprints,
             commands::get_config,
             commands::save_config,
+            commands::validate_config,
+            commands::set_shortcut_profile,
             commands::check_accessibility,
+            commands::request_accessibility,
             commands::open_accessibility_settings,
             commands::refresh_tray,
+            commands::reload_hotkeys,
             commands::set_update_available,
+            commands::check_for_update,
+            commands::install_update,
+            commands::skip_update,
+            commands::set_update_channel,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|_app, event| {
+            // Snaps only remember their display in memory until now
+            if let tauri::RunEvent::Exit = event {
+                window_manager::WindowManager::save_display_affinity();
+            }
+        });
 }
@@ -0,0 +1,30 @@
+//! Desktop notifications for failures that would otherwise only reach stderr,
+//! e.g. a shortcut pressed with no window focused.
+
+use std::sync::OnceLock;
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Remember the app handle, so notifications can be shown from anywhere.
+pub fn init(app: AppHandle) {
+    APP.set(app).ok();
+}
+
+/// Show an error as a desktop notification.
+pub fn show_error(message: &str) {
+    let Some(app) = APP.get() else {
+        return;
+    };
+
+    let result = app
+        .notification()
+        .builder()
+        .title("SnapToWindow")
+        .body(message)
+        .show();
+    if let Err(e) = result {
+        eprintln!("Failed to show notification: {}", e);
+    }
+}
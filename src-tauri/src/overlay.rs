@@ -0,0 +1,119 @@
+//! The on-screen confirmation (HUD) shown briefly after a window is snapped,
+//! set up by the `osd` section of the config. It is a small undecorated window
+//! that never takes focus, so the next shortcut still acts on the snapped window.
+
+use crate::config::{Config, OsdConfig, OsdPosition};
+use crate::i18n::tr;
+use crate::window_manager::{Display, SnapPosition};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use tauri::{
+    AppHandle, Emitter, LogicalPosition, LogicalSize, Manager, PhysicalPosition, Position,
+    WebviewUrl, WebviewWindowBuilder,
+};
+
+// Label of the HUD window
+const OSD_LABEL: &str = "osd";
+
+// Logical pixels between the HUD and the top or bottom of the work area
+const EDGE_OFFSET: f64 = 48.0;
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+// Counts HUDs shown, so an earlier one's timer doesn't hide a later one
+static SHOWN: AtomicU64 = AtomicU64::new(0);
+
+/// Remember the app handle, so the HUD can be shown from anywhere.
+pub fn init(app: AppHandle) {
+    APP.set(app).ok();
+}
+
+/// Show the HUD for a window snapped to `position` on `display`, if it is
+/// turned on. Custom frames have no name to show, so they get none.
+pub fn show_snap(position: SnapPosition, display: &Display) {
+    let Some(app) = APP.get() else {
+        return;
+    };
+    let osd = Config::load().map(|c| c.osd).unwrap_or_default();
+    if !osd.enabled {
+        return;
+    }
+    let Ok(serde_json::Value::String(name)) = serde_json::to_value(position) else {
+        return;
+    };
+
+    // Tray labels already name every position
+    let text = tr(&format!("tray.{}", name));
+    let app = app.clone();
+    let display = display.clone();
+    let shown = SHOWN.fetch_add(1, Ordering::SeqCst) + 1;
+
+    // Creating a window from the main thread deadlocks on Windows, and the
+    // HUD has to be hidden later anyway
+    std::thread::spawn(move || {
+        if let Err(e) = show(&app, &text, &osd, &display) {
+            eprintln!("Failed to show snap HUD: {}", e);
+            return;
+        }
+
+        std::thread::sleep(osd.duration());
+        if SHOWN.load(Ordering::SeqCst) == shown {
+            if let Some(window) = app.get_webview_window(OSD_LABEL) {
+                window.hide().ok();
+            }
+        }
+    });
+}
+
+fn show(app: &AppHandle, text: &str, osd: &OsdConfig, display: &Display) -> tauri::Result<()> {
+    let (width, height) = osd.size.dimensions();
+
+    // Display coordinates are points on macOS and physical pixels elsewhere
+    let scale = if cfg!(target_os = "macos") {
+        1.0
+    } else {
+        display.scale_factor
+    };
+    let area = display.work_area;
+    let x = area.x as f64 + (area.width as f64 - width * scale) / 2.0;
+    let y = match osd.position {
+        OsdPosition::Top => area.y as f64 + EDGE_OFFSET * scale,
+        OsdPosition::Center => area.y as f64 + (area.height as f64 - height * scale) / 2.0,
+        OsdPosition::Bottom => {
+            area.y as f64 + area.height as f64 - (height + EDGE_OFFSET) * scale
+        }
+    };
+    let position: Position = if cfg!(target_os = "macos") {
+        LogicalPosition::new(x, y).into()
+    } else {
+        PhysicalPosition::new(x, y).into()
+    };
+
+    let state = serde_json::json!({ "text": text, "size": osd.size });
+
+    // Reuse the window from an earlier snap
+    let window = match app.get_webview_window(OSD_LABEL) {
+        Some(window) => {
+            window.emit_to(OSD_LABEL, "osd", &state)?;
+            window
+        }
+        None => WebviewWindowBuilder::new(app, OSD_LABEL, WebviewUrl::App("index.html".into()))
+            .initialization_script(format!("window.__SNAPTOWINDOW_OSD__ = {};", state))
+            .title("SnapToWindow")
+            .decorations(false)
+            .resizable(false)
+            .always_on_top(true)
+            .visible_on_all_workspaces(true)
+            .skip_taskbar(true)
+            .shadow(false)
+            .focused(false)
+            .focusable(false)
+            .visible(false)
+            .build()?,
+    };
+
+    window.set_size(LogicalSize::new(width, height))?;
+    window.set_position(position)?;
+    window.set_ignore_cursor_events(true)?;
+    window.show()
+}
@@ -0,0 +1,67 @@
+//! Letting apps keep shortcuts for themselves: while one of the apps in
+//! `shortcut_passthrough` is frontmost, the shortcuts listed for it are
+//! unregistered, so the key presses reach the app instead of snapping.
+
+use crate::config::{Config, ShortcutPassthrough};
+use crate::hotkeys;
+use crate::window_manager::WindowManager;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::AppHandle;
+
+// How often to check which app is frontmost
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+// Passthrough rules from the config, kept here so polling doesn't re-read the file
+static RULES: Mutex<Vec<ShortcutPassthrough>> = Mutex::new(Vec::new());
+
+// Passthrough rules for the frontmost app
+static PASSED_THROUGH: Mutex<Vec<ShortcutPassthrough>> = Mutex::new(Vec::new());
+
+/// Pick up the passthrough rules from a freshly loaded config.
+pub fn reload_rules(config: &Config) {
+    *RULES.lock().unwrap() = config.shortcut_passthrough.clone();
+}
+
+/// The passthrough rules that apply to the frontmost app.
+pub fn passed_through() -> Vec<ShortcutPassthrough> {
+    PASSED_THROUGH.lock().unwrap().clone()
+}
+
+/// Watch the frontmost app and re-register hotkeys whenever the set of
+/// shortcuts it keeps for itself changes.
+pub fn start_passthrough_watcher(app: AppHandle) {
+    reload_rules(&Config::load().unwrap_or_default());
+
+    std::thread::spawn(move || loop {
+        std::thread::sleep(POLL_INTERVAL);
+
+        let rules = RULES.lock().unwrap().clone();
+        let frontmost = if rules.is_empty() {
+            None
+        } else {
+            WindowManager::frontmost_window().ok().map(|w| w.app)
+        };
+        let matching: Vec<_> = match frontmost {
+            Some(frontmost) => rules.into_iter().filter(|r| r.matches(&frontmost)).collect(),
+            None => Vec::new(),
+        };
+
+        {
+            let mut current = PASSED_THROUGH.lock().unwrap();
+            if *current == matching {
+                continue;
+            }
+            *current = matching;
+        }
+
+        // Hotkeys must be changed on the main thread
+        let app_clone = app.clone();
+        app.run_on_main_thread(move || {
+            if let Err(e) = hotkeys::reregister_hotkeys(&app_clone) {
+                eprintln!("Failed to register hotkeys: {}", e);
+            }
+        })
+        .ok();
+    });
+}
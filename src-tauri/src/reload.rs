@@ -0,0 +1,71 @@
+//! Applying config changes while the app is running, so edits to the config file
+//! take effect without a relaunch.
+
+use crate::app_rules;
+use crate::autostart;
+use crate::config::Config;
+use crate::hotkeys;
+use crate::i18n::{self, tr_args};
+#[cfg(target_os = "windows")]
+use crate::jump_list;
+use crate::passthrough;
+use crate::tray;
+use std::fs;
+use std::time::{Duration, SystemTime};
+use tauri::AppHandle;
+
+// How often to check the config file for changes
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Switch language, refresh cached rules, re-register hotkeys, rebuild the tray
+/// and jump list and sync autostart from the config on disk. Most other
+/// settings are read fresh for every action anyway.
+pub fn apply_config(app: &AppHandle) -> Result<(), String> {
+    // Keep the current hotkeys if the file doesn't parse, e.g. while it is
+    // half edited
+    let config = Config::load().map_err(|e| tr_args("error.invalid_config", &[&e]))?;
+
+    i18n::set_language(config.language.as_deref());
+    passthrough::reload_rules(&config);
+    app_rules::reload_rules(&config);
+
+    hotkeys::reregister_hotkeys(app).map_err(|e| tr_args("error.register_hotkeys", &[&e]))?;
+
+    tray::rebuild_tray(app).map_err(|e| e.to_string())?;
+
+    // The jump list's titles follow the language
+    #[cfg(target_os = "windows")]
+    if let Err(e) = jump_list::register_jump_list() {
+        eprintln!("Failed to register jump list: {}", e);
+    }
+
+    autostart::set_enabled(app, config.launch_at_login)
+}
+
+/// Watch the config file and apply it whenever it changes.
+pub fn start_config_watcher(app: AppHandle) {
+    std::thread::spawn(move || {
+        let modified =
+            || -> Option<SystemTime> { fs::metadata(Config::config_path()).ok()?.modified().ok() };
+        let mut last_modified = modified();
+
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+
+            let current = modified();
+            if current == last_modified {
+                continue;
+            }
+            last_modified = current;
+
+            // The tray and hotkeys must be changed on the main thread
+            let app_clone = app.clone();
+            app.run_on_main_thread(move || {
+                if let Err(e) = apply_config(&app_clone) {
+                    eprintln!("Failed to reload config: {}", e);
+                }
+            })
+            .ok();
+        }
+    });
+}
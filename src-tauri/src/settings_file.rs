@@ -0,0 +1,58 @@
+//! Exporting every setting to a single file and importing it again, to move a
+//! setup to another machine or share it with others. Custom positions,
+//! imported layouts, presets and shortcut profiles are all part of the config,
+//! so the file is the config plus the version that wrote it.
+
+use crate::config::Config;
+use crate::i18n::tr_args;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Serialize, Deserialize)]
+struct SettingsFile {
+    /// App version that exported the settings.
+    version: String,
+    config: Config,
+}
+
+/// Where settings are exported to, and imported from, when no path is given.
+fn default_path() -> PathBuf {
+    dirs::download_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("SnapToWindow Settings.json")
+}
+
+/// Write the current settings to `path`, or to Downloads. Returns the path
+/// written to.
+pub fn export_settings(path: Option<PathBuf>) -> Result<PathBuf, String> {
+    let path = path.unwrap_or_else(default_path);
+    let file = SettingsFile {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        config: Config::load().map_err(|e| e.to_string())?,
+    };
+
+    let contents = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+    fs::write(&path, contents).map_err(|e| tr_args("error.write_file", &[&path.display(), &e]))?;
+    Ok(path)
+}
+
+/// Replace the current settings with ones exported earlier. Whether to launch
+/// at login and which format the config file is kept in stay as they are on
+/// this machine.
+pub fn import_settings(path: Option<PathBuf>) -> Result<(), String> {
+    let path = path.unwrap_or_else(default_path);
+    let contents = fs::read_to_string(&path)
+        .map_err(|e| tr_args("error.read_file", &[&path.display(), &e]))?;
+    let file: SettingsFile = serde_json::from_str(&contents)
+        .map_err(|e| tr_args("error.parse_file", &[&path.display(), &e]))?;
+
+    let current = Config::load().map_err(|e| e.to_string())?;
+    let config = Config {
+        launch_at_login: current.launch_at_login,
+        config_format: current.config_format,
+        ..file.config
+    };
+
+    config.save().map_err(|e| e.to_string())
+}
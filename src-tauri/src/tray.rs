@@ -1,24 +1,49 @@
-use crate::config::Config;
-use crate::window_manager::{DisplayDirection, SnapPosition, WindowManager};
+use crate::autostart;
+use crate::config::{Config, UpdateChannel};
+use crate::hotkeys;
+use crate::i18n::{tr, tr_args};
+use crate::reload;
+use crate::window_manager::{
+    DisplayDirection, Rect, ResizeAction, SnapPosition, ThrowDirection, WindowManager,
+};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
 use tauri::{
     image::Image,
-    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem},
+    menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::TrayIconBuilder,
-    AppHandle, Emitter, Manager,
+    AppHandle, Emitter, Manager, Wry,
 };
-use tauri_plugin_autostart::ManagerExt;
-use tauri_plugin_updater::UpdaterExt;
+use tauri_plugin_updater::{Updater, UpdaterExt};
 
 const TRAY_ID: &str = "main-tray";
 
 // Track last known accessibility state
 static LAST_ACCESSIBILITY_STATE: AtomicBool = AtomicBool::new(false);
 
+// Displays the tray menu was last built with, to detect display changes
+static LAST_DISPLAYS: Mutex<Vec<(String, Rect)>> = Mutex::new(Vec::new());
+
+// Work areas last seen by the display watcher, to notice a taskbar or dock
+// that moved, resized or appeared on another display
+static LAST_WORK_AREAS: Mutex<Vec<Rect>> = Mutex::new(Vec::new());
+
+// Name of the display profile active when the displays last changed
+static LAST_PROFILE: Mutex<Option<String>> = Mutex::new(None);
+
+// Whether the main window has been shown as a stand-in for a missing tray
+#[cfg(target_os = "linux")]
+static FALLBACK_MENU_SHOWN: AtomicBool = AtomicBool::new(false);
+
 // Track update availability
 static UPDATE_AVAILABLE: AtomicBool = AtomicBool::new(false);
 static UPDATE_VERSION: Mutex<Option<String>> = Mutex::new(None);
+// Update manifest for the beta channel. The stable channel uses the endpoint
+// in tauri.conf.json
+const BETA_UPDATE_ENDPOINT: &str =
+    "https://github.com/ctmakes/SnapToWindow/releases/download/beta/latest.json";
+// Update downloaded in the background, as (version, bytes)
+static DOWNLOADED_UPDATE: Mutex<Option<(String, Vec<u8>)>> = Mutex::new(None);
 
 #[cfg(target_os = "macos")]
 fn check_accessibility() -> bool {
@@ -78,27 +103,48 @@ fn is_windows_dark_mode() -> bool {
     false
 }
 
-fn open_accessibility_settings() {
-    #[cfg(target_os = "macos")]
-    {
-        std::process::Command::new("open")
-            .arg("x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility")
-            .spawn()
-            .ok();
+/// Show the system dialog asking the user to grant accessibility access, which
+/// links straight to the right pane of System Settings. Returns whether access
+/// is already granted (in which case no dialog is shown).
+#[cfg(target_os = "macos")]
+pub fn request_accessibility() -> bool {
+    use core_foundation::base::TCFType;
+    use core_foundation::boolean::CFBoolean;
+    use core_foundation::dictionary::{CFDictionary, CFDictionaryRef};
+    use core_foundation::string::{CFString, CFStringRef};
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    unsafe extern "C" {
+        static kAXTrustedCheckOptionPrompt: CFStringRef;
+        fn AXIsProcessTrustedWithOptions(options: CFDictionaryRef) -> bool;
+    }
+
+    unsafe {
+        let prompt = CFString::wrap_under_get_rule(kAXTrustedCheckOptionPrompt);
+        let options = CFDictionary::from_CFType_pairs(&[(prompt, CFBoolean::true_value())]);
+        AXIsProcessTrustedWithOptions(options.as_concrete_TypeRef())
     }
 }
 
+#[cfg(not(target_os = "macos"))]
+pub fn request_accessibility() -> bool {
+    true
+}
+
 pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     let accessibility_enabled = check_accessibility();
     LAST_ACCESSIBILITY_STATE.store(accessibility_enabled, Ordering::SeqCst);
     let update_available = UPDATE_AVAILABLE.load(Ordering::SeqCst);
     let update_version = UPDATE_VERSION.lock().unwrap().clone();
 
-    // Update item (only shown if update available)
-    let update_label = if let Some(v) = &update_version {
-        format!("⬆️ Install Update (v{})", v)
-    } else {
-        "⬆️ Install Update".to_string()
+    // Update item (only shown if update available), naming the channel
+    // unless it is stable
+    let beta = Config::load().is_ok_and(|c| c.update_channel == UpdateChannel::Beta);
+    let update_label = match (&update_version, beta) {
+        (Some(v), false) => tr_args("tray.install_update_version", &[v]),
+        (Some(v), true) => tr_args("tray.install_beta_update_version", &[v]),
+        (None, false) => tr("tray.install_update"),
+        (None, true) => tr("tray.install_beta_update"),
     };
     let install_update = MenuItem::with_id(
         app,
@@ -107,13 +153,14 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
         true,
         None::<&str>,
     )?;
+    let skip_update = MenuItem::with_id(app, "skip_update", tr("tray.skip_update"), true, None::<&str>)?;
     let update_sep = PredefinedMenuItem::separator(app)?;
 
     // Warning item (only shown if accessibility not enabled)
     let warning = MenuItem::with_id(
         app,
         "accessibility_warning",
-        "⚠️ Accessibility Required",
+        tr("tray.accessibility_warning"),
         true,
         None::<&str>,
     )?;
@@ -123,28 +170,28 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     let left_half = MenuItem::with_id(
         app,
         "left_half",
-        "Left Half",
+        tr("tray.left_half"),
         accessibility_enabled,
         Some("ctrl+alt+left"),
     )?;
     let right_half = MenuItem::with_id(
         app,
         "right_half",
-        "Right Half",
+        tr("tray.right_half"),
         accessibility_enabled,
         Some("ctrl+alt+right"),
     )?;
     let top_half = MenuItem::with_id(
         app,
         "top_half",
-        "Top Half",
+        tr("tray.top_half"),
         accessibility_enabled,
         Some("ctrl+alt+up"),
     )?;
     let bottom_half = MenuItem::with_id(
         app,
         "bottom_half",
-        "Bottom Half",
+        tr("tray.bottom_half"),
         accessibility_enabled,
         Some("ctrl+alt+down"),
     )?;
@@ -153,28 +200,28 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     let top_left = MenuItem::with_id(
         app,
         "top_left",
-        "Top Left",
+        tr("tray.top_left"),
         accessibility_enabled,
         Some("ctrl+alt+u"),
     )?;
     let top_right = MenuItem::with_id(
         app,
         "top_right",
-        "Top Right",
+        tr("tray.top_right"),
         accessibility_enabled,
         Some("ctrl+alt+i"),
     )?;
     let bottom_left = MenuItem::with_id(
         app,
         "bottom_left",
-        "Bottom Left",
+        tr("tray.bottom_left"),
         accessibility_enabled,
         Some("ctrl+alt+j"),
     )?;
     let bottom_right = MenuItem::with_id(
         app,
         "bottom_right",
-        "Bottom Right",
+        tr("tray.bottom_right"),
         accessibility_enabled,
         Some("ctrl+alt+k"),
     )?;
@@ -183,70 +230,645 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     let left_third = MenuItem::with_id(
         app,
         "left_third",
-        "Left Third",
+        tr("tray.left_third"),
         accessibility_enabled,
         Some("ctrl+alt+d"),
     )?;
     let center_third = MenuItem::with_id(
         app,
         "center_third",
-        "Center Third",
+        tr("tray.center_third"),
         accessibility_enabled,
         Some("ctrl+alt+f"),
     )?;
     let right_third = MenuItem::with_id(
         app,
         "right_third",
-        "Right Third",
+        tr("tray.right_third"),
         accessibility_enabled,
         Some("ctrl+alt+g"),
     )?;
     let left_two_thirds = MenuItem::with_id(
         app,
         "left_two_thirds",
-        "Left Two Thirds",
+        tr("tray.left_two_thirds"),
         accessibility_enabled,
         Some("ctrl+alt+e"),
     )?;
     let right_two_thirds = MenuItem::with_id(
         app,
         "right_two_thirds",
-        "Right Two Thirds",
+        tr("tray.right_two_thirds"),
         accessibility_enabled,
         Some("ctrl+alt+r"),
     )?;
 
+    // Horizontal thirds
+    let top_third = MenuItem::with_id(
+        app,
+        "top_third",
+        tr("tray.top_third"),
+        accessibility_enabled,
+        Some("ctrl+alt+shift+d"),
+    )?;
+    let middle_third = MenuItem::with_id(
+        app,
+        "middle_third",
+        tr("tray.middle_third"),
+        accessibility_enabled,
+        Some("ctrl+alt+shift+f"),
+    )?;
+    let bottom_third = MenuItem::with_id(
+        app,
+        "bottom_third",
+        tr("tray.bottom_third"),
+        accessibility_enabled,
+        Some("ctrl+alt+shift+g"),
+    )?;
+    let top_two_thirds = MenuItem::with_id(
+        app,
+        "top_two_thirds",
+        tr("tray.top_two_thirds"),
+        accessibility_enabled,
+        Some("ctrl+alt+shift+e"),
+    )?;
+    let bottom_two_thirds = MenuItem::with_id(
+        app,
+        "bottom_two_thirds",
+        tr("tray.bottom_two_thirds"),
+        accessibility_enabled,
+        Some("ctrl+alt+shift+r"),
+    )?;
+    let horizontal_thirds = Submenu::with_items(
+        app,
+        tr("tray.horizontal_thirds"),
+        accessibility_enabled,
+        &[
+            &top_third,
+            &middle_third,
+            &bottom_third,
+            &top_two_thirds,
+            &bottom_two_thirds,
+        ],
+    )?;
+
+    // Fourths
+    let first_fourth = MenuItem::with_id(
+        app,
+        "first_fourth",
+        tr("tray.first_fourth"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let second_fourth = MenuItem::with_id(
+        app,
+        "second_fourth",
+        tr("tray.second_fourth"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let third_fourth = MenuItem::with_id(
+        app,
+        "third_fourth",
+        tr("tray.third_fourth"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let last_fourth = MenuItem::with_id(
+        app,
+        "last_fourth",
+        tr("tray.last_fourth"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let first_three_fourths = MenuItem::with_id(
+        app,
+        "first_three_fourths",
+        tr("tray.first_three_fourths"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let last_three_fourths = MenuItem::with_id(
+        app,
+        "last_three_fourths",
+        tr("tray.last_three_fourths"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let fourths = Submenu::with_items(
+        app,
+        tr("tray.fourths"),
+        accessibility_enabled,
+        &[
+            &first_fourth,
+            &second_fourth,
+            &third_fourth,
+            &last_fourth,
+            &first_three_fourths,
+            &last_three_fourths,
+        ],
+    )?;
+
+    // Corner two-thirds
+    let top_left_two_thirds = MenuItem::with_id(
+        app,
+        "top_left_two_thirds",
+        tr("tray.top_left_two_thirds"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let top_right_two_thirds = MenuItem::with_id(
+        app,
+        "top_right_two_thirds",
+        tr("tray.top_right_two_thirds"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let bottom_left_two_thirds = MenuItem::with_id(
+        app,
+        "bottom_left_two_thirds",
+        tr("tray.bottom_left_two_thirds"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let bottom_right_two_thirds = MenuItem::with_id(
+        app,
+        "bottom_right_two_thirds",
+        tr("tray.bottom_right_two_thirds"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let corner_two_thirds = Submenu::with_items(
+        app,
+        tr("tray.corner_two_thirds"),
+        accessibility_enabled,
+        &[
+            &top_left_two_thirds,
+            &top_right_two_thirds,
+            &bottom_left_two_thirds,
+            &bottom_right_two_thirds,
+        ],
+    )?;
+
+    // Sixths
+    let top_left_sixth = MenuItem::with_id(
+        app,
+        "top_left_sixth",
+        tr("tray.top_left_sixth"),
+        accessibility_enabled,
+        Some("ctrl+alt+shift+u"),
+    )?;
+    let top_center_sixth = MenuItem::with_id(
+        app,
+        "top_center_sixth",
+        tr("tray.top_center_sixth"),
+        accessibility_enabled,
+        Some("ctrl+alt+shift+i"),
+    )?;
+    let top_right_sixth = MenuItem::with_id(
+        app,
+        "top_right_sixth",
+        tr("tray.top_right_sixth"),
+        accessibility_enabled,
+        Some("ctrl+alt+shift+o"),
+    )?;
+    let bottom_left_sixth = MenuItem::with_id(
+        app,
+        "bottom_left_sixth",
+        tr("tray.bottom_left_sixth"),
+        accessibility_enabled,
+        Some("ctrl+alt+shift+j"),
+    )?;
+    let bottom_center_sixth = MenuItem::with_id(
+        app,
+        "bottom_center_sixth",
+        tr("tray.bottom_center_sixth"),
+        accessibility_enabled,
+        Some("ctrl+alt+shift+k"),
+    )?;
+    let bottom_right_sixth = MenuItem::with_id(
+        app,
+        "bottom_right_sixth",
+        tr("tray.bottom_right_sixth"),
+        accessibility_enabled,
+        Some("ctrl+alt+shift+l"),
+    )?;
+    let sixths = Submenu::with_items(
+        app,
+        tr("tray.sixths"),
+        accessibility_enabled,
+        &[
+            &top_left_sixth,
+            &top_center_sixth,
+            &top_right_sixth,
+            &bottom_left_sixth,
+            &bottom_center_sixth,
+            &bottom_right_sixth,
+        ],
+    )?;
+
+    // Ninths
+    let top_left_ninth = MenuItem::with_id(
+        app,
+        "top_left_ninth",
+        tr("tray.top_left_ninth"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let top_center_ninth = MenuItem::with_id(
+        app,
+        "top_center_ninth",
+        tr("tray.top_center_ninth"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let top_right_ninth = MenuItem::with_id(
+        app,
+        "top_right_ninth",
+        tr("tray.top_right_ninth"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let middle_left_ninth = MenuItem::with_id(
+        app,
+        "middle_left_ninth",
+        tr("tray.middle_left_ninth"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let middle_center_ninth = MenuItem::with_id(
+        app,
+        "middle_center_ninth",
+        tr("tray.middle_center_ninth"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let middle_right_ninth = MenuItem::with_id(
+        app,
+        "middle_right_ninth",
+        tr("tray.middle_right_ninth"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let bottom_left_ninth = MenuItem::with_id(
+        app,
+        "bottom_left_ninth",
+        tr("tray.bottom_left_ninth"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let bottom_center_ninth = MenuItem::with_id(
+        app,
+        "bottom_center_ninth",
+        tr("tray.bottom_center_ninth"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let bottom_right_ninth = MenuItem::with_id(
+        app,
+        "bottom_right_ninth",
+        tr("tray.bottom_right_ninth"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let ninths = Submenu::with_items(
+        app,
+        tr("tray.ninths"),
+        accessibility_enabled,
+        &[
+            &top_left_ninth,
+            &top_center_ninth,
+            &top_right_ninth,
+            &middle_left_ninth,
+            &middle_center_ninth,
+            &middle_right_ninth,
+            &bottom_left_ninth,
+            &bottom_center_ninth,
+            &bottom_right_ninth,
+        ],
+    )?;
+
     // Other actions
     let maximize = MenuItem::with_id(
         app,
         "maximize",
-        "Maximize",
+        tr("tray.maximize"),
         accessibility_enabled,
         Some("ctrl+alt+enter"),
     )?;
+    let maximize_height = MenuItem::with_id(
+        app,
+        "maximize_height",
+        tr("tray.maximize_height"),
+        accessibility_enabled,
+        Some("ctrl+alt+shift+up"),
+    )?;
+    let maximize_width = MenuItem::with_id(
+        app,
+        "maximize_width",
+        tr("tray.maximize_width"),
+        accessibility_enabled,
+        Some("ctrl+alt+shift+right"),
+    )?;
     let center = MenuItem::with_id(
         app,
         "center",
-        "Center",
+        tr("tray.center"),
         accessibility_enabled,
         Some("ctrl+alt+c"),
     )?;
+    let center_half = MenuItem::with_id(
+        app,
+        "center_half",
+        tr("tray.center_half"),
+        accessibility_enabled,
+        Some("ctrl+alt+shift+c"),
+    )?;
+    let restore = MenuItem::with_id(
+        app,
+        "restore",
+        tr("tray.restore"),
+        accessibility_enabled,
+        Some("ctrl+alt+backspace"),
+    )?;
+    let ignore_window = MenuItem::with_id(
+        app,
+        "ignore_window",
+        tr("tray.ignore_window"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let has_app_rules = Config::load()
+        .map(WindowManager::with_display_profile)
+        .is_ok_and(|c| !c.app_rules.is_empty());
+    let apply_app_rules = MenuItem::with_id(
+        app,
+        "apply_app_rules",
+        tr("tray.apply_app_rules"),
+        accessibility_enabled && has_app_rules,
+        None::<&str>,
+    )?;
+    let fill = MenuItem::with_id(
+        app,
+        "fill",
+        tr("tray.fill"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let mirror_previous = MenuItem::with_id(
+        app,
+        "mirror_previous",
+        tr("tray.mirror_previous"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let cascade_app_windows = MenuItem::with_id(
+        app,
+        "cascade_app_windows",
+        tr("tray.cascade_app_windows"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
 
     // Display actions
     let next_display = MenuItem::with_id(
         app,
         "next_display",
-        "Next Display",
+        tr("tray.next_display"),
         accessibility_enabled,
         Some("ctrl+alt+]"),
     )?;
     let previous_display = MenuItem::with_id(
         app,
         "previous_display",
-        "Previous Display",
+        tr("tray.previous_display"),
         accessibility_enabled,
         Some("ctrl+alt+["),
     )?;
+    let span_displays = MenuItem::with_id(
+        app,
+        "span_displays",
+        tr("tray.span_displays"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let swap_displays = MenuItem::with_id(
+        app,
+        "swap_displays",
+        tr("tray.swap_displays"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+
+    let throw_left = MenuItem::with_id(
+        app,
+        "throw_left",
+        tr("tray.throw_left"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let throw_right = MenuItem::with_id(
+        app,
+        "throw_right",
+        tr("tray.throw_right"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let throw_up = MenuItem::with_id(
+        app,
+        "throw_up",
+        tr("tray.throw_up"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let throw_down = MenuItem::with_id(
+        app,
+        "throw_down",
+        tr("tray.throw_down"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let throw_menu = Submenu::with_items(
+        app,
+        tr("tray.throw_to_display"),
+        accessibility_enabled,
+        &[&throw_left, &throw_right, &throw_up, &throw_down],
+    )?;
+
+    // Resize
+    let grow = MenuItem::with_id(
+        app,
+        "grow",
+        tr("tray.grow"),
+        accessibility_enabled,
+        Some("ctrl+alt+="),
+    )?;
+    let shrink = MenuItem::with_id(
+        app,
+        "shrink",
+        tr("tray.shrink"),
+        accessibility_enabled,
+        Some("ctrl+alt+-"),
+    )?;
+    let grow_width = MenuItem::with_id(
+        app,
+        "grow_width",
+        tr("tray.grow_width"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let shrink_width = MenuItem::with_id(
+        app,
+        "shrink_width",
+        tr("tray.shrink_width"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let grow_height = MenuItem::with_id(
+        app,
+        "grow_height",
+        tr("tray.grow_height"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let shrink_height = MenuItem::with_id(
+        app,
+        "shrink_height",
+        tr("tray.shrink_height"),
+        accessibility_enabled,
+        None::<&str>,
+    )?;
+    let resize_menu = Submenu::with_items(
+        app,
+        tr("tray.resize"),
+        accessibility_enabled,
+        &[
+            &grow,
+            &shrink,
+            &grow_width,
+            &shrink_width,
+            &grow_height,
+            &shrink_height,
+        ],
+    )?;
+
+    // Move to Display submenu, listing every connected display
+    let displays = WindowManager::new().displays_by_number().unwrap_or_default();
+    *LAST_DISPLAYS.lock().unwrap() = displays
+        .iter()
+        .map(|(_, d)| (d.fingerprint(), d.bounds))
+        .collect();
+    // Items are identified by their place in the list, as the parts of a split
+    // display share its number
+    let display_items = displays
+        .iter()
+        .enumerate()
+        .map(|(i, (number, display))| {
+            MenuItem::with_id(
+                app,
+                format!("move_to_display_{}", i),
+                format!("{}. {}", number, display.name),
+                accessibility_enabled,
+                None::<&str>,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let display_refs: Vec<&dyn IsMenuItem<Wry>> = display_items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    let move_to_display_menu = Submenu::with_items(
+        app,
+        tr("tray.move_to_display"),
+        accessibility_enabled && !display_items.is_empty(),
+        &display_refs,
+    )?;
+
+    // Size presets from config
+    let presets = Config::load().map(|c| c.presets).unwrap_or_default();
+    let preset_items = presets
+        .iter()
+        .enumerate()
+        .map(|(i, preset)| {
+            MenuItem::with_id(
+                app,
+                format!("preset_{}", i),
+                &preset.name,
+                accessibility_enabled,
+                None::<&str>,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let preset_refs: Vec<&dyn IsMenuItem<Wry>> = preset_items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    let presets_menu = Submenu::with_items(
+        app,
+        tr("tray.presets"),
+        accessibility_enabled && !preset_items.is_empty(),
+        &preset_refs,
+    )?;
+
+    // Custom positions from config
+    let custom_positions = Config::load()
+        .map(|c| WindowManager::with_display_profile(c).custom_positions)
+        .unwrap_or_default();
+    let custom_items = custom_positions
+        .iter()
+        .enumerate()
+        .map(|(i, custom)| {
+            MenuItem::with_id(
+                app,
+                format!("custom_{}", i),
+                &custom.name,
+                accessibility_enabled,
+                None::<&str>,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let custom_refs: Vec<&dyn IsMenuItem<Wry>> = custom_items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    let custom_menu = Submenu::with_items(
+        app,
+        tr("tray.custom_positions"),
+        accessibility_enabled && !custom_items.is_empty(),
+        &custom_refs,
+    )?;
+
+    // Shortcut profiles from config, with the active one checked
+    let config = Config::load().unwrap_or_default();
+    let active_profile = config.active_shortcut_profile.as_deref();
+    let mut profile_items = vec![CheckMenuItem::with_id(
+        app,
+        "shortcut_profile_default",
+        tr("tray.shortcut_profile_default"),
+        true,
+        active_profile.is_none(),
+        None::<&str>,
+    )?];
+    for (i, profile) in config.shortcut_profiles.iter().enumerate() {
+        profile_items.push(CheckMenuItem::with_id(
+            app,
+            format!("shortcut_profile_{}", i),
+            &profile.name,
+            true,
+            active_profile == Some(profile.name.as_str()),
+            None::<&str>,
+        )?);
+    }
+    let profile_refs: Vec<&dyn IsMenuItem<Wry>> = profile_items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    let shortcut_profiles_menu = Submenu::with_items(
+        app,
+        tr("tray.shortcut_profile"),
+        !config.shortcut_profiles.is_empty(),
+        &profile_refs,
+    )?;
 
     // Separators
     let sep1 = PredefinedMenuItem::separator(app)?;
@@ -257,10 +879,15 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
 
     // Settings, Updates, and Quit
     let launch_at_login_enabled = Config::load().map(|c| c.launch_at_login).unwrap_or(false);
-    let launch_at_login = CheckMenuItem::with_id(app, "launch_at_login", "Launch at Login", true, launch_at_login_enabled, None::<&str>)?;
-    let settings = MenuItem::with_id(app, "settings", "Settings...", true, None::<&str>)?;
-    let check_updates = MenuItem::with_id(app, "check_updates", "Check for Updates...", true, None::<&str>)?;
-    let quit = MenuItem::with_id(app, "quit", "Quit SnapToWindow", true, None::<&str>)?;
+    let launch_at_login = CheckMenuItem::with_id(app, "launch_at_login", tr("tray.launch_at_login"), true, launch_at_login_enabled, None::<&str>)?;
+    let settings = MenuItem::with_id(app, "settings", tr("tray.settings"), true, None::<&str>)?;
+    let check_updates_label = if beta {
+        tr("tray.check_beta_updates")
+    } else {
+        tr("tray.check_updates")
+    };
+    let check_updates = MenuItem::with_id(app, "check_updates", &check_updates_label, true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "quit", tr("tray.quit"), true, None::<&str>)?;
 
     let menu = match (accessibility_enabled, update_available) {
         (true, true) => Menu::with_items(
@@ -268,6 +895,7 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
             &[
                 // Update at top
                 &install_update,
+                &skip_update,
                 &update_sep,
                 // Halves
                 &left_half,
@@ -287,16 +915,38 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
                 &right_third,
                 &left_two_thirds,
                 &right_two_thirds,
+                &horizontal_thirds,
+                &fourths,
+                &corner_two_thirds,
+                &sixths,
+                &ninths,
                 &sep3,
                 // Other
                 &maximize,
+                &maximize_height,
+                &maximize_width,
                 &center,
+                &center_half,
+                &fill,
+                &mirror_previous,
+                &cascade_app_windows,
+                &restore,
+                &ignore_window,
+                &apply_app_rules,
+                &resize_menu,
+                &presets_menu,
+                &custom_menu,
                 &sep4,
                 // Display
                 &next_display,
                 &previous_display,
+                &span_displays,
+                &swap_displays,
+                &throw_menu,
+                &move_to_display_menu,
                 &sep5,
                 // App controls
+                &shortcut_profiles_menu,
                 &launch_at_login,
                 &settings,
                 &quit,
@@ -323,16 +973,38 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
                 &right_third,
                 &left_two_thirds,
                 &right_two_thirds,
+                &horizontal_thirds,
+                &fourths,
+                &corner_two_thirds,
+                &sixths,
+                &ninths,
                 &sep3,
                 // Other
                 &maximize,
+                &maximize_height,
+                &maximize_width,
                 &center,
+                &center_half,
+                &fill,
+                &mirror_previous,
+                &cascade_app_windows,
+                &restore,
+                &ignore_window,
+                &apply_app_rules,
+                &resize_menu,
+                &presets_menu,
+                &custom_menu,
                 &sep4,
                 // Display
                 &next_display,
                 &previous_display,
+                &span_displays,
+                &swap_displays,
+                &throw_menu,
+                &move_to_display_menu,
                 &sep5,
                 // App controls
+                &shortcut_profiles_menu,
                 &launch_at_login,
                 &settings,
                 &check_updates,
@@ -344,6 +1016,7 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
             &[
                 // Update at top
                 &install_update,
+                &skip_update,
                 &update_sep,
                 // Warning
                 &warning,
@@ -366,16 +1039,38 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
                 &right_third,
                 &left_two_thirds,
                 &right_two_thirds,
+                &horizontal_thirds,
+                &fourths,
+                &corner_two_thirds,
+                &sixths,
+                &ninths,
                 &sep3,
                 // Other (disabled)
                 &maximize,
+                &maximize_height,
+                &maximize_width,
                 &center,
+                &center_half,
+                &fill,
+                &mirror_previous,
+                &cascade_app_windows,
+                &restore,
+                &ignore_window,
+                &apply_app_rules,
+                &resize_menu,
+                &presets_menu,
+                &custom_menu,
                 &sep4,
                 // Display (disabled)
                 &next_display,
                 &previous_display,
+                &span_displays,
+                &swap_displays,
+                &throw_menu,
+                &move_to_display_menu,
                 &sep5,
                 // App controls
+                &shortcut_profiles_menu,
                 &launch_at_login,
                 &settings,
                 &quit,
@@ -405,16 +1100,38 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
                 &right_third,
                 &left_two_thirds,
                 &right_two_thirds,
+                &horizontal_thirds,
+                &fourths,
+                &corner_two_thirds,
+                &sixths,
+                &ninths,
                 &sep3,
                 // Other (disabled)
                 &maximize,
+                &maximize_height,
+                &maximize_width,
                 &center,
+                &center_half,
+                &fill,
+                &mirror_previous,
+                &cascade_app_windows,
+                &restore,
+                &ignore_window,
+                &apply_app_rules,
+                &resize_menu,
+                &presets_menu,
+                &custom_menu,
                 &sep4,
                 // Display (disabled)
                 &next_display,
                 &previous_display,
+                &span_displays,
+                &swap_displays,
+                &throw_menu,
+                &move_to_display_menu,
                 &sep5,
                 // App controls
+                &shortcut_profiles_menu,
                 &launch_at_login,
                 &settings,
                 &check_updates,
@@ -424,10 +1141,10 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     };
 
     let tooltip = match (accessibility_enabled, update_available) {
-        (true, true) => "SnapToWindow - ⬆️ Update Available",
-        (true, false) => "SnapToWindow",
-        (false, true) => "SnapToWindow - ⬆️ Update | ⚠️ Accessibility Required",
-        (false, false) => "SnapToWindow - ⚠️ Accessibility Required",
+        (true, true) => tr("tray.tooltip_update"),
+        (true, false) => "SnapToWindow".to_string(),
+        (false, true) => tr("tray.tooltip_update_accessibility"),
+        (false, false) => tr("tray.tooltip_accessibility"),
     };
 
     // Use white icon on Windows dark mode, otherwise use default dark icon
@@ -452,123 +1169,427 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     }
 
     builder
-        .on_menu_event(|app, event| {
-            let position = match event.id.as_ref() {
-                // Accessibility warning
-                "accessibility_warning" => {
-                    open_accessibility_settings();
-                    if let Some(window) = app.get_webview_window("main") {
-                        window.show().ok();
-                        window.set_focus().ok();
-                    }
-                    None
-                }
-                // Halves
-                "left_half" => Some(SnapPosition::LeftHalf),
-                "right_half" => Some(SnapPosition::RightHalf),
-                "top_half" => Some(SnapPosition::TopHalf),
-                "bottom_half" => Some(SnapPosition::BottomHalf),
-                // Quarters
-                "top_left" => Some(SnapPosition::TopLeft),
-                "top_right" => Some(SnapPosition::TopRight),
-                "bottom_left" => Some(SnapPosition::BottomLeft),
-                "bottom_right" => Some(SnapPosition::BottomRight),
-                // Thirds
-                "left_third" => Some(SnapPosition::LeftThird),
-                "center_third" => Some(SnapPosition::CenterThird),
-                "right_third" => Some(SnapPosition::RightThird),
-                "left_two_thirds" => Some(SnapPosition::LeftTwoThirds),
-                "right_two_thirds" => Some(SnapPosition::RightTwoThirds),
-                // Other
-                "maximize" => Some(SnapPosition::Maximize),
-                "center" => Some(SnapPosition::Center),
-                // Display actions
-                "next_display" => {
-                    let manager = WindowManager::new();
-                    if let Err(e) = manager.move_to_display(DisplayDirection::Next) {
-                        eprintln!("Failed to move window to next display: {}", e);
-                    }
-                    None
-                }
-                "previous_display" => {
-                    let manager = WindowManager::new();
-                    if let Err(e) = manager.move_to_display(DisplayDirection::Previous) {
-                        eprintln!("Failed to move window to previous display: {}", e);
-                    }
-                    None
-                }
-                // Non-snap actions
-                "launch_at_login" => {
-                    let autostart = app.autolaunch();
-                    let currently_enabled = autostart.is_enabled().unwrap_or(false);
-                    let new_state = !currently_enabled;
-
-                    let result = if new_state {
-                        autostart.enable()
-                    } else {
-                        autostart.disable()
-                    };
+        .on_menu_event(|app, event| handle_menu_event(app, event.id.as_ref()))
+        .build(app)?;
 
-                    if result.is_ok() {
-                        if let Ok(mut config) = Config::load() {
-                            config.launch_at_login = new_state;
-                            let _ = config.save();
-                        }
-                    }
-                    None
-                }
-                "settings" => {
-                    if let Some(window) = app.get_webview_window("main") {
-                        window.show().ok();
-                        window.set_focus().ok();
-                    }
-                    None
+    // Without a StatusNotifierItem host the tray icon never appears, so put
+    // the same menu on the main window instead
+    #[cfg(target_os = "linux")]
+    if !has_status_notifier_host() {
+        show_fallback_menu(app, menu)?;
+    }
+
+    Ok(())
+}
+
+/// Whether a StatusNotifierItem host (the tray of KDE, GNOME with the
+/// AppIndicator extension, Xfce, etc.) is running to show the tray icon.
+#[cfg(target_os = "linux")]
+fn has_status_notifier_host() -> bool {
+    let host_registered = || -> zbus::Result<bool> {
+        let conn = zbus::blocking::Connection::session()?;
+        let watcher = zbus::blocking::Proxy::new(
+            &conn,
+            "org.kde.StatusNotifierWatcher",
+            "/StatusNotifierWatcher",
+            "org.kde.StatusNotifierWatcher",
+        )?;
+        watcher.get_property("IsStatusNotifierHostRegistered")
+    };
+
+    host_registered().unwrap_or(false)
+}
+
+/// Attach the tray menu to the main window's menu bar so every action stays
+/// reachable when there is no tray to show it.
+#[cfg(target_os = "linux")]
+fn show_fallback_menu(app: &AppHandle, menu: Menu<Wry>) -> tauri::Result<()> {
+    let Some(window) = app.get_webview_window("main") else {
+        return Ok(());
+    };
+
+    // Tray rebuilds (display or update changes) just refresh the menu
+    window.set_menu(menu)?;
+    if FALLBACK_MENU_SHOWN.swap(true, Ordering::SeqCst) {
+        return Ok(());
+    }
+
+    window.on_menu_event(|window, event| {
+        // Our own window has focus while its menu is open; minimize it so the
+        // window manager hands focus back to the window the user wants to snap
+        window.minimize().ok();
+
+        let app = window.app_handle().clone();
+        let id = event.id.as_ref().to_string();
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(200));
+            handle_menu_event(&app, &id);
+        });
+    });
+    window.show()?;
+    window.set_focus()?;
+
+    Ok(())
+}
+
+/// Run the action for a tray (or fallback window) menu item.
+fn handle_menu_event(app: &AppHandle, id: &str) {
+    let position = match id {
+        // Accessibility warning
+        "accessibility_warning" => {
+            request_accessibility();
+            if let Some(window) = app.get_webview_window("main") {
+                window.show().ok();
+                window.set_focus().ok();
+            }
+            None
+        }
+        // Halves
+        "left_half" => Some(SnapPosition::LeftHalf),
+        "right_half" => Some(SnapPosition::RightHalf),
+        "top_half" => Some(SnapPosition::TopHalf),
+        "bottom_half" => Some(SnapPosition::BottomHalf),
+        // Quarters
+        "top_left" => Some(SnapPosition::TopLeft),
+        "top_right" => Some(SnapPosition::TopRight),
+        "bottom_left" => Some(SnapPosition::BottomLeft),
+        "bottom_right" => Some(SnapPosition::BottomRight),
+        // Thirds
+        "left_third" => Some(SnapPosition::LeftThird),
+        "center_third" => Some(SnapPosition::CenterThird),
+        "right_third" => Some(SnapPosition::RightThird),
+        "left_two_thirds" => Some(SnapPosition::LeftTwoThirds),
+        "right_two_thirds" => Some(SnapPosition::RightTwoThirds),
+        // Horizontal thirds
+        "top_third" => Some(SnapPosition::TopThird),
+        "middle_third" => Some(SnapPosition::MiddleThird),
+        "bottom_third" => Some(SnapPosition::BottomThird),
+        "top_two_thirds" => Some(SnapPosition::TopTwoThirds),
+        "bottom_two_thirds" => Some(SnapPosition::BottomTwoThirds),
+        // Fourths
+        "first_fourth" => Some(SnapPosition::FirstFourth),
+        "second_fourth" => Some(SnapPosition::SecondFourth),
+        "third_fourth" => Some(SnapPosition::ThirdFourth),
+        "last_fourth" => Some(SnapPosition::LastFourth),
+        "first_three_fourths" => Some(SnapPosition::FirstThreeFourths),
+        "last_three_fourths" => Some(SnapPosition::LastThreeFourths),
+        // Corner two-thirds
+        "top_left_two_thirds" => Some(SnapPosition::TopLeftTwoThirds),
+        "top_right_two_thirds" => Some(SnapPosition::TopRightTwoThirds),
+        "bottom_left_two_thirds" => Some(SnapPosition::BottomLeftTwoThirds),
+        "bottom_right_two_thirds" => Some(SnapPosition::BottomRightTwoThirds),
+        // Sixths
+        "top_left_sixth" => Some(SnapPosition::TopLeftSixth),
+        "top_center_sixth" => Some(SnapPosition::TopCenterSixth),
+        "top_right_sixth" => Some(SnapPosition::TopRightSixth),
+        "bottom_left_sixth" => Some(SnapPosition::BottomLeftSixth),
+        "bottom_center_sixth" => Some(SnapPosition::BottomCenterSixth),
+        "bottom_right_sixth" => Some(SnapPosition::BottomRightSixth),
+        // Ninths
+        "top_left_ninth" => Some(SnapPosition::TopLeftNinth),
+        "top_center_ninth" => Some(SnapPosition::TopCenterNinth),
+        "top_right_ninth" => Some(SnapPosition::TopRightNinth),
+        "middle_left_ninth" => Some(SnapPosition::MiddleLeftNinth),
+        "middle_center_ninth" => Some(SnapPosition::MiddleCenterNinth),
+        "middle_right_ninth" => Some(SnapPosition::MiddleRightNinth),
+        "bottom_left_ninth" => Some(SnapPosition::BottomLeftNinth),
+        "bottom_center_ninth" => Some(SnapPosition::BottomCenterNinth),
+        "bottom_right_ninth" => Some(SnapPosition::BottomRightNinth),
+        // Other
+        "maximize" => Some(SnapPosition::Maximize),
+        "maximize_height" => Some(SnapPosition::MaximizeHeight),
+        "maximize_width" => Some(SnapPosition::MaximizeWidth),
+        "center" => Some(SnapPosition::Center),
+        "center_half" => Some(SnapPosition::CenterHalf),
+        "restore" => {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.restore() {
+                eprintln!("Failed to restore window: {}", e);
+            }
+            None
+        }
+        "ignore_window" => {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.toggle_ignore_focused() {
+                eprintln!("Failed to toggle snapping for window: {}", e);
+            }
+            None
+        }
+        "apply_app_rules" => {
+            if let Ok(config) = Config::load().map(WindowManager::with_display_profile) {
+                let manager = WindowManager::new();
+                if let Err(e) = manager.apply_app_rules(&config.app_rules) {
+                    eprintln!("Failed to apply app rules: {}", e);
                 }
-                "check_updates" => {
-                    let app_handle = app.clone();
-                    tauri::async_runtime::spawn(async move {
-                        match check_for_updates(&app_handle).await {
-                            Ok(true) => println!("Update available, tray updated"),
-                            Ok(false) => println!("No updates available"),
-                            Err(e) => eprintln!("Update check failed: {}", e),
-                        }
-                    });
-                    None
+            }
+            None
+        }
+        // Display actions
+        "next_display" => {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.move_to_display(DisplayDirection::Next) {
+                eprintln!("Failed to move window to next display: {}", e);
+            }
+            None
+        }
+        "previous_display" => {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.move_to_display(DisplayDirection::Previous) {
+                eprintln!("Failed to move window to previous display: {}", e);
+            }
+            None
+        }
+        "fill" => {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.fill() {
+                eprintln!("Failed to fill empty space: {}", e);
+            }
+            None
+        }
+        "mirror_previous" => {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.mirror_previous() {
+                eprintln!("Failed to mirror previous window: {}", e);
+            }
+            None
+        }
+        "cascade_app_windows" => {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.cascade_app_windows() {
+                eprintln!("Failed to cascade app windows: {}", e);
+            }
+            None
+        }
+        // Resize
+        "grow" => {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.resize(ResizeAction::Grow) {
+                eprintln!("Failed to resize window: {}", e);
+            }
+            None
+        }
+        "shrink" => {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.resize(ResizeAction::Shrink) {
+                eprintln!("Failed to resize window: {}", e);
+            }
+            None
+        }
+        "grow_width" => {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.resize(ResizeAction::GrowWidth) {
+                eprintln!("Failed to resize window: {}", e);
+            }
+            None
+        }
+        "shrink_width" => {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.resize(ResizeAction::ShrinkWidth) {
+                eprintln!("Failed to resize window: {}", e);
+            }
+            None
+        }
+        "grow_height" => {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.resize(ResizeAction::GrowHeight) {
+                eprintln!("Failed to resize window: {}", e);
+            }
+            None
+        }
+        "shrink_height" => {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.resize(ResizeAction::ShrinkHeight) {
+                eprintln!("Failed to resize window: {}", e);
+            }
+            None
+        }
+        // Move to a specific display
+        id if id.starts_with("move_to_display_") => {
+            let manager = WindowManager::new();
+            let target = id["move_to_display_".len()..]
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| manager.displays_by_number().ok()?.into_iter().nth(i));
+            if let Some((number, display)) = target {
+                if let Err(e) = manager.move_to(&display) {
+                    eprintln!("Failed to move window to display {}: {}", number, e);
                 }
-                "install_update" => {
-                    let app_handle = app.clone();
-                    tauri::async_runtime::spawn(async move {
-                        if let Err(e) = do_install_update(&app_handle).await {
-                            eprintln!("Failed to install update: {}", e);
-                        }
-                    });
-                    None
+            }
+            None
+        }
+        // Size presets
+        id if id.starts_with("preset_") => {
+            let preset = id["preset_".len()..]
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| Config::load().ok()?.presets.into_iter().nth(i));
+            if let Some(preset) = preset {
+                let manager = WindowManager::new();
+                if let Err(e) = manager.snap_to_preset(&preset.size) {
+                    eprintln!("Failed to apply size preset: {}", e);
                 }
-                "quit" => {
-                    app.exit(0);
-                    None
+            }
+            None
+        }
+        // Custom positions
+        id if id.starts_with("custom_") => {
+            let custom = id["custom_".len()..]
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| {
+                    let config = WindowManager::with_display_profile(Config::load().ok()?);
+                    config.custom_positions.into_iter().nth(i)
+                });
+            if let Some(custom) = custom {
+                let manager = WindowManager::new();
+                if let Err(e) = manager.snap_to_custom(&custom.frame) {
+                    eprintln!("Failed to snap to custom position: {}", e);
                 }
+            }
+            None
+        }
+        "span_displays" => {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.span_displays() {
+                eprintln!("Failed to span window across displays: {}", e);
+            }
+            None
+        }
+        id if id.starts_with("throw_") => {
+            let direction = match id {
+                "throw_left" => Some(ThrowDirection::Left),
+                "throw_right" => Some(ThrowDirection::Right),
+                "throw_up" => Some(ThrowDirection::Up),
+                "throw_down" => Some(ThrowDirection::Down),
                 _ => None,
             };
-
-            if let Some(pos) = position {
+            if let Some(direction) = direction {
                 let manager = WindowManager::new();
-                if let Err(e) = manager.snap_to(pos) {
-                    eprintln!("Failed to snap window: {}", e);
+                if let Err(e) = manager.throw_to_display(direction) {
+                    eprintln!("Failed to throw window to display: {}", e);
                 }
             }
-        })
-        .build(app)?;
+            None
+        }
+        "swap_displays" => {
+            let manager = WindowManager::new();
+            if let Err(e) = manager.swap_with_adjacent_display() {
+                eprintln!("Failed to swap displays: {}", e);
+            }
+            None
+        }
+        // Non-snap actions
+        id if id.starts_with("shortcut_profile_") => {
+            // "shortcut_profile_default" doesn't parse and switches back to
+            // the top-level shortcuts
+            let name = id["shortcut_profile_".len()..]
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| Config::load().ok()?.shortcut_profiles.into_iter().nth(i))
+                .map(|p| p.name);
+            if let Err(e) = hotkeys::switch_shortcut_profile(app, name) {
+                eprintln!("Failed to switch shortcut profile: {}", e);
+            }
 
-    Ok(())
+            // Update the check marks once this event has been handled
+            let app_handle = app.clone();
+            app.run_on_main_thread(move || {
+                if let Err(e) = rebuild_tray(&app_handle) {
+                    eprintln!("Failed to rebuild tray: {}", e);
+                }
+            })
+            .ok();
+            None
+        }
+        "launch_at_login" => {
+            let new_state = !autostart::is_enabled(app);
+
+            if autostart::set_enabled(app, new_state).is_ok() {
+                if let Ok(mut config) = Config::load() {
+                    config.launch_at_login = new_state;
+                    let _ = config.save();
+                }
+            }
+            None
+        }
+        "settings" => {
+            if let Some(window) = app.get_webview_window("main") {
+                window.show().ok();
+                window.set_focus().ok();
+            }
+            None
+        }
+        "check_updates" => {
+            let app_handle = app.clone();
+            tauri::async_runtime::spawn(async move {
+                // A manual check also offers a skipped version again
+                match check_for_updates(&app_handle, true).await {
+                    Ok(true) => println!("Update available, tray updated"),
+                    Ok(false) => println!("No updates available"),
+                    Err(e) => eprintln!("Update check failed: {}", e),
+                }
+            });
+            None
+        }
+        "skip_update" => {
+            // Rebuild the tray once this event has been handled
+            let app_handle = app.clone();
+            app.run_on_main_thread(move || {
+                if let Err(e) = skip_update(&app_handle) {
+                    eprintln!("Failed to skip update: {}", e);
+                }
+            })
+            .ok();
+            None
+        }
+        "install_update" => {
+            let app_handle = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = do_install_update(&app_handle).await {
+                    eprintln!("Failed to install update: {}", e);
+                }
+            });
+            None
+        }
+        "quit" => {
+            app.exit(0);
+            None
+        }
+        _ => None,
+    };
+
+    if let Some(pos) = position {
+        let manager = WindowManager::new();
+        if let Err(e) = manager.snap_to(pos) {
+            eprintln!("Failed to snap window: {}", e);
+        }
+    }
+}
+
+/// The updater for the configured update channel.
+fn updater(app: &AppHandle) -> Result<Updater, Box<dyn std::error::Error + Send + Sync>> {
+    let channel = Config::load().map(|c| c.update_channel).unwrap_or_default();
+
+    let mut builder = app.updater_builder();
+    if channel == UpdateChannel::Beta {
+        builder = builder.endpoints(vec![BETA_UPDATE_ENDPOINT.parse()?])?;
+    }
+    Ok(builder.build()?)
 }
 
 /// Check for updates and update tray if available
-async fn check_for_updates(app: &AppHandle) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
-    let updater = app.updater()?;
+async fn check_for_updates(
+    app: &AppHandle,
+    include_skipped: bool,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    let updater = updater(app)?;
+    let skipped_version = Config::load().ok().and_then(|c| c.skipped_update_version);
 
     match updater.check().await {
-        Ok(Some(update)) => {
+        // A skipped version counts as no update, unless asked for
+        Ok(Some(update))
+            if include_skipped || skipped_version.as_deref() != Some(update.version.as_str()) =>
+        {
             let version = update.version.clone();
             println!("Update available: {}", version);
 
@@ -590,7 +1611,7 @@ async fn check_for_updates(app: &AppHandle) -> Result<bool, Box<dyn std::error::
 
             Ok(true)
         }
-        Ok(None) => {
+        Ok(_) => {
             println!("App is up to date");
             UPDATE_AVAILABLE.store(false, Ordering::SeqCst);
             *UPDATE_VERSION.lock().unwrap() = None;
@@ -604,38 +1625,124 @@ async fn check_for_updates(app: &AppHandle) -> Result<bool, Box<dyn std::error::
 
 /// Install the available update
 async fn do_install_update(app: &AppHandle) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let updater = app.updater()?;
+    let updater = updater(app)?;
 
     if let Some(update) = updater.check().await? {
         println!("Installing update: {}", update.version);
 
-        let mut downloaded = 0;
-        update.download_and_install(
-            |chunk_length, content_length| {
-                downloaded += chunk_length;
-                println!("Downloaded {} of {:?}", downloaded, content_length);
-            },
-            || {
-                println!("Download complete, preparing to install...");
-            },
-        ).await?;
-
-        // Restart the app to apply the update
+        // Use the background download if it is for this version
+        let downloaded_update = DOWNLOADED_UPDATE
+            .lock()
+            .unwrap()
+            .take()
+            .filter(|(version, _)| *version == update.version);
+
+        if let Some((_, bytes)) = downloaded_update {
+            app.emit("update-progress", 100).ok();
+            update.install(bytes)?;
+        } else {
+            let mut downloaded = 0;
+            update.download_and_install(
+                |chunk_length, content_length| {
+                    downloaded += chunk_length;
+                    println!("Downloaded {} of {:?}", downloaded, content_length);
+
+                    // Let the settings window show progress
+                    if let Some(total) = content_length.filter(|t| *t > 0) {
+                        let percent = downloaded as u64 * 100 / total;
+                        app.emit("update-progress", percent).ok();
+                    }
+                },
+                || {
+                    println!("Download complete, preparing to install...");
+                },
+            ).await?;
+        }
+
+        // Restart the app to apply the update. Restarting can skip the exit event
+        WindowManager::save_display_affinity();
         app.restart();
     }
 
     Ok(())
 }
 
-/// Public function to check for updates at startup
+/// Download the available update without installing it, so installing it
+/// later doesn't have to wait for the download.
+async fn download_update(app: &AppHandle) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let updater = updater(app)?;
+
+    if let Some(update) = updater.check().await? {
+        let already_downloaded = DOWNLOADED_UPDATE
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|(version, _)| *version == update.version);
+        if already_downloaded {
+            return Ok(());
+        }
+
+        let bytes = update.download(|_, _| {}, || {}).await?;
+        println!("Downloaded update: {}", update.version);
+        *DOWNLOADED_UPDATE.lock().unwrap() = Some((update.version.clone(), bytes));
+    }
+
+    Ok(())
+}
+
+/// Check for an update on the configured channel, as the settings window
+/// does. Returns the version available, if any.
+pub async fn check_for_update(app: &AppHandle, include_skipped: bool) -> Result<Option<String>, String> {
+    check_for_updates(app, include_skipped).await.map_err(|e| e.to_string())?;
+    Ok(UPDATE_VERSION.lock().unwrap().clone())
+}
+
+/// Stop offering the available update, until a newer one is released.
+pub fn skip_update(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(version) = UPDATE_VERSION.lock().unwrap().take() else {
+        return Ok(());
+    };
+    UPDATE_AVAILABLE.store(false, Ordering::SeqCst);
+
+    let mut config = Config::load()?;
+    config.skipped_update_version = Some(version);
+    config.save()?;
+
+    rebuild_tray(app)
+}
+
+/// Install the available update from the configured channel and restart.
+pub async fn install_update(app: &AppHandle) -> Result<(), String> {
+    do_install_update(app).await.map_err(|e| e.to_string())
+}
+
+/// Public function for the automatic update checks, at startup and periodically
 pub async fn check_for_updates_startup(app: &AppHandle) {
-    match check_for_updates(app).await {
-        Ok(true) => println!("Update available on startup"),
+    match check_for_updates(app, false).await {
+        Ok(true) => {
+            println!("Update available on startup");
+
+            if Config::load().is_ok_and(|c| c.download_updates) {
+                if let Err(e) = download_update(app).await {
+                    eprintln!("Failed to download update: {}", e);
+                }
+            }
+        }
         Ok(false) => println!("App is up to date"),
         Err(e) => eprintln!("Startup update check failed: {}", e),
     }
 }
 
+/// Rebuild the tray menu, e.g. after config changes that add or remove items.
+pub fn rebuild_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(tray) = app.remove_tray_by_id(TRAY_ID) {
+        drop(tray);
+    }
+    setup_tray(app)?;
+
+    Ok(())
+}
+
 /// Refresh the tray to update accessibility status (only if changed)
 pub fn refresh_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     let current = check_accessibility();
@@ -669,6 +1776,91 @@ pub fn set_update_available(app: &AppHandle, available: bool, version: Option<St
     Ok(())
 }
 
+/// Poll for display changes (monitors added/removed, resolution changes) and
+/// rebuild the tray so the Move to Display submenu stays current
+pub fn start_display_watcher(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(std::time::Duration::from_secs(2));
+
+        // Display APIs (NSScreen on macOS) must be queried on the main thread
+        let app_clone = app.clone();
+        app.run_on_main_thread(move || {
+            let current = WindowManager::new().displays().unwrap_or_default();
+            let displays: Vec<(String, Rect)> = current
+                .iter()
+                .map(|d| (d.fingerprint(), d.bounds))
+                .collect();
+
+            let work_areas: Vec<Rect> = current.iter().map(|d| d.work_area).collect();
+            let work_areas_changed = {
+                let mut last = LAST_WORK_AREAS.lock().unwrap();
+                let changed = !last.is_empty() && *last != work_areas;
+                *last = work_areas;
+                changed
+            };
+
+            let previous: Vec<String> = LAST_DISPLAYS
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(fingerprint, _)| fingerprint.clone())
+                .collect();
+
+            if displays != *LAST_DISPLAYS.lock().unwrap() {
+                let manager = WindowManager::new();
+                let connected = manager.connected_displays().unwrap_or_default();
+                let profile = Config::load()
+                    .ok()
+                    .and_then(|c| c.active_profile(&connected).map(|p| p.name.clone()));
+                let profile_changed = {
+                    let mut last = LAST_PROFILE.lock().unwrap();
+                    let changed = *last != profile;
+                    *last = profile.clone();
+                    changed
+                };
+                if let (true, Some(profile)) = (profile_changed, &profile) {
+                    println!("Switched to display profile \"{}\"", profile);
+                }
+
+                // Put snapped windows back in place on the new arrangement
+                if let Err(e) = manager.resnap_windows() {
+                    eprintln!("Failed to re-snap windows after display change: {}", e);
+                }
+
+                // Bring windows back to displays that were reconnected
+                if let Err(e) = manager.restore_display_affinity(&previous) {
+                    eprintln!("Failed to restore windows to reconnected display: {}", e);
+                }
+
+                // Keep what was snapped where on disk, for the next launch
+                WindowManager::save_display_affinity();
+
+                if profile_changed {
+                    // The profile can bring its own custom positions and app
+                    // rules, which hotkeys and the tray are built from
+                    if let Err(e) = reload::apply_config(&app_clone) {
+                        eprintln!("Failed to apply display profile: {}", e);
+                    }
+                } else {
+                    if let Some(tray) = app_clone.remove_tray_by_id(TRAY_ID) {
+                        drop(tray);
+                    }
+                    if let Err(e) = setup_tray(&app_clone) {
+                        eprintln!("Failed to rebuild tray after display change: {}", e);
+                    }
+                }
+            } else if work_areas_changed {
+                // Same displays, but the taskbar/dock moved: keep snapped
+                // windows out from under it
+                if let Err(e) = WindowManager::new().resnap_windows() {
+                    eprintln!("Failed to re-snap windows after work area change: {}", e);
+                }
+            }
+        })
+        .ok();
+    });
+}
+
 /// Watch for Windows theme changes and rebuild tray when theme changes
 #[cfg(target_os = "windows")]
 pub fn start_theme_watcher(app: AppHandle) {
@@ -0,0 +1,322 @@
+//! Checking the config for mistakes up front, so the settings window can point
+//! them out instead of hotkeys silently failing to register.
+
+use crate::config::{Config, PresetConfig, ShortcutConfig};
+use crate::i18n::{self, tr, tr_args};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use tauri_plugin_global_shortcut::Shortcut;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// The setting can't be used as is.
+    Error,
+    /// The setting works, but probably not as intended.
+    Warning,
+}
+
+/// A problem with one setting.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigIssue {
+    pub severity: Severity,
+    /// Path of the setting, e.g. "shortcuts.left_half" or "custom_positions[2]".
+    pub setting: String,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    fn error(setting: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            setting: setting.into(),
+            message: message.into(),
+        }
+    }
+
+    fn warning(setting: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            setting: setting.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Check the config file, returning every problem found. Empty when it is fine.
+pub fn validate_config() -> Vec<ConfigIssue> {
+    match Config::load() {
+        Ok(config) => validate(&config),
+        Err(e) => vec![ConfigIssue::error("", tr_args("validate.unparsable", &[&e]))],
+    }
+}
+
+/// Problems that would stop some of the active hotkeys from registering:
+/// bindings that don't parse or are used twice.
+pub fn shortcut_errors(config: &Config) -> Vec<ConfigIssue> {
+    let prefix = config
+        .active_shortcut_profile
+        .as_ref()
+        .and_then(|name| config.shortcut_profiles.iter().position(|p| &p.name == name))
+        .map_or("shortcuts".to_string(), |i| format!("shortcut_profiles[{}].shortcuts", i));
+
+    let mut issues = Vec::new();
+    validate_shortcuts(config.active_shortcuts(), &prefix, &config.presets, &mut issues);
+    for (i, preset) in config.presets.iter().enumerate() {
+        if let Err(message) = parse_shortcut(&preset.shortcut) {
+            issues.push(ConfigIssue::error(format!("presets[{}].shortcut", i), message));
+        }
+    }
+
+    issues.retain(|issue| issue.severity == Severity::Error);
+    issues
+}
+
+fn validate(config: &Config) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+
+    validate_shortcuts(&config.shortcuts, "shortcuts", &config.presets, &mut issues);
+    for (i, profile) in config.shortcut_profiles.iter().enumerate() {
+        let prefix = format!("shortcut_profiles[{}].shortcuts", i);
+        validate_shortcuts(&profile.shortcuts, &prefix, &config.presets, &mut issues);
+    }
+
+    if let Some(name) = &config.active_shortcut_profile {
+        if !config.shortcut_profiles.iter().any(|p| &p.name == name) {
+            issues.push(ConfigIssue::warning(
+                "active_shortcut_profile",
+                tr_args("validate.no_shortcut_profile", &[name]),
+            ));
+        }
+    }
+
+    for (i, preset) in config.presets.iter().enumerate() {
+        if let Err(message) = parse_shortcut(&preset.shortcut) {
+            issues.push(ConfigIssue::error(format!("presets[{}].shortcut", i), message));
+        }
+    }
+
+    let action_names: Vec<String> = config
+        .shortcuts
+        .bindings()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    for (i, passthrough) in config.shortcut_passthrough.iter().enumerate() {
+        for name in &passthrough.shortcuts {
+            if !action_names.contains(name) {
+                issues.push(ConfigIssue::warning(
+                    format!("shortcut_passthrough[{}].shortcuts", i),
+                    tr_args("validate.unknown_shortcut", &[name]),
+                ));
+            }
+        }
+    }
+
+    let shortcut_sets = std::iter::once(("shortcuts".to_string(), &config.shortcuts)).chain(
+        config
+            .shortcut_profiles
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (format!("shortcut_profiles[{}].shortcuts", i), &p.shortcuts)),
+    );
+    for (prefix, shortcuts) in shortcut_sets {
+        for name in shortcuts.custom_positions.keys() {
+            // A display profile may bring the position along
+            let mut positions = config.custom_positions.iter().chain(
+                config
+                    .profiles
+                    .iter()
+                    .flat_map(|p| p.custom_positions.iter().flatten()),
+            );
+            if !positions.any(|c| &c.name == name) {
+                issues.push(ConfigIssue::warning(
+                    format!("{}.custom_positions.{}", prefix, name),
+                    tr_args("validate.no_custom_position", &[name]),
+                ));
+            }
+        }
+    }
+
+    for (i, custom) in config.custom_positions.iter().enumerate() {
+        let frame = &custom.frame;
+        let setting = format!("custom_positions[{}]", i);
+
+        let fractions = [frame.x, frame.y, frame.width, frame.height];
+        if fractions.iter().any(|f| !(0.0..=1.0).contains(f)) {
+            issues.push(ConfigIssue::error(
+                &setting,
+                tr("validate.custom_fractions"),
+            ));
+        } else if frame.width == 0.0 || frame.height == 0.0 {
+            issues.push(ConfigIssue::error(&setting, tr("validate.custom_empty")));
+        } else if frame.x + frame.width > 1.0 + f64::EPSILON
+            || frame.y + frame.height > 1.0 + f64::EPSILON
+        {
+            issues.push(ConfigIssue::warning(
+                &setting,
+                tr("validate.custom_off_screen"),
+            ));
+        }
+    }
+
+    if let Some(language) = &config.language {
+        if !i18n::is_supported(language) {
+            issues.push(ConfigIssue::warning(
+                "language",
+                tr_args("validate.unknown_language", &[language]),
+            ));
+        }
+    }
+
+    if !(0.1..=0.9).contains(&config.split_ratio) {
+        issues.push(ConfigIssue::warning("split_ratio", tr("validate.split_ratio")));
+    }
+
+    // Presets are checked against every shortcut set, so the same clash
+    // between presets would otherwise be reported once per set
+    let mut seen = HashSet::new();
+    issues.retain(|issue| seen.insert((issue.setting.clone(), issue.message.clone())));
+
+    issues
+}
+
+/// Check every shortcut of one set: that it parses, that it isn't used twice,
+/// and that disabled names exist. Presets are registered alongside every set,
+/// so their shortcuts must not clash with it either; they are only checked for
+/// clashes here, as they don't belong to the set.
+fn validate_shortcuts(
+    shortcuts: &ShortcutConfig,
+    prefix: &str,
+    presets: &[PresetConfig],
+    issues: &mut Vec<ConfigIssue>,
+) {
+    let bindings = shortcuts.bindings();
+    let mut used: HashMap<Shortcut, String> = HashMap::new();
+
+    for (name, binding) in &bindings {
+        let setting = format!("{}.{}", prefix, name);
+        match parse_shortcut(binding) {
+            Err(message) => issues.push(ConfigIssue::error(setting, message)),
+            Ok(Some(shortcut)) if !shortcuts.disabled.contains(name) => {
+                if let Some(other) = used.insert(shortcut, name.clone()) {
+                    issues.push(ConfigIssue::error(
+                        setting,
+                        tr_args("validate.duplicate_shortcut", &[binding, &other]),
+                    ));
+                }
+            }
+            Ok(_) => {}
+        }
+    }
+
+    for (name, binding) in &shortcuts.custom_positions {
+        let setting = format!("{}.custom_positions.{}", prefix, name);
+        match parse_shortcut(binding) {
+            Err(message) => issues.push(ConfigIssue::error(setting, message)),
+            Ok(Some(shortcut)) => {
+                if let Some(other) = used.insert(shortcut, name.clone()) {
+                    issues.push(ConfigIssue::error(
+                        setting,
+                        tr_args("validate.duplicate_shortcut", &[binding, &other]),
+                    ));
+                }
+            }
+            Ok(None) => {}
+        }
+    }
+
+    for (i, preset) in presets.iter().enumerate() {
+        if let Ok(Some(shortcut)) = parse_shortcut(&preset.shortcut) {
+            if let Some(other) = used.insert(shortcut, preset.name.clone()) {
+                issues.push(ConfigIssue::error(
+                    format!("presets[{}].shortcut", i),
+                    tr_args("validate.duplicate_shortcut", &[&preset.shortcut, &other]),
+                ));
+            }
+        }
+    }
+
+    for name in &shortcuts.disabled {
+        if !bindings.iter().any(|(n, _)| n == name) {
+            issues.push(ConfigIssue::warning(
+                format!("{}.disabled", prefix),
+                tr_args("validate.unknown_shortcut", &[name]),
+            ));
+        }
+    }
+}
+
+/// Parse a shortcut setting. Empty means unbound.
+fn parse_shortcut(binding: &str) -> Result<Option<Shortcut>, String> {
+    if binding.is_empty() {
+        return Ok(None);
+    }
+
+    binding
+        .parse::<Shortcut>()
+        .map(Some)
+        .map_err(|e| tr_args("validate.invalid_shortcut", &[&binding, &e]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::window_manager::PresetSize;
+
+    fn preset(name: &str, shortcut: &str) -> PresetConfig {
+        PresetConfig {
+            name: name.to_string(),
+            shortcut: shortcut.to_string(),
+            size: PresetSize {
+                width: 1280,
+                height: 800,
+                anchor: Default::default(),
+            },
+        }
+    }
+
+    #[test]
+    fn preset_clashing_with_a_shortcut_is_an_error() {
+        let mut config = Config::default();
+        config.presets.push(preset("Big", &config.shortcuts.left_half));
+
+        let errors = shortcut_errors(&config);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert_eq!(errors[0].setting, "presets[0].shortcut");
+    }
+
+    #[test]
+    fn preset_clashing_with_a_custom_position_is_an_error() {
+        let mut config = Config::default();
+        config
+            .shortcuts
+            .custom_positions
+            .insert("Notes".to_string(), "Control+Alt+Shift+N".to_string());
+        config.presets.push(preset("Big", "Control+Alt+Shift+N"));
+
+        assert_eq!(shortcut_errors(&config).len(), 1);
+    }
+
+    #[test]
+    fn presets_clashing_with_each_other_are_reported_once() {
+        let mut config = Config::default();
+        config.shortcut_profiles.push(crate::config::ShortcutProfile {
+            name: "Laptop".to_string(),
+            shortcuts: config.shortcuts.clone(),
+        });
+        config.presets.push(preset("Big", "Control+Alt+Shift+B"));
+        config.presets.push(preset("Bigger", "Control+Alt+Shift+B"));
+
+        let clashes: Vec<_> = validate(&config)
+            .into_iter()
+            .filter(|issue| issue.setting.starts_with("presets"))
+            .collect();
+        assert_eq!(clashes.len(), 1, "{:?}", clashes);
+    }
+
+    #[test]
+    fn default_config_has_no_shortcut_errors() {
+        assert!(shortcut_errors(&Config::default()).is_empty());
+    }
+}
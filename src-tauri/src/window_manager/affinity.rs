@@ -0,0 +1,113 @@
+//! Remembers which display each window was snapped on, so windows can be put back
+//! when that display is reconnected, or when the app starts again.
+//!
+//! Snaps only update the copy in memory; it is written to disk by [`flush`],
+//! on display changes and when the app exits, so snapping never waits on the
+//! file.
+
+use super::{Display, SnapPosition, Window};
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex, MutexGuard};
+
+// How many windows to remember
+const MAX_ENTRIES: usize = 200;
+
+// The store shared by every window manager, read from disk on first use
+static STORE: LazyLock<Mutex<AffinityStore>> = LazyLock::new(|| Mutex::new(AffinityStore::load()));
+
+/// The display and position a window was last snapped to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisplayAffinity {
+    pub app: String,
+    pub title: String,
+    /// Fingerprint of the display, see [`Display::fingerprint`].
+    pub display: String,
+    pub position: SnapPosition,
+}
+
+/// Persistent list of display affinities, most recent first.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AffinityStore {
+    entries: Vec<DisplayAffinity>,
+    /// Whether anything was recorded since the store was last written
+    #[serde(skip)]
+    changed: bool,
+}
+
+impl AffinityStore {
+    fn path() -> PathBuf {
+        Config::config_path().with_file_name("display_affinity.json")
+    }
+
+    /// Get the shared store.
+    pub fn shared() -> MutexGuard<'static, Self> {
+        STORE.lock().unwrap()
+    }
+
+    fn load() -> Self {
+        fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(Self::path(), content)?;
+        Ok(())
+    }
+
+    /// Remember that `window` was snapped to `position` on `display`.
+    pub fn record(&mut self, window: &Window, display: &Display, position: SnapPosition) {
+        self.entries
+            .retain(|e| e.app != window.app || e.title != window.title);
+        self.entries.insert(
+            0,
+            DisplayAffinity {
+                app: window.app.clone(),
+                title: window.title.clone(),
+                display: display.fingerprint(),
+                position,
+            },
+        );
+        self.entries.truncate(MAX_ENTRIES);
+        self.changed = true;
+    }
+
+    /// Find the affinity for a window: the entry for the same app and title, or
+    /// failing that (titles often change, e.g. with the open document) the
+    /// app's only entry. An app with several entries can't tell which one an
+    /// unknown title belongs to, so its windows are left alone.
+    pub fn find(&self, window: &Window) -> Option<&DisplayAffinity> {
+        if window.app.is_empty() {
+            return None;
+        }
+
+        let same_app = || self.entries.iter().filter(|e| e.app == window.app);
+
+        same_app().find(|e| e.title == window.title).or_else(|| {
+            let mut entries = same_app();
+            match (entries.next(), entries.next()) {
+                (Some(only), None) => Some(only),
+                _ => None,
+            }
+        })
+    }
+}
+
+/// Write the shared store to disk, if anything was recorded since it was
+/// last written.
+pub fn flush() {
+    let mut store = AffinityStore::shared();
+    if !store.changed {
+        return;
+    }
+
+    match store.save() {
+        Ok(()) => store.changed = false,
+        Err(e) => eprintln!("Failed to save display affinity: {}", e),
+    }
+}
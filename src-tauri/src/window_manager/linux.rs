@@ -1,40 +1,825 @@
 #![cfg(target_os = "linux")]
 
-use super::{Display, Rect, Result, Window, WindowHandle, WindowManagerError, WindowManagerTrait};
+use super::{Display, Insets, Rect, Result, Window, WindowHandle, WindowManagerError, WindowManagerTrait};
+use x11rb::connection::Connection;
+use x11rb::errors::{ConnectionError, ReplyError};
+use x11rb::protocol::randr::ConnectionExt as _;
+use x11rb::protocol::xproto::{
+    Atom, AtomEnum, ClientMessageEvent, ConfigureWindowAux, ConnectionExt as _, EventMask,
+    MapState,
+};
+use x11rb::rust_connection::RustConnection;
+use x11rb::NONE;
 
-pub struct LinuxManager;
+// _NET_WM_STATE action for removing a state
+const NET_WM_STATE_REMOVE: u32 = 0;
+
+// Source indication for client messages: a pager, i.e. a direct user action
+const SOURCE_PAGER: u32 = 2;
+
+// Flags for _NET_MOVERESIZE_WINDOW: StaticGravity (so coordinates refer to the
+// client window, matching what we read back), x/y/width/height present, and
+// "pager" as the source so WMs treat it as a user request
+const MOVERESIZE_FLAGS: u32 = 10 | (1 << 8) | (1 << 9) | (1 << 10) | (1 << 11) | (SOURCE_PAGER << 12);
+
+// Xft.dpi at 100% scaling
+const BASE_DPI: f64 = 96.0;
+
+x11rb::atom_manager! {
+    Atoms: AtomsCookie {
+        _NET_SUPPORTED,
+        _NET_ACTIVE_WINDOW,
+        _NET_CLIENT_LIST,
+        _NET_CLIENT_LIST_STACKING,
+        _NET_CURRENT_DESKTOP,
+        _NET_WORKAREA,
+        _NET_WM_STRUT,
+        _NET_WM_STRUT_PARTIAL,
+        _NET_MOVERESIZE_WINDOW,
+        _NET_WM_NAME,
+        _NET_WM_PID,
+        _NET_WM_STATE,
+        _NET_WM_STATE_HIDDEN,
+        _NET_WM_STATE_MAXIMIZED_VERT,
+        _NET_WM_STATE_MAXIMIZED_HORZ,
+        _NET_FRAME_EXTENTS,
+        _GTK_FRAME_EXTENTS,
+        UTF8_STRING,
+    }
+}
+
+impl From<ConnectionError> for WindowManagerError {
+    fn from(e: ConnectionError) -> Self {
+        WindowManagerError::X11(e.to_string())
+    }
+}
+
+impl From<ReplyError> for WindowManagerError {
+    fn from(e: ReplyError) -> Self {
+        WindowManagerError::X11(e.to_string())
+    }
+}
+
+/// An open connection to the X server.
+struct X11 {
+    conn: RustConnection,
+    screen_num: usize,
+    root: u32,
+    atoms: Atoms,
+    // Device pixels per logical pixel. X11 has a single scale for the whole
+    // screen, so the conversion is the same on every monitor
+    scale: f64,
+}
+
+impl X11 {
+    fn connect() -> Result<Self> {
+        let (conn, screen_num) =
+            x11rb::connect(None).map_err(|e| WindowManagerError::X11(e.to_string()))?;
+        let root = conn.setup().roots[screen_num].root;
+        let atoms = Atoms::new(&conn)?.reply()?;
+
+        let mut x11 = Self {
+            conn,
+            screen_num,
+            root,
+            atoms,
+            scale: 1.0,
+        };
+        x11.scale = x11.xft_scale();
+
+        Ok(x11)
+    }
+
+    /// Read the desktop's scale from `Xft.dpi`, which GNOME, KDE and Xfce set
+    /// for both integer and fractional scaling.
+    fn xft_scale(&self) -> f64 {
+        let resources = self
+            .get_string_property(self.root, AtomEnum::RESOURCE_MANAGER.into(), AtomEnum::STRING)
+            .unwrap_or_default();
+
+        resources
+            .lines()
+            .find_map(|line| line.strip_prefix("Xft.dpi:"))
+            .and_then(|dpi| dpi.trim().parse::<f64>().ok())
+            .filter(|&dpi| dpi > 0.0)
+            .map_or(1.0, |dpi| dpi / BASE_DPI)
+    }
+
+    /// Convert a window's frame from device to logical pixels
+    fn to_logical(&self, mut window: Window) -> Window {
+        window.frame = window.frame.scaled(1.0 / self.scale);
+        window
+    }
+
+    /// Read a property made of 32-bit values (windows, atoms, cardinals)
+    fn get_u32_property(&self, window: u32, property: Atom, kind: impl Into<Atom>) -> Result<Vec<u32>> {
+        let reply = self
+            .conn
+            .get_property(false, window, property, kind, 0, u32::MAX)?
+            .reply()?;
+
+        Ok(reply.value32().map(|values| values.collect()).unwrap_or_default())
+    }
+
+    /// Read a text property as UTF-8
+    fn get_string_property(&self, window: u32, property: Atom, kind: impl Into<Atom>) -> Result<String> {
+        let reply = self
+            .conn
+            .get_property(false, window, property, kind, 0, u32::MAX)?
+            .reply()?;
+
+        Ok(String::from_utf8_lossy(&reply.value).into_owned())
+    }
+
+    /// Whether the window manager advertises support for a hint
+    fn supports(&self, hint: Atom) -> bool {
+        self.get_u32_property(self.root, self.atoms._NET_SUPPORTED, AtomEnum::ATOM)
+            .map(|supported| supported.contains(&hint))
+            .unwrap_or(false)
+    }
+
+    fn active_window(&self) -> Result<u32> {
+        self.get_u32_property(self.root, self.atoms._NET_ACTIVE_WINDOW, AtomEnum::WINDOW)?
+            .first()
+            .copied()
+            .filter(|&w| w != NONE)
+            .ok_or(WindowManagerError::NoFocusedWindow)
+    }
+
+    /// Read a left, right, top, bottom extents property
+    fn get_extents(&self, window: u32, property: Atom) -> Insets {
+        let values = self
+            .get_u32_property(window, property, AtomEnum::CARDINAL)
+            .unwrap_or_default();
+
+        match values.as_slice() {
+            [left, right, top, bottom] => Insets {
+                top: *top,
+                right: *right,
+                bottom: *bottom,
+                left: *left,
+            },
+            _ => Insets::default(),
+        }
+    }
+
+    /// Get the decorations a reparenting WM draws around the window (title bar
+    /// and borders), and the invisible shadow a client-side decorated (GTK)
+    /// window draws inside its own area
+    fn frame_extents(&self, window: u32) -> (Insets, Insets) {
+        (
+            self.get_extents(window, self.atoms._NET_FRAME_EXTENTS),
+            self.get_extents(window, self.atoms._GTK_FRAME_EXTENTS),
+        )
+    }
+
+    /// Get the visible frame of a window in root coordinates, including WM
+    /// decorations and excluding client-side shadows
+    fn window_frame(&self, window: u32) -> Result<Rect> {
+        let (decorations, shadows) = self.frame_extents(window);
+
+        Ok(self
+            .client_frame(window)?
+            .outset_by(&decorations)
+            .inset_by(&shadows))
+    }
+
+    /// Get the client window's own frame in root coordinates
+    fn client_frame(&self, window: u32) -> Result<Rect> {
+        let geometry = self.conn.get_geometry(window)?.reply()?;
+        let position = self
+            .conn
+            .translate_coordinates(window, self.root, 0, 0)?
+            .reply()?;
+
+        Ok(Rect::new(
+            position.dst_x as i32,
+            position.dst_y as i32,
+            geometry.width as u32,
+            geometry.height as u32,
+        ))
+    }
+
+    fn window_title(&self, window: u32) -> String {
+        self.get_string_property(window, self.atoms._NET_WM_NAME, self.atoms.UTF8_STRING)
+            .ok()
+            .filter(|title| !title.is_empty())
+            .or_else(|| {
+                self.get_string_property(window, AtomEnum::WM_NAME.into(), AtomEnum::STRING)
+                    .ok()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Get the application name from the class part of WM_CLASS ("instance\0Class\0")
+    fn window_app(&self, window: u32) -> String {
+        self.get_string_property(window, AtomEnum::WM_CLASS.into(), AtomEnum::STRING)
+            .ok()
+            .and_then(|class| class.split('\0').nth(1).map(str::to_string))
+            .unwrap_or_default()
+    }
+
+    fn window_pid(&self, window: u32) -> Option<u32> {
+        self.get_u32_property(window, self.atoms._NET_WM_PID, AtomEnum::CARDINAL)
+            .ok()?
+            .first()
+            .copied()
+    }
+
+    /// Whether the window is mapped and not minimized
+    fn is_visible(&self, window: u32) -> bool {
+        let viewable = self
+            .conn
+            .get_window_attributes(window)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .is_some_and(|attributes| attributes.map_state == MapState::VIEWABLE);
+
+        let hidden = self
+            .get_u32_property(window, self.atoms._NET_WM_STATE, AtomEnum::ATOM)
+            .map(|state| state.contains(&self.atoms._NET_WM_STATE_HIDDEN))
+            .unwrap_or(false);
+
+        viewable && !hidden
+    }
+
+    fn window(&self, window: u32) -> Result<Window> {
+        Ok(Window {
+            handle: WindowHandle::Linux(window as u64),
+            app: self.window_app(window),
+            title: self.window_title(window),
+            frame: self.window_frame(window)?,
+        })
+    }
+
+    /// Restore a window if it is maximized. Many WMs ignore resize requests for
+    /// maximized windows, so this has to happen before setting its frame.
+    fn restore_window(&self, window: u32) -> Result<()> {
+        let state = self.get_u32_property(window, self.atoms._NET_WM_STATE, AtomEnum::ATOM)?;
+        let maximized = state.contains(&self.atoms._NET_WM_STATE_MAXIMIZED_VERT)
+            || state.contains(&self.atoms._NET_WM_STATE_MAXIMIZED_HORZ);
+        if !maximized {
+            return Ok(());
+        }
+
+        let event = ClientMessageEvent::new(
+            32,
+            window,
+            self.atoms._NET_WM_STATE,
+            [
+                NET_WM_STATE_REMOVE,
+                self.atoms._NET_WM_STATE_MAXIMIZED_VERT,
+                self.atoms._NET_WM_STATE_MAXIMIZED_HORZ,
+                SOURCE_PAGER,
+                0,
+            ],
+        );
+        self.conn.send_event(
+            false,
+            self.root,
+            EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+            event,
+        )?;
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    /// Ask the window manager to move and resize a window, falling back to a
+    /// plain configure request for WMs without _NET_MOVERESIZE_WINDOW
+    fn move_resize(&self, window: u32, frame: Rect) -> Result<()> {
+        // Positions are for the client window, so take decorations off and put
+        // shadows back on to have the visible frame land on `frame`
+        let (decorations, shadows) = self.frame_extents(window);
+        let frame = frame.inset_by(&decorations).outset_by(&shadows);
+
+        if self.supports(self.atoms._NET_MOVERESIZE_WINDOW) {
+            let event = ClientMessageEvent::new(
+                32,
+                window,
+                self.atoms._NET_MOVERESIZE_WINDOW,
+                [
+                    MOVERESIZE_FLAGS,
+                    frame.x as u32,
+                    frame.y as u32,
+                    frame.width,
+                    frame.height,
+                ],
+            );
+            self.conn.send_event(
+                false,
+                self.root,
+                EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+                event,
+            )?;
+        } else {
+            let aux = ConfigureWindowAux::new()
+                .x(frame.x)
+                .y(frame.y)
+                .width(frame.width)
+                .height(frame.height);
+            self.conn.configure_window(window, &aux)?;
+        }
+
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    /// Application windows front to back
+    fn client_windows(&self) -> Result<Vec<u32>> {
+        // The stacking list is ordered bottom to top
+        let mut windows =
+            self.get_u32_property(self.root, self.atoms._NET_CLIENT_LIST_STACKING, AtomEnum::WINDOW)?;
+        windows.reverse();
+
+        Ok(windows)
+    }
+
+    /// List the monitors with their work areas
+    fn displays(&self) -> Result<Vec<Display>> {
+        let mut displays = self.monitors();
+        let screen = self.screen_display().bounds;
+        let struts = self.struts();
+        let desktop_work_area = self.desktop_work_area();
+
+        for display in &mut displays {
+            let mut work_area = struts
+                .iter()
+                .fold(display.bounds, |area, strut| strut.exclude_from(area, &screen));
+
+            // Not every panel publishes struts (e.g. the GNOME Shell top bar), but
+            // the WM accounts for them in the desktop-wide work area
+            if let Some(clipped) = desktop_work_area.and_then(|d| d.intersection(&work_area)) {
+                work_area = clipped;
+            }
+
+            display.work_area = work_area;
+        }
+
+        Ok(displays)
+    }
+
+    /// Get the space reserved by panels and docks, from every client window's
+    /// _NET_WM_STRUT_PARTIAL (or the older _NET_WM_STRUT)
+    fn struts(&self) -> Vec<Strut> {
+        let windows = self
+            .get_u32_property(self.root, self.atoms._NET_CLIENT_LIST, AtomEnum::WINDOW)
+            .unwrap_or_default();
+
+        windows
+            .into_iter()
+            .filter_map(|window| {
+                let partial = self
+                    .get_u32_property(window, self.atoms._NET_WM_STRUT_PARTIAL, AtomEnum::CARDINAL)
+                    .unwrap_or_default();
+                if let Ok(values) = <[u32; 12]>::try_from(partial.as_slice()) {
+                    return Some(Strut::from_partial(values));
+                }
+
+                let full = self
+                    .get_u32_property(window, self.atoms._NET_WM_STRUT, AtomEnum::CARDINAL)
+                    .unwrap_or_default();
+                <[u32; 4]>::try_from(full.as_slice()).ok().map(Strut::from_full)
+            })
+            .collect()
+    }
+
+    /// Get the work area of the current desktop as set by the window manager
+    fn desktop_work_area(&self) -> Option<Rect> {
+        let desktop = self
+            .get_u32_property(self.root, self.atoms._NET_CURRENT_DESKTOP, AtomEnum::CARDINAL)
+            .ok()?
+            .first()
+            .copied()
+            .unwrap_or(0) as usize;
+        let areas = self
+            .get_u32_property(self.root, self.atoms._NET_WORKAREA, AtomEnum::CARDINAL)
+            .ok()?;
+
+        // Four values (x, y, width, height) per desktop
+        let area = areas.chunks_exact(4).nth(desktop)?;
+        Some(Rect::new(area[0] as i32, area[1] as i32, area[2], area[3]))
+    }
+
+    /// List the monitors using RandR, falling back to the whole screen when the
+    /// extension (1.5+) isn't available
+    fn monitors(&self) -> Vec<Display> {
+        let Ok(reply) = self
+            .conn
+            .randr_get_monitors(self.root, true)
+            .map_err(WindowManagerError::from)
+            .and_then(|cookie| cookie.reply().map_err(WindowManagerError::from))
+        else {
+            return vec![self.screen_display()];
+        };
+
+        let displays: Vec<Display> = reply
+            .monitors
+            .iter()
+            .enumerate()
+            .map(|(i, monitor)| {
+                let bounds = Rect::new(
+                    monitor.x as i32,
+                    monitor.y as i32,
+                    monitor.width as u32,
+                    monitor.height as u32,
+                );
+
+                // Monitor names are atoms, usually the output name (e.g. "DP-1")
+                let name = self
+                    .conn
+                    .get_atom_name(monitor.name)
+                    .ok()
+                    .and_then(|cookie| cookie.reply().ok())
+                    .map(|reply| String::from_utf8_lossy(&reply.name).into_owned())
+                    .unwrap_or_else(|| format!("Display {}", i + 1));
+
+                Display {
+                    name,
+                    bounds,
+                    work_area: bounds,
+                    is_primary: monitor.primary,
+                    scale_factor: 1.0,
+                    safe_area: Insets::default(),
+                }
+            })
+            .collect();
+
+        if displays.is_empty() {
+            return vec![self.screen_display()];
+        }
+
+        displays
+    }
+
+    /// The root window's size, as a single display
+    fn screen_display(&self) -> Display {
+        let screen = &self.conn.setup().roots[self.screen_num];
+        let bounds = Rect::new(
+            0,
+            0,
+            screen.width_in_pixels as u32,
+            screen.height_in_pixels as u32,
+        );
+
+        Display {
+            name: "Screen".to_string(),
+            bounds,
+            work_area: bounds,
+            is_primary: true,
+            scale_factor: 1.0,
+            safe_area: Insets::default(),
+        }
+    }
+}
+
+/// Space a panel or dock reserves along the edges of the screen (the root
+/// window, not an individual monitor), with the range it covers along that edge.
+struct Strut {
+    left: (u32, u32, u32),
+    right: (u32, u32, u32),
+    top: (u32, u32, u32),
+    bottom: (u32, u32, u32),
+}
+
+impl Strut {
+    /// From _NET_WM_STRUT_PARTIAL: left, right, top, bottom, then start/end of
+    /// each in the same order
+    fn from_partial(v: [u32; 12]) -> Self {
+        Self {
+            left: (v[0], v[4], v[5]),
+            right: (v[1], v[6], v[7]),
+            top: (v[2], v[8], v[9]),
+            bottom: (v[3], v[10], v[11]),
+        }
+    }
+
+    /// From _NET_WM_STRUT, which reserves space along the whole edge
+    fn from_full(v: [u32; 4]) -> Self {
+        Self {
+            left: (v[0], 0, u32::MAX),
+            right: (v[1], 0, u32::MAX),
+            top: (v[2], 0, u32::MAX),
+            bottom: (v[3], 0, u32::MAX),
+        }
+    }
+
+    /// Shrink a monitor's area so it doesn't overlap the reserved space.
+    fn exclude_from(&self, area: Rect, screen: &Rect) -> Rect {
+        // Whether the strut's range along an edge overlaps the monitor's range
+        let covers = |(size, start, end): (u32, u32, u32), from: i32, to: i32| {
+            size > 0 && (start as i64) < to as i64 && (end as i64) >= from as i64
+        };
+
+        let mut left = area.x;
+        let mut top = area.y;
+        let mut right = area.right();
+        let mut bottom = area.bottom();
+
+        if covers(self.left, area.y, area.bottom()) {
+            left = left.max(screen.x + self.left.0 as i32);
+        }
+        if covers(self.right, area.y, area.bottom()) {
+            right = right.min(screen.right() - self.right.0 as i32);
+        }
+        if covers(self.top, area.x, area.right()) {
+            top = top.max(screen.y + self.top.0 as i32);
+        }
+        if covers(self.bottom, area.x, area.right()) {
+            bottom = bottom.min(screen.bottom() - self.bottom.0 as i32);
+        }
+
+        // A strut reaching past the monitor entirely belongs to another monitor
+        if right <= left || bottom <= top {
+            return area;
+        }
+
+        Rect::new(left, top, (right - left) as u32, (bottom - top) as u32)
+    }
+}
+
+// X11 works in device pixels while the rest of the app works in logical
+// pixels, so everything crossing this boundary is scaled
+impl WindowManagerTrait for X11 {
+    fn get_focused_window(&self) -> Result<Window> {
+        let window = self.active_window()?;
+
+        self.window(window).map(|w| self.to_logical(w))
+    }
+
+    fn set_window_frame(&self, window: &Window, frame: Rect) -> Result<()> {
+        let WindowHandle::Linux(id) = window.handle;
+
+        // Restore window first if it's maximized
+        self.restore_window(id as u32)?;
+        self.move_resize(id as u32, frame.scaled(self.scale))
+    }
+
+    fn get_current_display(&self) -> Result<Display> {
+        let window = self.get_focused_window()?;
+
+        display_containing(self.get_all_displays()?, &window.frame)
+    }
+
+    fn get_all_displays(&self) -> Result<Vec<Display>> {
+        let displays = self
+            .displays()?
+            .into_iter()
+            .map(|d| Display {
+                bounds: d.bounds.scaled(1.0 / self.scale),
+                work_area: d.work_area.scaled(1.0 / self.scale),
+                scale_factor: self.scale,
+                ..d
+            })
+            .collect();
+
+        Ok(displays)
+    }
+
+    fn get_visible_windows(&self) -> Result<Vec<Window>> {
+        let own_pid = std::process::id();
+
+        let windows = self
+            .client_windows()?
+            .into_iter()
+            .filter(|&w| self.is_visible(w) && self.window_pid(w) != Some(own_pid))
+            .filter_map(|w| self.window(w).ok())
+            .map(|w| self.to_logical(w))
+            .collect();
+
+        Ok(windows)
+    }
+
+    fn get_app_windows(&self, window: &Window) -> Result<Vec<Window>> {
+        let WindowHandle::Linux(id) = window.handle;
+        let pid = self.window_pid(id as u32);
+
+        let windows = self
+            .client_windows()?
+            .into_iter()
+            .filter(|&w| self.is_visible(w) && pid.is_some() && self.window_pid(w) == pid)
+            .filter_map(|w| self.window(w).ok())
+            .map(|w| self.to_logical(w))
+            .collect();
+
+        Ok(windows)
+    }
+
+    fn move_cursor(&self, x: i32, y: i32) -> Result<()> {
+        let x = (x as f64 * self.scale).round() as i16;
+        let y = (y as f64 * self.scale).round() as i16;
+
+        self.conn.warp_pointer(NONE, self.root, 0, 0, 0, 0, x, y)?;
+        self.conn.flush()?;
+        Ok(())
+    }
+}
+
+// A window as sent by the GNOME Shell extension: ID, app name, title, x, y,
+// width, height
+type ShellWindow = (u64, String, String, i32, i32, u32, u32);
+
+// A monitor as sent by the GNOME Shell extension: name, primary, bounds
+// (x, y, width, height), work area (x, y, width, height), scale factor
+type ShellMonitor = (String, bool, i32, i32, u32, u32, i32, i32, u32, u32, f64);
+
+/// D-Bus interface of the companion GNOME Shell extension (see `gnome-extension/`)
+#[zbus::proxy(
+    interface = "org.snaptowindow.Shell",
+    default_service = "org.snaptowindow.Shell",
+    default_path = "/org/snaptowindow/Shell"
+)]
+trait Shell {
+    fn get_focused_window(&self) -> zbus::Result<ShellWindow>;
+
+    /// Normal, non-minimized windows, front to back
+    fn get_windows(&self) -> zbus::Result<Vec<ShellWindow>>;
+
+    fn set_window_frame(&self, id: u64, x: i32, y: i32, width: u32, height: u32) -> zbus::Result<()>;
+
+    fn get_monitors(&self) -> zbus::Result<Vec<ShellMonitor>>;
+}
+
+impl From<zbus::Error> for WindowManagerError {
+    fn from(e: zbus::Error) -> Self {
+        WindowManagerError::DBus(e.to_string())
+    }
+}
+
+/// Window management through the GNOME Shell extension, for GNOME on Wayland
+/// where clients can't see or move each other's windows.
+///
+/// Mutter reports everything in its layout coordinates, which are already
+/// logical pixels when fractional scaling is enabled, so frames pass through
+/// unchanged and only the per-monitor scale is carried on the Display.
+struct GnomeShell {
+    proxy: ShellProxyBlocking<'static>,
+}
+
+impl GnomeShell {
+    fn connect() -> Result<Self> {
+        let conn = zbus::blocking::Connection::session()?;
+        let proxy = ShellProxyBlocking::new(&conn)?;
+
+        // Make sure the extension is actually installed and enabled
+        proxy.get_monitors()?;
+
+        Ok(Self { proxy })
+    }
+
+    fn window((id, app, title, x, y, width, height): ShellWindow) -> Window {
+        Window {
+            handle: WindowHandle::Linux(id),
+            app,
+            title,
+            frame: Rect::new(x, y, width, height),
+        }
+    }
+}
+
+impl WindowManagerTrait for GnomeShell {
+    fn get_focused_window(&self) -> Result<Window> {
+        let window = self
+            .proxy
+            .get_focused_window()
+            .map_err(|_| WindowManagerError::NoFocusedWindow)?;
+
+        Ok(Self::window(window))
+    }
+
+    fn set_window_frame(&self, window: &Window, frame: Rect) -> Result<()> {
+        let WindowHandle::Linux(id) = window.handle;
+
+        // The extension un-maximizes the window before moving it
+        self.proxy
+            .set_window_frame(id, frame.x, frame.y, frame.width, frame.height)?;
+        Ok(())
+    }
+
+    fn get_current_display(&self) -> Result<Display> {
+        let window = self.get_focused_window()?;
+
+        display_containing(self.get_all_displays()?, &window.frame)
+    }
+
+    fn get_all_displays(&self) -> Result<Vec<Display>> {
+        let displays = self
+            .proxy
+            .get_monitors()?
+            .into_iter()
+            .map(|(name, is_primary, x, y, width, height, wx, wy, wwidth, wheight, scale)| Display {
+                name,
+                bounds: Rect::new(x, y, width, height),
+                work_area: Rect::new(wx, wy, wwidth, wheight),
+                is_primary,
+                scale_factor: scale,
+                safe_area: Insets::default(),
+            })
+            .collect();
+
+        Ok(displays)
+    }
+
+    fn get_visible_windows(&self) -> Result<Vec<Window>> {
+        let windows = self
+            .proxy
+            .get_windows()?
+            .into_iter()
+            .map(Self::window)
+            .collect();
+
+        Ok(windows)
+    }
+
+    fn get_app_windows(&self, window: &Window) -> Result<Vec<Window>> {
+        let windows = self
+            .get_visible_windows()?
+            .into_iter()
+            .filter(|w| w.app == window.app)
+            .collect();
+
+        Ok(windows)
+    }
+
+    fn move_cursor(&self, _x: i32, _y: i32) -> Result<()> {
+        // Wayland doesn't let clients warp the pointer
+        Err(WindowManagerError::PlatformNotSupported)
+    }
+}
+
+/// Find the display `frame` is on, falling back to the primary display.
+fn display_containing(displays: Vec<Display>, frame: &Rect) -> Result<Display> {
+    Display::for_frame(&displays, frame)
+        .or_else(|| displays.iter().find(|d| d.is_primary))
+        .cloned()
+        .ok_or(WindowManagerError::DisplayError)
+}
+
+pub struct LinuxManager {
+    // None when there is nothing to talk to (e.g. Wayland without the GNOME
+    // Shell extension)
+    backend: Option<Box<dyn WindowManagerTrait>>,
+}
 
 impl LinuxManager {
     pub fn new() -> Self {
-        Self
+        Self {
+            backend: Self::connect(),
+        }
+    }
+
+    fn connect() -> Option<Box<dyn WindowManagerTrait>> {
+        // Under Wayland, X11 only sees XWayland windows, so prefer GNOME Shell
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            match GnomeShell::connect() {
+                Ok(shell) => return Some(Box::new(shell)),
+                Err(e) => eprintln!("GNOME Shell extension not available: {}", e),
+            }
+        }
+
+        match X11::connect() {
+            Ok(x11) => Some(Box::new(x11)),
+            Err(e) => {
+                eprintln!("Failed to connect to X server: {}", e);
+                None
+            }
+        }
+    }
+
+    fn backend(&self) -> Result<&dyn WindowManagerTrait> {
+        // TODO: Other Wayland compositors need their own protocols
+        self.backend
+            .as_deref()
+            .ok_or(WindowManagerError::PlatformNotSupported)
     }
 }
 
 impl WindowManagerTrait for LinuxManager {
     fn get_focused_window(&self) -> Result<Window> {
-        // TODO: Implement for X11 using xcb or x11rb
-        // Use _NET_ACTIVE_WINDOW to get the focused window
-        // For Wayland, implementation will vary by compositor
-        Err(WindowManagerError::PlatformNotSupported)
+        self.backend()?.get_focused_window()
     }
 
-    fn set_window_frame(&self, _window: &Window, _frame: Rect) -> Result<()> {
-        // TODO: Implement for X11
-        // Use XMoveResizeWindow or _NET_MOVERESIZE_WINDOW
-        // For Wayland, this may require compositor-specific protocols
-        Err(WindowManagerError::PlatformNotSupported)
+    fn set_window_frame(&self, window: &Window, frame: Rect) -> Result<()> {
+        self.backend()?.set_window_frame(window, frame)
     }
 
     fn get_current_display(&self) -> Result<Display> {
-        // TODO: Implement using Xrandr for X11
-        // For Wayland, use wl_output
-        Err(WindowManagerError::PlatformNotSupported)
+        self.backend()?.get_current_display()
     }
 
     fn get_all_displays(&self) -> Result<Vec<Display>> {
-        // TODO: Implement using Xrandr for X11
-        // For Wayland, enumerate wl_output objects
-        Err(WindowManagerError::PlatformNotSupported)
+        self.backend()?.get_all_displays()
+    }
+
+    fn get_visible_windows(&self) -> Result<Vec<Window>> {
+        self.backend()?.get_visible_windows()
+    }
+
+    fn get_app_windows(&self, window: &Window) -> Result<Vec<Window>> {
+        self.backend()?.get_app_windows(window)
+    }
+
+    fn move_cursor(&self, x: i32, y: i32) -> Result<()> {
+        self.backend()?.move_cursor(x, y)
     }
 }
 
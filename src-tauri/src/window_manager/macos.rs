@@ -1,8 +1,9 @@
 #![cfg(target_os = "macos")]
 
-use super::{Display, Rect, Result, Window, WindowHandle, WindowManagerError, WindowManagerTrait};
-use core_foundation::array::CFArray;
+use super::{Display, Insets, Rect, Result, Window, WindowHandle, WindowManagerError, WindowManagerTrait};
+use core_foundation::array::{CFArray, CFArrayRef};
 use core_foundation::base::TCFType;
+use core_foundation::boolean::CFBoolean;
 use core_foundation::dictionary::CFDictionary;
 use core_foundation::number::CFNumber;
 use core_foundation::string::{CFString, CFStringRef};
@@ -16,6 +17,8 @@ use core_graphics::window::{
 use std::ffi::c_void;
 use std::ptr;
 use std::sync::atomic::{AtomicI32, Ordering};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex, Once};
 
 // Accessibility API types and constants
 type AXUIElementRef = *mut c_void;
@@ -27,6 +30,23 @@ const AX_VALUE_TYPE_CG_POINT: AXValueType = 1;
 const AX_VALUE_TYPE_CG_SIZE: AXValueType = 2;
 const K_AX_ERROR_SUCCESS: AXError = 0;
 
+// How often and how many times to check whether a window has finished a
+// state change (e.g. the unminimize animation)
+const POLL_INTERVAL_MS: u64 = 20;
+const UNMINIMIZE_POLLS: u32 = 25;
+const FULLSCREEN_EXIT_POLLS: u32 = 100;
+
+// Extra wait after leaving full screen for the Space transition to finish
+const FULLSCREEN_SETTLE_MS: u64 = 300;
+
+// How many times to reapply a frame the app didn't accept, and how long to
+// give it between attempts
+const FRAME_RETRIES: u32 = 3;
+const RETRY_DELAY_MS: u64 = 50;
+
+// How far (in points) a window may land from the requested frame
+const FRAME_TOLERANCE: f64 = 1.0;
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 struct CGPoint {
@@ -57,6 +77,12 @@ unsafe extern "C" {
         attribute: CFStringRef,
         value: *mut *mut c_void,
     ) -> AXError;
+    fn AXUIElementCopyMultipleAttributeValues(
+        element: AXUIElementRef,
+        attributes: CFArrayRef,
+        options: u32,
+        values: *mut CFArrayRef,
+    ) -> AXError;
     fn AXUIElementSetAttributeValue(
         element: AXUIElementRef,
         attribute: CFStringRef,
@@ -64,11 +90,15 @@ unsafe extern "C" {
     ) -> AXError;
     fn AXValueCreate(value_type: AXValueType, value: *const c_void) -> AXValueRef;
     fn AXValueGetValue(value: AXValueRef, value_type: AXValueType, value_out: *mut c_void) -> bool;
+    // Private, but stable for years and the only way to map an AX element to a CGWindowID
+    fn _AXUIElementGetWindow(element: AXUIElementRef, window_id: *mut u32) -> AXError;
 }
 
 #[link(name = "CoreGraphics", kind = "framework")]
 unsafe extern "C" {
     fn CGRectContainsPoint(rect: CGRect, point: CGPoint) -> bool;
+    fn CGWarpMouseCursorPosition(new_cursor_position: CGPoint) -> i32;
+    fn CGAssociateMouseAndMouseCursorPosition(connected: bool) -> i32;
 }
 
 #[link(name = "AppKit", kind = "framework")]
@@ -78,13 +108,113 @@ unsafe extern "C" {}
 #[link(name = "Foundation", kind = "framework")]
 unsafe extern "C" {}
 
-// Store the last known frontmost app PID for fallback when tray menu steals focus
-static LAST_FRONTMOST_PID: AtomicI32 = AtomicI32::new(0);
+// The most recently activated app other than ours, kept up to date by an
+// NSWorkspace observer so snaps from the tray menu (which activates us) still
+// target the app the user was in
+static FRONTMOST_PID: AtomicI32 = AtomicI32::new(0);
+
+// Processes that draw system UI in regular window layers; snapping their
+// windows only breaks them
+const SYSTEM_PROCESSES: &[&str] = &[
+    "Dock",
+    "Window Server",
+    "WindowManager",
+    "SystemUIServer",
+    "Control Center",
+    "ControlCenter",
+    "Notification Center",
+    "NotificationCenter",
+    "Spotlight",
+    "loginwindow",
+    "screencaptureui",
+];
+
+/// Whether a window owner is our own app or part of the system UI
+fn is_excluded_owner(owner: &str) -> bool {
+    owner.contains("SnapToWindow") || SYSTEM_PROCESSES.contains(&owner)
+}
+static WORKSPACE_OBSERVER: Once = Once::new();
+
+/// An application's AX element and name, kept between snaps to save AX round trips
+struct CachedApp {
+    element: AXUIElementRef,
+    name: Option<String>,
+}
+
+// AX elements are immutable CF objects that can be used from any thread
+unsafe impl Send for CachedApp {}
+
+impl Drop for CachedApp {
+    fn drop(&mut self) {
+        unsafe {
+            core_foundation::base::CFRelease(self.element as *const c_void);
+        }
+    }
+}
+
+// Cleared of apps as they quit, by the NSWorkspace observer
+static APP_CACHE: LazyLock<Mutex<HashMap<i32, CachedApp>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Start tracking the frontmost app and quitting apps through NSWorkspace
+/// notifications
+fn observe_workspace() {
+    use block2::RcBlock;
+    use objc2_app_kit::{
+        NSRunningApplication, NSWorkspace, NSWorkspaceDidActivateApplicationNotification,
+        NSWorkspaceDidTerminateApplicationNotification,
+    };
+    use objc2_foundation::NSNotification;
+    use std::ptr::NonNull;
+
+    fn record_frontmost() {
+        let pid = NSWorkspace::sharedWorkspace()
+            .frontmostApplication()
+            .map(|app| app.processIdentifier())
+            .unwrap_or(0);
+
+        if pid > 0 && pid as u32 != std::process::id() {
+            FRONTMOST_PID.store(pid, Ordering::SeqCst);
+        }
+    }
+
+    fn forget_terminated_apps() {
+        APP_CACHE.lock().unwrap().retain(|&pid, _| {
+            NSRunningApplication::runningApplicationWithProcessIdentifier(pid).is_some()
+        });
+    }
+
+    record_frontmost();
+
+    let activated = RcBlock::new(|_: NonNull<NSNotification>| record_frontmost());
+    let terminated = RcBlock::new(|_: NonNull<NSNotification>| forget_terminated_apps());
+    unsafe {
+        let center = NSWorkspace::sharedWorkspace().notificationCenter();
+        let observers = [
+            center.addObserverForName_object_queue_usingBlock(
+                Some(NSWorkspaceDidActivateApplicationNotification),
+                None,
+                None,
+                &activated,
+            ),
+            center.addObserverForName_object_queue_usingBlock(
+                Some(NSWorkspaceDidTerminateApplicationNotification),
+                None,
+                None,
+                &terminated,
+            ),
+        ];
+
+        // Observe for the lifetime of the app
+        std::mem::forget(observers);
+    }
+}
 
 pub struct MacOSManager;
 
 impl MacOSManager {
     pub fn new() -> Self {
+        WORKSPACE_OBSERVER.call_once(observe_workspace);
         Self
     }
 
@@ -125,11 +255,11 @@ impl MacOSManager {
                     // Layer 0 is regular windows
                     if layer_num.to_i32() == Some(0) {
                         if let Some(pid_val) = pid_num.to_i32() {
-                            // Skip our own app (SnapToWindow)
+                            // Skip our own app and system UI
                             if let Some(name_ref) = window_dict.find(&name_key) {
                                 let name_str =
                                     CFString::wrap_under_get_rule(name_ref.as_CFTypeRef() as _);
-                                if name_str.to_string().contains("SnapToWindow") {
+                                if is_excluded_owner(&name_str.to_string()) {
                                     continue;
                                 }
                             }
@@ -145,25 +275,22 @@ impl MacOSManager {
 
     /// Get the PID of the frontmost application
     fn get_frontmost_app_pid(&self) -> Result<i32> {
-        // Try the standard AX method first
+        // Try the standard AX method first, unless it reports us because the
+        // tray menu took focus
         if let Ok(pid) = self.get_frontmost_app_pid_ax() {
-            // Save as last known good PID
-            LAST_FRONTMOST_PID.store(pid, Ordering::SeqCst);
-            return Ok(pid);
-        }
-
-        // Fallback 1: Try to get topmost window from CGWindowList
-        if let Ok(pid) = self.get_topmost_window_pid() {
-            return Ok(pid);
+            if pid as u32 != std::process::id() {
+                return Ok(pid);
+            }
         }
 
-        // Fallback 2: Use last known frontmost PID
-        let last_pid = LAST_FRONTMOST_PID.load(Ordering::SeqCst);
-        if last_pid > 0 {
-            return Ok(last_pid);
+        // Fallback 1: The last app activated before us
+        let tracked_pid = FRONTMOST_PID.load(Ordering::SeqCst);
+        if tracked_pid > 0 {
+            return Ok(tracked_pid);
         }
 
-        Err(WindowManagerError::NoFocusedWindow)
+        // Fallback 2: Try to get topmost window from CGWindowList
+        self.get_topmost_window_pid()
     }
 
     /// Get the PID of the frontmost application using Accessibility API
@@ -215,7 +342,7 @@ impl MacOSManager {
     /// Get the focused window AXUIElement for an application
     fn get_focused_window_element(&self, pid: i32) -> Result<AXUIElementRef> {
         unsafe {
-            let app_element = AXUIElementCreateApplication(pid);
+            let app_element = self.get_app_element(pid);
             if app_element.is_null() {
                 return Err(WindowManagerError::NoFocusedWindow);
             }
@@ -239,6 +366,253 @@ impl MacOSManager {
         }
     }
 
+    /// Get all window elements of an application, front to back.
+    /// The caller must release each returned element.
+    fn get_app_window_elements(&self, pid: i32) -> Result<Vec<AXUIElementRef>> {
+        use core_foundation::base::CFType;
+
+        unsafe {
+            let app_element = self.get_app_element(pid);
+            if app_element.is_null() {
+                return Err(WindowManagerError::WindowListError);
+            }
+
+            let attr_name = CFString::new("AXWindows");
+            let mut value: *mut c_void = ptr::null_mut();
+
+            let result = AXUIElementCopyAttributeValue(
+                app_element,
+                attr_name.as_concrete_TypeRef(),
+                &mut value,
+            );
+
+            core_foundation::base::CFRelease(app_element as *const c_void);
+
+            if result != K_AX_ERROR_SUCCESS || value.is_null() {
+                return Err(WindowManagerError::WindowListError);
+            }
+
+            let windows: CFArray<CFType> = CFArray::wrap_under_create_rule(value as _);
+            let elements = windows
+                .iter()
+                .map(|w| {
+                    let element = w.as_CFTypeRef() as AXUIElementRef;
+                    core_foundation::base::CFRetain(element as *const c_void);
+                    element
+                })
+                .collect();
+
+            Ok(elements)
+        }
+    }
+
+    /// Get the window element for a handle, falling back to the app's focused
+    /// window when the handle has no window ID or the window is gone.
+    fn get_window_element(&self, pid: i32, window_id: u32) -> Result<AXUIElementRef> {
+        if window_id != 0 {
+            if let Ok(elements) = self.get_app_window_elements(pid) {
+                let mut found = None;
+                for element in elements {
+                    if found.is_none() && self.get_window_id(element) == window_id {
+                        found = Some(element);
+                    } else {
+                        unsafe {
+                            core_foundation::base::CFRelease(element as *const c_void);
+                        }
+                    }
+                }
+
+                if let Some(element) = found {
+                    return Ok(element);
+                }
+            }
+        }
+
+        self.get_focused_window_element(pid)
+    }
+
+    /// Get the CGWindowID of a window element (0 if unavailable)
+    fn get_window_id(&self, window: AXUIElementRef) -> u32 {
+        let mut window_id: u32 = 0;
+
+        unsafe {
+            if _AXUIElementGetWindow(window, &mut window_id) != K_AX_ERROR_SUCCESS {
+                return 0;
+            }
+        }
+
+        window_id
+    }
+
+    /// Check whether a window element is minimized
+    fn is_window_minimized(&self, window: AXUIElementRef) -> bool {
+        self.get_bool_attribute(window, "AXMinimized")
+    }
+
+    /// Check whether a window element is in native full screen
+    fn is_window_fullscreen(&self, window: AXUIElementRef) -> bool {
+        self.get_bool_attribute(window, "AXFullScreen")
+    }
+
+    /// Read a boolean attribute of an element (false if unsupported)
+    fn get_bool_attribute(&self, element: AXUIElementRef, attribute: &str) -> bool {
+        unsafe {
+            let attr_name = CFString::new(attribute);
+            let mut value: *mut c_void = ptr::null_mut();
+
+            let result =
+                AXUIElementCopyAttributeValue(element, attr_name.as_concrete_TypeRef(), &mut value);
+
+            if result != K_AX_ERROR_SUCCESS || value.is_null() {
+                return false;
+            }
+
+            CFBoolean::wrap_under_create_rule(value as _).into()
+        }
+    }
+
+    /// Set a boolean attribute on an element
+    fn set_bool_attribute(&self, element: AXUIElementRef, attribute: &str, value: bool) -> Result<()> {
+        unsafe {
+            let attr_name = CFString::new(attribute);
+            let value = if value {
+                CFBoolean::true_value()
+            } else {
+                CFBoolean::false_value()
+            };
+
+            let result = AXUIElementSetAttributeValue(
+                element,
+                attr_name.as_concrete_TypeRef(),
+                value.as_CFTypeRef(),
+            );
+
+            if result != K_AX_ERROR_SUCCESS {
+                return Err(WindowManagerError::MoveError(format!(
+                    "Failed to set {}: error {}",
+                    attribute, result
+                )));
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Bring a window back from the Dock if it is minimized, waiting for the
+    /// restore animation so the new frame isn't applied to the Dock tile
+    fn unminimize_window(&self, window: AXUIElementRef) -> Result<()> {
+        if !self.is_window_minimized(window) {
+            return Ok(());
+        }
+
+        self.set_bool_attribute(window, "AXMinimized", false)?;
+
+        for _ in 0..UNMINIMIZE_POLLS {
+            std::thread::sleep(std::time::Duration::from_millis(POLL_INTERVAL_MS));
+            if !self.is_window_minimized(window) {
+                return Ok(());
+            }
+        }
+
+        Err(WindowManagerError::MoveError("Window did not unminimize".into()))
+    }
+
+    /// Take a window out of native full screen, waiting for it to return to
+    /// its original Space
+    fn exit_fullscreen(&self, window: AXUIElementRef) -> Result<()> {
+        if !self.is_window_fullscreen(window) {
+            return Ok(());
+        }
+
+        self.set_bool_attribute(window, "AXFullScreen", false)
+            .map_err(|_| WindowManagerError::FullScreen)?;
+
+        for _ in 0..FULLSCREEN_EXIT_POLLS {
+            std::thread::sleep(std::time::Duration::from_millis(POLL_INTERVAL_MS));
+            if !self.is_window_fullscreen(window) {
+                // The attribute flips before the Space animation ends, and frame
+                // changes made during the animation are discarded
+                std::thread::sleep(std::time::Duration::from_millis(FULLSCREEN_SETTLE_MS));
+                return Ok(());
+            }
+        }
+
+        Err(WindowManagerError::FullScreen)
+    }
+
+    /// Build a Window from a window element
+    fn window_from_element(&self, pid: i32, window: AXUIElementRef) -> Result<Window> {
+        use core_foundation::base::CFType;
+
+        // Read everything in one AX round trip
+        let attributes = CFArray::from_CFTypes(&[
+            CFString::new("AXPosition"),
+            CFString::new("AXSize"),
+            CFString::new("AXTitle"),
+        ]);
+        let mut values: CFArrayRef = ptr::null();
+
+        let result = unsafe {
+            AXUIElementCopyMultipleAttributeValues(
+                window,
+                attributes.as_concrete_TypeRef(),
+                0,
+                &mut values,
+            )
+        };
+
+        if result != K_AX_ERROR_SUCCESS || values.is_null() {
+            return Err(WindowManagerError::MoveError("Failed to get window attributes".into()));
+        }
+
+        // Attributes that fail to read come back as AXValues holding the error,
+        // which AXValueGetValue rejects
+        let values: CFArray<CFType> = unsafe { CFArray::wrap_under_create_rule(values) };
+        let value_at = |i: isize| -> AXValueRef {
+            values
+                .get(i)
+                .map_or(ptr::null_mut(), |v| v.as_CFTypeRef() as AXValueRef)
+        };
+
+        let mut position = CGPoint { x: 0.0, y: 0.0 };
+        let mut size = CGSize {
+            width: 0.0,
+            height: 0.0,
+        };
+        unsafe {
+            let position_value = value_at(0);
+            let size_value = value_at(1);
+            if position_value.is_null()
+                || size_value.is_null()
+                || !AXValueGetValue(position_value, AX_VALUE_TYPE_CG_POINT, &mut position as *mut _ as _)
+                || !AXValueGetValue(size_value, AX_VALUE_TYPE_CG_SIZE, &mut size as *mut _ as _)
+            {
+                return Err(WindowManagerError::MoveError("Failed to get window frame".into()));
+            }
+        }
+
+        let title = values
+            .get(2)
+            .and_then(|v| v.downcast::<CFString>())
+            .map(|title| title.to_string())
+            .unwrap_or_default();
+
+        Ok(Window {
+            handle: WindowHandle::MacOS {
+                pid: pid as u32,
+                window_id: self.get_window_id(window),
+            },
+            app: self.get_app_name(pid),
+            title,
+            frame: Rect::new(
+                position.x.round() as i32,
+                position.y.round() as i32,
+                size.width as u32,
+                size.height as u32,
+            ),
+        })
+    }
+
     /// Get the position of a window element
     fn get_window_position(&self, window: AXUIElementRef) -> Result<CGPoint> {
         unsafe {
@@ -296,22 +670,82 @@ impl MacOSManager {
 
     /// Get the title of a window element
     fn get_window_title(&self, window: AXUIElementRef) -> String {
+        self.get_string_attribute(window, "AXTitle").unwrap_or_default()
+    }
+
+    /// Check whether a window element is a regular document window, rather
+    /// than a dialog, sheet, floating panel or system UI
+    fn is_standard_window(&self, window: AXUIElementRef) -> bool {
+        self.get_string_attribute(window, "AXRole").as_deref() == Some("AXWindow")
+            && self.get_string_attribute(window, "AXSubrole").as_deref() == Some("AXStandardWindow")
+    }
+
+    /// Read a string attribute of an element
+    fn get_string_attribute(&self, element: AXUIElementRef, attribute: &str) -> Option<String> {
+        use core_foundation::base::CFType;
+
         unsafe {
-            let attr_name = CFString::new("AXTitle");
+            let attr_name = CFString::new(attribute);
             let mut value: *mut c_void = ptr::null_mut();
 
             let result =
-                AXUIElementCopyAttributeValue(window, attr_name.as_concrete_TypeRef(), &mut value);
+                AXUIElementCopyAttributeValue(element, attr_name.as_concrete_TypeRef(), &mut value);
 
             if result != K_AX_ERROR_SUCCESS || value.is_null() {
-                return String::new();
+                return None;
             }
 
-            let title = CFString::wrap_under_create_rule(value as CFStringRef);
-            title.to_string()
+            let value = CFType::wrap_under_create_rule(value as _);
+            value.downcast::<CFString>().map(|s| s.to_string())
         }
     }
 
+    /// Get the AX element of an application, from the cache when possible.
+    /// The caller must release the returned element.
+    fn get_app_element(&self, pid: i32) -> AXUIElementRef {
+        let mut cache = APP_CACHE.lock().unwrap();
+
+        let element = match cache.get(&pid) {
+            Some(app) => app.element,
+            None => {
+                let element = unsafe { AXUIElementCreateApplication(pid) };
+                if element.is_null() {
+                    return element;
+                }
+                cache.insert(pid, CachedApp { element, name: None });
+                element
+            }
+        };
+
+        unsafe {
+            core_foundation::base::CFRetain(element as *const c_void);
+        }
+        element
+    }
+
+    /// Get the name of an application
+    fn get_app_name(&self, pid: i32) -> String {
+        if let Some(name) = APP_CACHE.lock().unwrap().get(&pid).and_then(|app| app.name.clone()) {
+            return name;
+        }
+
+        let app_element = self.get_app_element(pid);
+        if app_element.is_null() {
+            return String::new();
+        }
+
+        // Applications expose their name as the title of their AX element
+        let name = self.get_window_title(app_element);
+        unsafe {
+            core_foundation::base::CFRelease(app_element as *const c_void);
+        }
+
+        if let Some(app) = APP_CACHE.lock().unwrap().get_mut(&pid) {
+            app.name = Some(name.clone());
+        }
+        name
+    }
+
     /// Set the position of a window
     fn set_window_position(&self, window: AXUIElementRef, point: CGPoint) -> Result<()> {
         unsafe {
@@ -368,8 +802,128 @@ impl MacOSManager {
         }
     }
 
-    /// Get work area for a display using NSScreen
-    fn get_display_work_area(&self, display_id: CGDirectDisplayID) -> Result<Rect> {
+    /// Move and resize a window element, retrying when the app clamps or
+    /// reverts the change (e.g. iTerm snapping to its cell grid, or Java apps
+    /// applying the size asynchronously)
+    fn apply_frame(&self, window: AXUIElementRef, position: CGPoint, size: CGSize) -> Result<()> {
+        let size_first = self.should_size_first(window, position, size);
+
+        for attempt in 0..=FRAME_RETRIES {
+            if attempt > 0 {
+                std::thread::sleep(std::time::Duration::from_millis(RETRY_DELAY_MS));
+            }
+
+            if size_first {
+                // Shrink first so the window fits on the destination display,
+                // then move it, then resize again in case the move clamped it
+                self.set_window_size(window, size)?;
+                self.set_window_position(window, position)?;
+                self.set_window_size(window, size)?;
+            } else {
+                // Move to target position first, then resize
+                self.set_window_position(window, position)?;
+                self.set_window_size(window, size)?;
+
+                // Set position again to ensure correct placement after resize
+                // (some apps adjust position when resized)
+                self.set_window_position(window, position)?;
+            }
+
+            if self.frame_matches(window, position, size) {
+                return Ok(());
+            }
+        }
+
+        // Apps with a minimum or maximum size never match (e.g. Spotify is
+        // wider than a third of most displays), so center whatever size they
+        // accepted within the target area rather than leaving it at the corner
+        let actual_size = self.get_window_size(window)?;
+        if (actual_size.width - size.width).abs() > FRAME_TOLERANCE
+            || (actual_size.height - size.height).abs() > FRAME_TOLERANCE
+        {
+            let centered = CGPoint {
+                x: (position.x + (size.width - actual_size.width) / 2.0).round(),
+                y: (position.y + (size.height - actual_size.height) / 2.0).round(),
+            };
+            self.set_window_position(window, centered)?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether to resize a window before moving it. macOS clamps a window
+    /// moved to where it would overhang the screen, so when shrinking or
+    /// changing displays the size has to be applied first.
+    fn should_size_first(&self, window: AXUIElementRef, position: CGPoint, size: CGSize) -> bool {
+        let (Ok(current_position), Ok(current_size)) =
+            (self.get_window_position(window), self.get_window_size(window))
+        else {
+            return false;
+        };
+
+        if size.width < current_size.width || size.height < current_size.height {
+            return true;
+        }
+
+        let current_center = CGPoint {
+            x: current_position.x + current_size.width / 2.0,
+            y: current_position.y + current_size.height / 2.0,
+        };
+        let target_center = CGPoint {
+            x: position.x + size.width / 2.0,
+            y: position.y + size.height / 2.0,
+        };
+
+        self.display_id_at(current_center) != self.display_id_at(target_center)
+    }
+
+    /// Get the display containing a point, in global coordinates
+    fn display_id_at(&self, point: CGPoint) -> Option<CGDirectDisplayID> {
+        CGDisplay::active_displays().ok()?.into_iter().find(|&id| {
+            let bounds = CGDisplay::new(id).bounds();
+            let bounds = CGRect {
+                origin: CGPoint {
+                    x: bounds.origin.x,
+                    y: bounds.origin.y,
+                },
+                size: CGSize {
+                    width: bounds.size.width,
+                    height: bounds.size.height,
+                },
+            };
+
+            unsafe { CGRectContainsPoint(bounds, point) }
+        })
+    }
+
+    /// Check whether a window element ended up at the given frame
+    fn frame_matches(&self, window: AXUIElementRef, position: CGPoint, size: CGSize) -> bool {
+        let (Ok(actual_position), Ok(actual_size)) =
+            (self.get_window_position(window), self.get_window_size(window))
+        else {
+            return false;
+        };
+
+        (actual_position.x - position.x).abs() <= FRAME_TOLERANCE
+            && (actual_position.y - position.y).abs() <= FRAME_TOLERANCE
+            && (actual_size.width - size.width).abs() <= FRAME_TOLERANCE
+            && (actual_size.height - size.height).abs() <= FRAME_TOLERANCE
+    }
+
+    /// Get the backing scale factor of a display from its current mode
+    fn get_display_scale(&self, display: &CGDisplay) -> f64 {
+        display
+            .display_mode()
+            .filter(|mode| mode.width() > 0)
+            .map(|mode| mode.pixel_width() as f64 / mode.width() as f64)
+            .unwrap_or(1.0)
+    }
+
+    /// Find the NSScreen for a CG display
+    fn get_screen(
+        &self,
+        display_id: CGDirectDisplayID,
+    ) -> Option<objc2::rc::Retained<objc2_app_kit::NSScreen>> {
         use objc2::msg_send;
         use objc2::runtime::AnyObject;
         use objc2::MainThreadMarker;
@@ -382,56 +936,99 @@ impl MacOSManager {
             let screens = NSScreen::screens(mtm);
             let screen_number_key = NSString::from_str("NSScreenNumber");
 
-            // Get the primary screen height for coordinate conversion
-            // NSScreen uses bottom-left origin, CG/AX uses top-left origin
-            // The first screen in NSScreen.screens() is always the primary screen
-            let primary_screen_height: f64 = screens
-                .iter()
-                .next()
-                .map(|s| s.frame().size.height)
-                .unwrap_or(0.0);
-
-            for screen in screens.iter() {
+            screens.iter().find(|screen| {
                 let device_desc = screen.deviceDescription();
 
                 // Use msg_send for dictionary lookup to avoid type issues
                 let screen_number_obj: *mut AnyObject =
                     msg_send![&*device_desc, objectForKey: &*screen_number_key];
 
-                if !screen_number_obj.is_null() {
-                    let num: u32 = msg_send![screen_number_obj, unsignedIntValue];
-
-                    if num == display_id {
-                        let visible_frame = screen.visibleFrame();
-
-                        // Convert NSScreen coordinates (bottom-left origin) to CG coordinates (top-left origin)
-                        // In NSScreen: y=0 is at bottom of primary screen, positive y goes up
-                        // In CG/AX: y=0 is at top of primary screen, positive y goes down
-                        // CG_y = primary_height - NSScreen_y - height
-                        let cg_y = primary_screen_height
-                            - visible_frame.origin.y
-                            - visible_frame.size.height;
-
-                        return Ok(Rect::new(
-                            visible_frame.origin.x as i32,
-                            cg_y as i32,
-                            visible_frame.size.width as u32,
-                            visible_frame.size.height as u32,
-                        ));
-                    }
+                if screen_number_obj.is_null() {
+                    return false;
                 }
-            }
 
-            // Fallback to display bounds
-            let bounds = CGDisplay::new(display_id).bounds();
-            Ok(Rect::new(
-                bounds.origin.x as i32,
-                bounds.origin.y as i32,
-                bounds.size.width as u32,
-                bounds.size.height as u32,
-            ))
+                let num: u32 = msg_send![screen_number_obj, unsignedIntValue];
+                num == display_id
+            })
         }
     }
+
+    /// Get the user-visible name of a display (e.g. "DELL U2720Q")
+    fn get_display_name(&self, display_id: CGDirectDisplayID) -> Option<String> {
+        let screen = self.get_screen(display_id)?;
+        let name = screen.localizedName().to_string();
+
+        (!name.is_empty()).then_some(name)
+    }
+
+    /// Get the insets of the area not covered by the camera housing (notch).
+    /// Displays without a notch, and macOS versions before 12, have none.
+    fn get_safe_area(&self, display_id: CGDirectDisplayID) -> Insets {
+        use objc2::runtime::NSObjectProtocol;
+
+        let Some(screen) = self.get_screen(display_id) else {
+            return Insets::default();
+        };
+        if !screen.respondsToSelector(objc2::sel!(safeAreaInsets)) {
+            return Insets::default();
+        }
+
+        let insets = screen.safeAreaInsets();
+        Insets {
+            top: insets.top.round() as u32,
+            right: insets.right.round() as u32,
+            bottom: insets.bottom.round() as u32,
+            left: insets.left.round() as u32,
+        }
+    }
+
+    /// Get work area for a display using NSScreen
+    fn get_display_work_area(&self, display_id: CGDirectDisplayID) -> Result<Rect> {
+        let bounds = CGDisplay::new(display_id).bounds();
+        let display_bounds = Rect::new(
+            bounds.origin.x.round() as i32,
+            bounds.origin.y.round() as i32,
+            bounds.size.width.round() as u32,
+            bounds.size.height.round() as u32,
+        );
+
+        let work_area = match self.get_screen(display_id) {
+            Some(screen) => {
+                cocoa_to_cg(screen.frame(), screen.visibleFrame(), display_bounds)
+            }
+            // Fallback to display bounds
+            None => display_bounds,
+        };
+
+        Ok(work_area)
+    }
+}
+
+/// Convert an NSScreen visible frame to CG/AX coordinates.
+///
+/// NSScreen uses a bottom-left origin with y going up, CG/AX a top-left origin with
+/// y going down, and both are relative to the primary display. Rather than flipping
+/// around the primary screen's height (which breaks when screens sit above, below
+/// or to the left of the primary and get negative coordinates), the visible frame is
+/// expressed as insets from its screen's frame and applied to the display's CG bounds,
+/// which are already in the right space for any arrangement.
+fn cocoa_to_cg(
+    screen_frame: objc2_foundation::NSRect,
+    visible_frame: objc2_foundation::NSRect,
+    display_bounds: Rect,
+) -> Rect {
+    let left = visible_frame.origin.x - screen_frame.origin.x;
+    // The top inset is the gap between the top edges, which are at max y in NSScreen
+    let top = (screen_frame.origin.y + screen_frame.size.height)
+        - (visible_frame.origin.y + visible_frame.size.height);
+
+    // Round rather than truncate, which would pull negative coordinates towards zero
+    Rect::new(
+        display_bounds.x + left.round() as i32,
+        display_bounds.y + top.round() as i32,
+        visible_frame.size.width.round() as u32,
+        visible_frame.size.height.round() as u32,
+    )
 }
 
 impl WindowManagerTrait for MacOSManager {
@@ -439,32 +1036,33 @@ impl WindowManagerTrait for MacOSManager {
         let pid = self.get_frontmost_app_pid()?;
         let window_element = self.get_focused_window_element(pid)?;
 
-        let position = self.get_window_position(window_element)?;
-        let size = self.get_window_size(window_element)?;
-        let title = self.get_window_title(window_element);
+        // Dialogs, sheets and panels are positioned by their app
+        if !self.is_standard_window(window_element) {
+            unsafe {
+                core_foundation::base::CFRelease(window_element as *const c_void);
+            }
+            return Err(WindowManagerError::NoFocusedWindow);
+        }
+
+        let window = self.window_from_element(pid, window_element);
 
         unsafe {
             core_foundation::base::CFRelease(window_element as *const c_void);
         }
 
-        Ok(Window {
-            handle: WindowHandle::MacOS(pid as u32),
-            title,
-            frame: Rect::new(
-                position.x as i32,
-                position.y as i32,
-                size.width as u32,
-                size.height as u32,
-            ),
-        })
+        window
     }
 
     fn set_window_frame(&self, window: &Window, frame: Rect) -> Result<()> {
-        let pid = match window.handle {
-            WindowHandle::MacOS(p) => p as i32,
+        let (pid, window_id) = match window.handle {
+            WindowHandle::MacOS { pid, window_id } => (pid as i32, window_id),
         };
 
-        let window_element = self.get_focused_window_element(pid)?;
+        let window_element = self.get_window_element(pid, window_id)?;
+
+        // Minimized and full screen windows ignore position and size changes
+        self.unminimize_window(window_element)?;
+        self.exit_fullscreen(window_element)?;
 
         let target_position = CGPoint {
             x: frame.x as f64,
@@ -475,54 +1073,41 @@ impl WindowManagerTrait for MacOSManager {
             height: frame.height as f64,
         };
 
-        // Always use move-then-size to avoid window spanning displays
-        // Move to target position first, then resize
-        self.set_window_position(window_element, target_position)?;
-        self.set_window_size(window_element, target_size)?;
+        // Electron and Chromium apps animate AX changes while
+        // AXEnhancedUserInterface is on (VoiceOver and some utilities turn it on)
+        // and land at the wrong size, so turn it off while moving the window
+        let app_element = self.get_app_element(pid);
+        let enhanced_ui = !app_element.is_null()
+            && self.get_bool_attribute(app_element, "AXEnhancedUserInterface");
+        if enhanced_ui {
+            let _ = self.set_bool_attribute(app_element, "AXEnhancedUserInterface", false);
+        }
 
-        // Set position again to ensure correct placement after resize
-        // (some apps adjust position when resized)
-        self.set_window_position(window_element, target_position)?;
+        let result = self.apply_frame(window_element, target_position, target_size);
+
+        if enhanced_ui {
+            let _ = self.set_bool_attribute(app_element, "AXEnhancedUserInterface", true);
+        }
 
         unsafe {
+            if !app_element.is_null() {
+                core_foundation::base::CFRelease(app_element as *const c_void);
+            }
             core_foundation::base::CFRelease(window_element as *const c_void);
         }
 
-        Ok(())
+        result
     }
 
     fn get_current_display(&self) -> Result<Display> {
         // Get the focused window position to determine which display it's on
         let window = self.get_focused_window()?;
-        let window_center = CGPoint {
-            x: window.frame.x as f64 + (window.frame.width / 2) as f64,
-            y: window.frame.y as f64 + (window.frame.height / 2) as f64,
-        };
-
         let displays = self.get_all_displays()?;
 
-        // Find the display containing the window center
-        for display in &displays {
-            let bounds = CGRect {
-                origin: CGPoint {
-                    x: display.bounds.x as f64,
-                    y: display.bounds.y as f64,
-                },
-                size: CGSize {
-                    width: display.bounds.width as f64,
-                    height: display.bounds.height as f64,
-                },
-            };
-
-            if unsafe { CGRectContainsPoint(bounds, window_center) } {
-                return Ok(display.clone());
-            }
-        }
-
         // Fallback to primary display
-        displays
-            .into_iter()
-            .find(|d| d.is_primary)
+        Display::for_frame(&displays, &window.frame)
+            .or_else(|| displays.iter().find(|d| d.is_primary))
+            .cloned()
             .ok_or(WindowManagerError::DisplayError)
     }
 
@@ -548,21 +1133,161 @@ impl WindowManagerTrait for MacOSManager {
                 let work_area = self.get_display_work_area(display_id)?;
 
                 displays.push(Display {
-                    name: format!("Display {}", i + 1),
+                    name: self
+                        .get_display_name(display_id)
+                        .unwrap_or_else(|| format!("Display {}", i + 1)),
                     bounds: Rect::new(
-                        bounds.origin.x as i32,
-                        bounds.origin.y as i32,
+                        bounds.origin.x.round() as i32,
+                        bounds.origin.y.round() as i32,
                         bounds.size.width as u32,
                         bounds.size.height as u32,
                     ),
                     work_area,
                     is_primary: display_id == main_display,
+                    scale_factor: self.get_display_scale(&cg_display),
+                    safe_area: self.get_safe_area(display_id),
                 });
             }
 
             Ok(displays)
         }
     }
+
+    fn get_visible_windows(&self) -> Result<Vec<Window>> {
+        use core_foundation::base::CFType;
+
+        unsafe {
+            let options = kCGWindowListOptionOnScreenOnly | kCGWindowListExcludeDesktopElements;
+            let window_list = CGWindowListCopyWindowInfo(options, kCGNullWindowID);
+
+            if window_list.is_null() {
+                return Err(WindowManagerError::WindowListError);
+            }
+
+            let windows: CFArray<CFType> = CFArray::wrap_under_create_rule(window_list as _);
+            let layer_key = CFString::new("kCGWindowLayer");
+            let pid_key = CFString::new("kCGWindowOwnerPID");
+            let name_key = CFString::new("kCGWindowOwnerName");
+            let bounds_key = CFString::new("kCGWindowBounds");
+            let number_key = CFString::new("kCGWindowNumber");
+            let title_key = CFString::new("kCGWindowName");
+
+            let mut result = Vec::new();
+
+            for i in 0..windows.len() {
+                let Some(window_ref) = windows.get(i) else {
+                    continue;
+                };
+
+                let window_dict: CFDictionary<CFString, CFType> =
+                    CFDictionary::wrap_under_get_rule(window_ref.as_CFTypeRef() as _);
+
+                // Layer 0 is regular windows
+                let layer = window_dict
+                    .find(&layer_key)
+                    .and_then(|v| CFNumber::wrap_under_get_rule(v.as_CFTypeRef() as _).to_i32());
+                if layer != Some(0) {
+                    continue;
+                }
+
+                let Some(pid) = window_dict
+                    .find(&pid_key)
+                    .and_then(|v| CFNumber::wrap_under_get_rule(v.as_CFTypeRef() as _).to_i32())
+                else {
+                    continue;
+                };
+
+                // Skip our own app and system UI
+                let owner = window_dict
+                    .find(&name_key)
+                    .map(|v| CFString::wrap_under_get_rule(v.as_CFTypeRef() as _).to_string())
+                    .unwrap_or_default();
+                if is_excluded_owner(&owner) {
+                    continue;
+                }
+
+                let Some(bounds_ref) = window_dict.find(&bounds_key) else {
+                    continue;
+                };
+                let bounds_dict: CFDictionary<CFString, CFType> =
+                    CFDictionary::wrap_under_get_rule(bounds_ref.as_CFTypeRef() as _);
+                let bound = |key: &str| {
+                    bounds_dict
+                        .find(&CFString::new(key))
+                        .and_then(|v| CFNumber::wrap_under_get_rule(v.as_CFTypeRef() as _).to_f64())
+                };
+
+                let (Some(x), Some(y), Some(width), Some(height)) =
+                    (bound("X"), bound("Y"), bound("Width"), bound("Height"))
+                else {
+                    continue;
+                };
+
+                let window_id = window_dict
+                    .find(&number_key)
+                    .and_then(|v| CFNumber::wrap_under_get_rule(v.as_CFTypeRef() as _).to_i64())
+                    .unwrap_or(0) as u32;
+
+                // Only available with the screen recording permission
+                let title = window_dict
+                    .find(&title_key)
+                    .map(|v| CFString::wrap_under_get_rule(v.as_CFTypeRef() as _).to_string())
+                    .unwrap_or_default();
+
+                result.push(Window {
+                    handle: WindowHandle::MacOS {
+                        pid: pid as u32,
+                        window_id,
+                    },
+                    app: owner,
+                    title,
+                    frame: Rect::new(x as i32, y as i32, width as u32, height as u32),
+                });
+            }
+
+            Ok(result)
+        }
+    }
+
+    fn get_app_windows(&self, window: &Window) -> Result<Vec<Window>> {
+        let pid = match window.handle {
+            WindowHandle::MacOS { pid, .. } => pid as i32,
+        };
+
+        let mut windows = Vec::new();
+
+        for element in self.get_app_window_elements(pid)? {
+            if self.is_standard_window(element) && !self.is_window_minimized(element) {
+                if let Ok(window) = self.window_from_element(pid, element) {
+                    windows.push(window);
+                }
+            }
+
+            unsafe {
+                core_foundation::base::CFRelease(element as *const c_void);
+            }
+        }
+
+        Ok(windows)
+    }
+
+    fn move_cursor(&self, x: i32, y: i32) -> Result<()> {
+        let point = CGPoint {
+            x: x as f64,
+            y: y as f64,
+        };
+
+        unsafe {
+            if CGWarpMouseCursorPosition(point) != 0 {
+                return Err(WindowManagerError::MoveError("Failed to move cursor".into()));
+            }
+
+            // Warping suppresses mouse movement briefly; reconnect immediately
+            CGAssociateMouseAndMouseCursorPosition(true);
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for MacOSManager {
@@ -570,3 +1295,56 @@ impl Default for MacOSManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use objc2_foundation::{NSPoint, NSRect, NSSize};
+
+    fn ns_rect(x: f64, y: f64, width: f64, height: f64) -> NSRect {
+        NSRect::new(NSPoint::new(x, y), NSSize::new(width, height))
+    }
+
+    // A 1440x900 primary display at the origin, with the menu bar (25) at the
+    // top and the dock (70) at the bottom
+    #[test]
+    fn primary_display() {
+        let work_area = cocoa_to_cg(
+            ns_rect(0.0, 0.0, 1440.0, 900.0),
+            ns_rect(0.0, 70.0, 1440.0, 805.0),
+            Rect::new(0, 0, 1440, 900),
+        );
+        assert_eq!(work_area, Rect::new(0, 25, 1440, 805));
+    }
+
+    #[test]
+    fn display_left_of_primary() {
+        let work_area = cocoa_to_cg(
+            ns_rect(-1920.0, 0.0, 1920.0, 1080.0),
+            ns_rect(-1920.0, 0.0, 1920.0, 1055.0),
+            Rect::new(-1920, -180, 1920, 1080),
+        );
+        assert_eq!(work_area, Rect::new(-1920, -155, 1920, 1055));
+    }
+
+    #[test]
+    fn display_above_primary() {
+        let work_area = cocoa_to_cg(
+            ns_rect(-240.0, 900.0, 1920.0, 1080.0),
+            ns_rect(-240.0, 970.0, 1920.0, 985.0),
+            Rect::new(-240, -1080, 1920, 1080),
+        );
+        assert_eq!(work_area, Rect::new(-240, -1055, 1920, 985));
+    }
+
+    // With the dock on the left
+    #[test]
+    fn display_below_primary() {
+        let work_area = cocoa_to_cg(
+            ns_rect(200.0, -800.0, 1280.0, 800.0),
+            ns_rect(260.0, -800.0, 1220.0, 775.0),
+            Rect::new(200, 900, 1280, 800),
+        );
+        assert_eq!(work_area, Rect::new(260, 925, 1220, 775));
+    }
+}
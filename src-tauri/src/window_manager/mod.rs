@@ -1,3 +1,4 @@
+mod affinity;
 mod types;
 
 #[cfg(target_os = "windows")]
@@ -11,25 +12,105 @@ mod linux;
 
 pub use types::*;
 
+use affinity::AffinityStore;
+use crate::config::{self, AppRule, Config, DisplayOverride, DisplaySplit, NoFocusFallback};
+use crate::i18n::{tr, tr_args};
+use crate::notify;
+use crate::overlay;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{LazyLock, Mutex};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum WindowManagerError {
-    #[error("Failed to get focused window")]
+    #[error("{}", tr("error.no_focused_window"))]
     NoFocusedWindow,
 
-    #[error("Failed to get display information")]
+    #[error("{}", tr("error.display"))]
     DisplayError,
 
-    #[error("Failed to move window: {0}")]
+    #[error("{}", tr_args("error.move", &[.0]))]
     MoveError(String),
 
-    #[error("No adjacent display in that direction")]
+    #[error("{}", tr("error.no_adjacent_display"))]
     NoAdjacentDisplay,
+
+    #[error("{}", tr("error.no_previous_frame"))]
+    NoPreviousFrame,
+
+    #[error("{}", tr("error.no_previous_window"))]
+    NoPreviousWindow,
+
+    #[error("{}", tr("error.window_list"))]
+    WindowListError,
+
+    #[error("{}", tr("error.no_free_space"))]
+    NoFreeSpace,
+
+    #[error("{}", tr_args("error.no_such_display", &[.0]))]
+    NoSuchDisplay(usize),
+
+    #[error("{}", tr("error.display_excluded"))]
+    DisplayExcluded,
+
+    #[error("{}", tr("error.platform_not_supported"))]
+    PlatformNotSupported,
+
+    #[cfg(target_os = "macos")]
+    #[error("{}", tr("error.full_screen"))]
+    FullScreen,
+
+    #[cfg(target_os = "windows")]
+    #[error("{}", tr("error.elevated"))]
+    Elevated,
+
+    #[error("{}", tr("error.window_ignored"))]
+    WindowIgnored,
+
+    #[error("{}", tr_args("error.app_excluded", &[.0]))]
+    AppExcluded(String),
+
+    #[cfg(target_os = "linux")]
+    #[error("{}", tr_args("error.x11", &[.0]))]
+    X11(String),
+
+    #[cfg(target_os = "linux")]
+    #[error("{}", tr_args("error.dbus", &[.0]))]
+    DBus(String),
 }
 
 pub type Result<T> = std::result::Result<T, WindowManagerError>;
 
+// How far (in pixels) a window may be off a snapped frame and still count as snapped
+// there. Some apps (e.g. terminals) round their size to whole character cells.
+const FRAME_TOLERANCE: i32 = 16;
+
+// Offset between successive windows when cascading
+const CASCADE_OFFSET: u32 = 30;
+
+// How many recently focused windows to remember
+const FOCUS_HISTORY_LEN: usize = 8;
+
+// Recently focused windows, most recent first, used by mirror
+static FOCUS_HISTORY: Mutex<VecDeque<Window>> = Mutex::new(VecDeque::new());
+
+// Position each window was last snapped to and the frame it got, used to keep the
+// position when changing displays and to re-snap after display changes
+static LAST_POSITION: LazyLock<Mutex<HashMap<WindowHandle, (SnapPosition, Rect)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// Windows the user turned snapping off for, e.g. to leave them to the OS's own
+// snapping
+static IGNORED_WINDOWS: LazyLock<Mutex<HashSet<WindowHandle>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+// Window most recently snapped, used when no window is focused
+static LAST_SNAPPED: Mutex<Option<WindowHandle>> = Mutex::new(None);
+
+// Frame each window had before it was last snapped, used by restore
+static FRAME_HISTORY: LazyLock<Mutex<HashMap<WindowHandle, Rect>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
 /// Trait defining the platform-specific window management operations.
 pub trait WindowManagerTrait: Send + Sync {
     /// Get the currently focused window.
@@ -43,88 +124,1133 @@ pub trait WindowManagerTrait: Send + Sync {
 
     /// Get all available displays.
     fn get_all_displays(&self) -> Result<Vec<Display>>;
+
+    /// Get all visible, non-minimized application windows, front to back.
+    fn get_visible_windows(&self) -> Result<Vec<Window>>;
+
+    /// Get all non-minimized windows belonging to the same application as `window`,
+    /// front to back.
+    fn get_app_windows(&self, window: &Window) -> Result<Vec<Window>>;
+
+    /// Move the mouse cursor to the given screen coordinates.
+    fn move_cursor(&self, x: i32, y: i32) -> Result<()>;
 }
 
-/// The main WindowManager struct that delegates to platform-specific implementations.
-pub struct WindowManager {
-    #[cfg(target_os = "windows")]
-    inner: windows::WindowsManager,
+// A display as arranged for the user
+struct ArrangedDisplay {
+    // Physical number, shared by the parts of a split display
+    number: usize,
+    display: Display,
+    excluded: bool,
+}
 
-    #[cfg(target_os = "macos")]
-    inner: macos::MacOSManager,
+// The window manager of the platform being built for
+#[cfg(target_os = "windows")]
+type PlatformManager = windows::WindowsManager;
 
-    #[cfg(target_os = "linux")]
-    inner: linux::LinuxManager,
+#[cfg(target_os = "macos")]
+type PlatformManager = macos::MacOSManager;
+
+#[cfg(target_os = "linux")]
+type PlatformManager = linux::LinuxManager;
+
+/// The main WindowManager struct that delegates to platform-specific implementations.
+pub struct WindowManager {
+    options: LayoutOptions,
+    cursor_follows_window: bool,
+    display_overrides: Vec<DisplayOverride>,
+    display_splits: Vec<DisplaySplit>,
+    excluded_displays: Vec<DisplaySelector>,
+    global_positions: Vec<SnapPosition>,
+    avoid_notch: bool,
+    restore_on_reconnect: bool,
+    restore_layout_on_startup: bool,
+    no_focus_fallback: NoFocusFallback,
+    excluded_apps: Vec<String>,
+    inner: PlatformManager,
 }
 
 impl WindowManager {
     pub fn new() -> Self {
+        let inner = PlatformManager::new();
+        let config = Self::apply_display_profile(Config::load().unwrap_or_default(), &inner);
+
+        #[cfg(target_os = "windows")]
+        let inner = inner
+            .activate_on_snap(config.activate_on_snap)
+            .leave_os_snap_groups(config.leave_os_snap_groups);
+
         Self {
-            #[cfg(target_os = "windows")]
-            inner: windows::WindowsManager::new(),
+            options: config.layout_options(),
+            cursor_follows_window: config.cursor_follows_window,
+            display_overrides: config.display_overrides,
+            display_splits: config.display_splits,
+            excluded_displays: config.excluded_displays,
+            global_positions: config.global_positions,
+            avoid_notch: config.avoid_notch,
+            restore_on_reconnect: config.restore_on_reconnect,
+            restore_layout_on_startup: config.restore_layout_on_startup,
+            no_focus_fallback: config.no_focus_fallback,
+            excluded_apps: config.excluded_apps,
+            inner,
+        }
+    }
+
+    /// Get the focused window and remember it in the focus history.
+    fn focused_window(&self) -> Result<Window> {
+        let window = self.target_window()?;
+        self.check_snappable(&window)?;
+        Self::remember_focus(window.clone());
+
+        Ok(window)
+    }
+
+    /// Put a window at the front of the focus history.
+    pub fn remember_focus(window: Window) {
+        let mut history = FOCUS_HISTORY.lock().unwrap();
+        history.retain(|w| w.handle != window.handle);
+        history.push_front(window);
+        history.truncate(FOCUS_HISTORY_LEN);
+    }
+
+    /// Refuse windows that snapping is turned off for, and windows of apps in
+    /// `excluded_apps`.
+    fn check_snappable(&self, window: &Window) -> Result<()> {
+        if IGNORED_WINDOWS.lock().unwrap().contains(&window.handle) {
+            return Err(WindowManagerError::WindowIgnored);
+        }
+        if self.excluded_apps.iter().any(|app| config::same_app(app, &window.app)) {
+            return Err(WindowManagerError::AppExcluded(window.app.clone()));
+        }
+        Ok(())
+    }
+
+    fn is_snappable(&self, window: &Window) -> bool {
+        self.check_snappable(window).is_ok()
+    }
+
+    /// The focused window, or the configured fallback when none is focused,
+    /// as happens right after using the tray.
+    fn target_window(&self) -> Result<Window> {
+        match self.inner.get_focused_window() {
+            Err(WindowManagerError::NoFocusedWindow) => self.fallback_window(),
+            result => result,
+        }
+    }
+
+    /// The window to act on when none is focused, per `no_focus_fallback`.
+    fn fallback_window(&self) -> Result<Window> {
+        match self.no_focus_fallback {
+            NoFocusFallback::Error => Err(WindowManagerError::NoFocusedWindow),
+            NoFocusFallback::Notify => {
+                notify::show_error(&tr("error.no_focused_window_hint"));
+                Err(WindowManagerError::NoFocusedWindow)
+            }
+            NoFocusFallback::LastSnapped => {
+                let handle = LAST_SNAPPED
+                    .lock()
+                    .unwrap()
+                    .ok_or(WindowManagerError::NoFocusedWindow)?;
+                self.inner
+                    .get_visible_windows()?
+                    .into_iter()
+                    .find(|w| w.handle == handle)
+                    .ok_or(WindowManagerError::NoFocusedWindow)
+            }
+            NoFocusFallback::Topmost => self
+                .inner
+                .get_visible_windows()?
+                .into_iter()
+                .next()
+                .ok_or(WindowManagerError::NoFocusedWindow),
+        }
+    }
+
+    /// The focused window as it is, without any fallback or exclusions, for
+    /// watching which window is frontmost. Doesn't read the config, so it is
+    /// cheap enough to poll.
+    pub fn frontmost_window() -> Result<Window> {
+        PlatformManager::new().get_focused_window()
+    }
+
+    /// `config` with the profile for the connected displays applied, for
+    /// settings read outside the window manager, like custom positions and
+    /// app rules.
+    pub fn with_display_profile(config: Config) -> Config {
+        Self::apply_display_profile(config, &PlatformManager::new())
+    }
+
+    /// Switch to the profile for the connected displays, if one is configured.
+    fn apply_display_profile(config: Config, inner: &PlatformManager) -> Config {
+        if config.profiles.is_empty() {
+            return config;
+        }
+
+        let displays = inner.get_all_displays().unwrap_or_default();
+        config.with_profile(&displays)
+    }
 
-            #[cfg(target_os = "macos")]
-            inner: macos::MacOSManager::new(),
+    /// Turn snapping off for the focused window, or back on if it was off.
+    /// Lasts until the app quits. Returns whether the window is now ignored.
+    pub fn toggle_ignore_focused(&self) -> Result<bool> {
+        let window = self.inner.get_focused_window()?;
+        let mut ignored = IGNORED_WINDOWS.lock().unwrap();
 
-            #[cfg(target_os = "linux")]
-            inner: linux::LinuxManager::new(),
+        if ignored.remove(&window.handle) {
+            Ok(false)
+        } else {
+            ignored.insert(window.handle);
+            Ok(true)
         }
     }
 
     /// Snap the focused window to the specified position.
     pub fn snap_to(&self, position: SnapPosition) -> Result<()> {
-        let window = self.inner.get_focused_window()?;
-        let display = self.inner.get_current_display()?;
-        let frame = position.calculate_frame(&display.work_area);
+        let window = self.focused_window()?;
+        let display = if self.global_positions.contains(&position) {
+            self.combined_display()?
+        } else {
+            self.current_display()?
+        };
+
+        self.snap_window(&window, position, &display)?;
+        overlay::show_snap(position, &display);
+        Ok(())
+    }
+
+    /// Snap a window to the specified position within the given display.
+    fn snap_window(&self, window: &Window, position: SnapPosition, display: &Display) -> Result<()> {
+        let frame = position.calculate_frame(&window.frame, &display.work_area, &self.options);
+
+        // Maximize acts as a toggle: if the window already fills the work area,
+        // put it back where it was before it was maximized
+        if matches!(position, SnapPosition::Maximize)
+            && window.frame.is_close_to(&frame, FRAME_TOLERANCE)
+        {
+            let previous = FRAME_HISTORY.lock().unwrap().remove(&window.handle);
+            if let Some(previous) = previous {
+                LAST_POSITION.lock().unwrap().remove(&window.handle);
+                return self.inner.set_window_frame(window, previous);
+            }
+        }
 
+        self.record_snap(window, position, frame, display);
+        self.apply_frame(window, frame)
+    }
+
+    /// Remember where a window is being snapped, for keeping its position across
+    /// display moves and changes.
+    fn record_snap(&self, window: &Window, position: SnapPosition, frame: Rect, display: &Display) {
+        LAST_POSITION
+            .lock()
+            .unwrap()
+            .insert(window.handle, (position, frame));
+        *LAST_SNAPPED.lock().unwrap() = Some(window.handle);
+
+        if self.restore_on_reconnect || self.restore_layout_on_startup {
+            AffinityStore::shared().record(window, display, position);
+        }
+    }
+
+    /// Resize the focused window to a fixed-size preset on its current display.
+    pub fn snap_to_preset(&self, preset: &PresetSize) -> Result<()> {
+        let window = self.focused_window()?;
+        let display = self.current_display()?;
+        let frame = preset.calculate_frame(&display.work_area);
+
+        self.apply_frame(&window, frame)
+    }
+
+    /// Snap the focused window to a custom position on the current display.
+    pub fn snap_to_custom(&self, custom: &CustomFrame) -> Result<()> {
+        let window = self.focused_window()?;
+        let display = self.current_display()?;
+
+        self.snap_window(&window, custom.position(), &display)
+    }
+
+    /// Grow or shrink the focused window by the configured step.
+    pub fn resize(&self, action: ResizeAction) -> Result<()> {
+        let window = self.focused_window()?;
+        let display = self.current_display()?;
+        let frame = action.calculate_frame(
+            &window.frame,
+            &display.work_area,
+            self.options.resize_step,
+            self.options.resize_anchor,
+        );
+
+        // Incremental resizes don't overwrite the restore point
         self.inner.set_window_frame(&window, frame)
     }
 
-    /// Move the focused window to the next or previous display (maximized).
-    pub fn move_to_display(&self, direction: DisplayDirection) -> Result<()> {
-        let window = self.inner.get_focused_window()?;
-        let current_display = self.inner.get_current_display()?;
-        let mut displays = self.inner.get_all_displays()?;
+    /// Snap the focused window into the largest part of its display that isn't
+    /// covered by other windows.
+    pub fn fill(&self) -> Result<()> {
+        let window = self.focused_window()?;
+        let display = self.current_display()?;
+        let area = self.options.margin_area(&display.work_area);
+
+        let obstacles: Vec<Rect> = self
+            .inner
+            .get_visible_windows()?
+            .into_iter()
+            .filter(|w| w.handle != window.handle)
+            .map(|w| w.frame)
+            .collect();
+
+        let frame = largest_free_rect(&area, &obstacles).ok_or(WindowManagerError::NoFreeSpace)?;
+
+        self.apply_frame(&window, frame)
+    }
+
+    /// Snap the focused window to exactly match the frame of the previously
+    /// focused window.
+    pub fn mirror_previous(&self) -> Result<()> {
+        let window = self.focused_window()?;
+        let visible = self.inner.get_visible_windows().unwrap_or_default();
+
+        // The most recently focused window that is still open, with its current
+        // frame in case it moved since it was focused
+        let previous = FOCUS_HISTORY
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|w| w.handle != window.handle)
+            .find_map(|previous| visible.iter().find(|w| w.handle == previous.handle))
+            .map(|w| w.frame);
+
+        // Nothing recorded yet: fall back to the next window in z-order
+        let frame = match previous {
+            Some(frame) => frame,
+            None => visible
+                .iter()
+                .find(|w| w.handle != window.handle)
+                .map(|w| w.frame)
+                .ok_or(WindowManagerError::NoPreviousWindow)?,
+        };
+
+        self.apply_frame(&window, frame)
+    }
+
+    /// Arrange all windows of the focused app in an offset cascade on the current
+    /// display, keeping their stacking order.
+    pub fn cascade_app_windows(&self) -> Result<()> {
+        let window = self.focused_window()?;
+        let display = self.current_display()?;
+        let area = self.options.margin_area(&display.work_area);
+
+        // Back to front, so the frontmost window ends up furthest down the cascade
+        let mut windows = self.inner.get_app_windows(&window)?;
+        windows.retain(|w| self.is_snappable(w));
+        windows.reverse();
+
+        // Shrink the offset for large cascades so windows stay at least half size
+        let steps = windows.len().saturating_sub(1) as u32;
+        let offset = if steps == 0 {
+            0
+        } else {
+            CASCADE_OFFSET.min(area.width / 2 / steps).min(area.height / 2 / steps)
+        };
+        let width = area.width - offset * steps;
+        let height = area.height - offset * steps;
+
+        for (i, w) in windows.iter().enumerate() {
+            let shift = (offset * i as u32) as i32;
+            let frame = Rect::new(area.x + shift, area.y + shift, width, height);
+            self.apply_frame(w, frame)?;
+        }
+
+        Ok(())
+    }
+
+    /// Snap the frontmost window of the app with the given name, which doesn't
+    /// need to be focused.
+    pub fn snap_app(&self, app: &str, position: SnapPosition) -> Result<()> {
+        let window = self
+            .inner
+            .get_visible_windows()?
+            .into_iter()
+            .find(|w| config::same_app(&w.app, app))
+            .ok_or(WindowManagerError::WindowListError)?;
+        self.check_snappable(&window)?;
+
+        let displays = self.numbered_displays()?;
+        let display = match Display::for_frame(&displays, &window.frame) {
+            Some(display) => display.clone(),
+            None => self.current_display()?,
+        };
+
+        self.snap_window(&window, position, &display)
+    }
+
+    /// Get the topmost window under a point, in screen coordinates.
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    pub fn window_at(&self, x: i32, y: i32) -> Result<Window> {
+        let window = self
+            .inner
+            .get_visible_windows()?
+            .into_iter()
+            .find(|w| w.frame.contains(x, y))
+            .ok_or(WindowManagerError::WindowListError)?;
+        self.check_snappable(&window)?;
+
+        Ok(window)
+    }
+
+    /// Move a window to an exact frame, without snapping or recording history
+    /// (e.g. while the user drags it).
+    #[cfg_attr(target_os = "linux", allow(dead_code))]
+    pub fn set_frame(&self, window: &Window, frame: Rect) -> Result<()> {
+        self.inner.set_window_frame(window, frame)
+    }
+
+    /// Get the titles of the focused app's windows, front to back.
+    pub fn app_window_titles(&self) -> Result<Vec<String>> {
+        let window = self.focused_window()?;
+        let windows = self.inner.get_app_windows(&window)?;
+
+        Ok(windows.into_iter().map(|w| w.title).collect())
+    }
+
+    /// Snap one window of the focused app to a position on the display it is
+    /// on, without having to bring it to the front first.
+    pub fn snap_app_window(&self, position: SnapPosition, selector: &WindowSelector) -> Result<()> {
+        let window = self.focused_window()?;
+        let windows = self.inner.get_app_windows(&window)?;
+        let target = selector
+            .select(&windows)
+            .ok_or(WindowManagerError::WindowListError)?;
+        self.check_snappable(target)?;
+
+        // The chosen window may be on another display than the focused one
+        let displays = self.numbered_displays()?;
+        let display = match Display::for_frame(&displays, &target.frame) {
+            Some(display) => display.clone(),
+            None => self.current_display()?,
+        };
+
+        self.snap_window(target, position, &display)
+    }
+
+    /// Get every visible window of every app, front to back, for picking one
+    /// to snap without focusing it first.
+    pub fn visible_windows(&self) -> Result<Vec<Window>> {
+        let mut windows = self.inner.get_visible_windows()?;
+        windows.retain(|w| self.is_snappable(w));
+
+        Ok(windows)
+    }
+
+    /// Snap a window picked from [`visible_windows`](Self::visible_windows) to
+    /// a position on the display it is on.
+    pub fn snap_window_by_handle(&self, handle: WindowHandle, position: SnapPosition) -> Result<()> {
+        let window = self.window_by_handle(&handle)?;
+        self.snap_window_in_place(&window, position)
+    }
+
+    /// Get a visible window by its handle, if it can be snapped.
+    pub fn window_by_handle(&self, handle: &WindowHandle) -> Result<Window> {
+        let window = self
+            .inner
+            .get_visible_windows()?
+            .into_iter()
+            .find(|w| w.handle == *handle)
+            .ok_or(WindowManagerError::WindowListError)?;
+        self.check_snappable(&window)?;
+
+        Ok(window)
+    }
+
+    /// Snap a window of any app, chosen by its 1-based place in the front-to-back
+    /// order or by title, to a position on the display it is on.
+    pub fn snap_visible_window(&self, position: SnapPosition, selector: &WindowSelector) -> Result<()> {
+        let windows = self.inner.get_visible_windows()?;
+        let target = selector
+            .select(&windows)
+            .ok_or(WindowManagerError::WindowListError)?;
+
+        self.snap_window_in_place(target, position)
+    }
+
+    /// Snap a window to the position of the first rule for its app, on the
+    /// rule's display if it has one. Returns whether there was a rule.
+    pub fn apply_app_rule(&self, window: &Window, rules: &[AppRule]) -> Result<bool> {
+        let Some(rule) = rules.iter().find(|r| r.matches(&window.app)) else {
+            return Ok(false);
+        };
+        self.check_snappable(window)?;
+
+        let display = match rule.display {
+            Some(number) => self.display_by_number(number)?,
+            None => Display::for_frame(&self.numbered_displays()?, &window.frame)
+                .cloned()
+                .ok_or(WindowManagerError::DisplayError)?,
+        };
+
+        self.snap_window(window, rule.position, &display)?;
+        Ok(true)
+    }
+
+    /// Apply `app_rules` to every visible window. Returns how many windows
+    /// were snapped.
+    pub fn apply_app_rules(&self, rules: &[AppRule]) -> Result<usize> {
+        // Keep going if one window refuses to move, but report the first failure
+        let mut count = 0;
+        let mut first_error = None;
+        for window in self.visible_windows()? {
+            match self.apply_app_rule(&window, rules) {
+                Ok(true) => count += 1,
+                Ok(false) => {}
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(count),
+        }
+    }
+
+    /// Snap a window to a position on the display it is on, which may not be
+    /// the one with the focused window.
+    fn snap_window_in_place(&self, window: &Window, position: SnapPosition) -> Result<()> {
+        self.check_snappable(window)?;
+
+        let displays = self.numbered_displays()?;
+        let display = match Display::for_frame(&displays, &window.frame) {
+            Some(display) => display.clone(),
+            None => self.current_display()?,
+        };
 
+        self.snap_window(window, position, &display)
+    }
+
+    /// Snap every window of the focused app to the same position on the
+    /// current display.
+    pub fn snap_all_app_windows(&self, position: SnapPosition) -> Result<()> {
+        let window = self.focused_window()?;
+        let display = self.current_display()?;
+
+        // Back to front, so the focused window stays on top
+        let mut windows = self.inner.get_app_windows(&window)?;
+        windows.retain(|w| self.is_snappable(w));
+        windows.reverse();
+
+        // Keep going if one window refuses to move, but report the first failure
+        let mut first_error = None;
+        for w in &windows {
+            if let Err(e) = self.snap_window(w, position, &display) {
+                first_error.get_or_insert(e);
+            }
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Move the focused window back to the frame it had before it was last snapped.
+    pub fn restore(&self) -> Result<()> {
+        let window = self.focused_window()?;
+        let previous = FRAME_HISTORY
+            .lock()
+            .unwrap()
+            .remove(&window.handle)
+            .ok_or(WindowManagerError::NoPreviousFrame)?;
+
+        LAST_POSITION.lock().unwrap().remove(&window.handle);
+        self.inner.set_window_frame(&window, previous)
+    }
+
+    /// Record the window's current frame in the history, then move it.
+    fn apply_frame(&self, window: &Window, frame: Rect) -> Result<()> {
+        FRAME_HISTORY
+            .lock()
+            .unwrap()
+            .insert(window.handle, window.frame);
+
+        self.inner.set_window_frame(window, frame)
+    }
+
+    /// Get all displays sorted left-to-right, top-to-bottom, along with the index
+    /// of the given display in that order. Fails if the given display is excluded.
+    fn sorted_displays(&self, current_display: &Display) -> Result<(Vec<Display>, usize)> {
+        let arranged = self.arranged_displays()?;
+
+        // Look in the full list, as the current display may be excluded
+        let arranged_idx = Self::display_index(&arranged, current_display)?;
+        if arranged[arranged_idx].1 {
+            return Err(WindowManagerError::DisplayExcluded);
+        }
+        let current_idx = arranged[..arranged_idx]
+            .iter()
+            .filter(|(_, excluded)| !excluded)
+            .count();
+
+        let displays: Vec<Display> = arranged
+            .into_iter()
+            .filter(|(_, excluded)| !excluded)
+            .map(|(display, _)| display)
+            .collect();
         if displays.len() < 2 {
             return Err(WindowManagerError::NoAdjacentDisplay);
         }
 
+        Ok((displays, current_idx))
+    }
+
+    /// Find a display in a list of arranged displays by its origin.
+    fn display_index(arranged: &[(Display, bool)], display: &Display) -> Result<usize> {
+        arranged
+            .iter()
+            .position(|(d, _)| d.bounds.x == display.bounds.x && d.bounds.y == display.bounds.y)
+            .ok_or(WindowManagerError::DisplayError)
+    }
+
+    /// Get all displays, left-to-right, top-to-bottom.
+    pub fn displays(&self) -> Result<Vec<Display>> {
+        self.numbered_displays()
+    }
+
+    /// Get all displays that windows can be moved to, each with the number the
+    /// user knows it by. Every connected display is counted left-to-right,
+    /// top-to-bottom from 1, the same as display selectors in config, so
+    /// excluding a display doesn't renumber the others and the parts of a
+    /// split display share its number.
+    pub fn displays_by_number(&self) -> Result<Vec<(usize, Display)>> {
+        let displays = self
+            .arrangement()?
+            .into_iter()
+            .filter(|a| !a.excluded)
+            .map(|a| (a.number, a.display))
+            .collect();
+
+        Ok(displays)
+    }
+
+    /// The display with the given number (see [`Self::displays_by_number`]).
+    /// For a split display, this is its first part.
+    fn display_by_number(&self, number: usize) -> Result<Display> {
+        let arranged = self
+            .arrangement()?
+            .into_iter()
+            .find(|a| a.number == number)
+            .ok_or(WindowManagerError::NoSuchDisplay(number))?;
+        if arranged.excluded {
+            return Err(WindowManagerError::DisplayExcluded);
+        }
+
+        Ok(arranged.display)
+    }
+
+    /// Get the physical displays as reported by the OS, without overrides or splits.
+    pub fn connected_displays(&self) -> Result<Vec<Display>> {
+        self.inner.get_all_displays()
+    }
+
+    /// Get all displays left-to-right, top-to-bottom. Work-area overrides from
+    /// config are applied, split displays are replaced by their virtual
+    /// displays, and displays excluded in config are left out.
+    fn numbered_displays(&self) -> Result<Vec<Display>> {
+        let displays = self
+            .arranged_displays()?
+            .into_iter()
+            .filter(|(_, excluded)| !excluded)
+            .map(|(display, _)| display)
+            .collect();
+
+        Ok(displays)
+    }
+
+    /// Get every display, including excluded ones, sorted left-to-right,
+    /// top-to-bottom with overrides and splits applied. Each display is paired
+    /// with whether it is excluded.
+    fn arranged_displays(&self) -> Result<Vec<(Display, bool)>> {
+        let displays = self
+            .arrangement()?
+            .into_iter()
+            .map(|a| (a.display, a.excluded))
+            .collect();
+
+        Ok(displays)
+    }
+
+    /// Get every display, including excluded ones, sorted left-to-right,
+    /// top-to-bottom with overrides and splits applied, along with its number.
+    fn arrangement(&self) -> Result<Vec<ArrangedDisplay>> {
+        let mut displays = self.inner.get_all_displays()?;
+
         // Sort displays by X coordinate, then Y coordinate (left-to-right, top-to-bottom)
         displays.sort_by(|a, b| {
             a.bounds.x.cmp(&b.bounds.x).then(a.bounds.y.cmp(&b.bounds.y))
         });
 
-        // Find the index of the current display
-        let current_idx = displays
+        // Config selects displays by their physical number, so match before splitting
+        let displays = displays
+            .into_iter()
+            .enumerate()
+            .flat_map(|(i, mut display)| {
+                self.apply_safe_area(&mut display);
+                self.apply_display_override(&mut display, i + 1);
+                let excluded = self.excluded_displays.iter().any(|e| e.matches(&display, i + 1));
+
+                let split = self
+                    .display_splits
+                    .iter()
+                    .find(|s| s.display.matches(&display, i + 1));
+                let displays = match split {
+                    Some(split) => display.split(&split.splits),
+                    None => vec![display],
+                };
+                displays.into_iter().map(move |display| ArrangedDisplay {
+                    number: i + 1,
+                    display,
+                    excluded,
+                })
+            })
+            .collect();
+
+        Ok(displays)
+    }
+
+    /// Get the display containing the focused window, with the notch and
+    /// work-area overrides from config applied. On a split display, this is the virtual display
+    /// under the focused window.
+    fn current_display(&self) -> Result<Display> {
+        let mut current = match self.inner.get_current_display() {
+            // Use the display of the fallback window instead
+            Err(WindowManagerError::NoFocusedWindow) => {
+                let window = self.target_window()?;
+                let displays = self.inner.get_all_displays()?;
+                Display::for_frame(&displays, &window.frame)
+                    .cloned()
+                    .ok_or(WindowManagerError::DisplayError)?
+            }
+            result => result?,
+        };
+
+        // Overrides can match by display number, which needs the full list
+        if self.display_overrides.is_empty() && self.display_splits.is_empty() {
+            self.apply_safe_area(&mut current);
+            return Ok(current);
+        }
+
+        // Snapping within an excluded display still works, so include them here
+        let candidates: Vec<Display> = self
+            .arranged_displays()?
+            .into_iter()
+            .map(|(display, _)| display)
+            .filter(|d| current.bounds.contains(d.bounds.x, d.bounds.y))
+            .collect();
+
+        let segment = if candidates.len() > 1 {
+            let window = self.target_window()?;
+            Display::for_frame(&candidates, &window.frame).cloned()
+        } else {
+            None
+        };
+
+        Ok(segment.or(candidates.into_iter().next()).unwrap_or(current))
+    }
+
+    /// A display covering the bounding box of all displays, for positions that
+    /// treat every monitor as one surface.
+    fn combined_display(&self) -> Result<Display> {
+        let displays = self.numbered_displays()?;
+        let first = displays.first().ok_or(WindowManagerError::DisplayError)?;
+
+        Ok(Display {
+            name: tr("display.all_displays"),
+            bounds: displays.iter().fold(first.bounds, |acc, d| acc.union(&d.bounds)),
+            work_area: displays
+                .iter()
+                .fold(first.work_area, |acc, d| acc.union(&d.work_area)),
+            is_primary: false,
+            scale_factor: first.scale_factor,
+            safe_area: Insets::default(),
+        })
+    }
+
+    /// Keep a display's work area clear of the notch if `avoid_notch` is set.
+    fn apply_safe_area(&self, display: &mut Display) {
+        if !self.avoid_notch {
+            return;
+        }
+
+        let safe = display.bounds.inset_by(&display.safe_area);
+        if let Some(work_area) = display.work_area.intersection(&safe) {
+            display.work_area = work_area;
+        }
+    }
+
+    /// Shrink a display's work area by the first matching override from config.
+    fn apply_display_override(&self, display: &mut Display, number: usize) {
+        let matching = self
+            .display_overrides
             .iter()
-            .position(|d| d.bounds.x == current_display.bounds.x && d.bounds.y == current_display.bounds.y)
-            .ok_or(WindowManagerError::DisplayError)?;
-
-        // Calculate target display index based on direction (wrap around)
-        let target_idx = match direction {
-            DisplayDirection::Next => (current_idx + 1) % displays.len(),
-            DisplayDirection::Previous => {
-                if current_idx == 0 {
-                    displays.len() - 1
+            .find(|o| o.display.matches(display, number));
+
+        if let Some(o) = matching {
+            display.work_area = display.work_area.inset_by(&o.margins);
+        }
+    }
+
+    /// Move the focused window to the display with the given 1-based number,
+    /// keeping its snap position.
+    pub fn move_to_display_number(&self, number: usize) -> Result<()> {
+        let target_display = self.display_by_number(number)?;
+        self.move_to(&target_display)
+    }
+
+    /// Move the focused window to `display`, keeping its snap position.
+    pub fn move_to(&self, display: &Display) -> Result<()> {
+        let window = self.focused_window()?;
+        let current_display = self.current_display()?;
+
+        self.move_window_to(&window, &current_display, display)
+    }
+
+    /// Snap the focused window to a position on the display with the given
+    /// 1-based number, moving it there first if it is on another display.
+    pub fn snap_to_display(&self, position: SnapPosition, number: usize) -> Result<()> {
+        let window = self.focused_window()?;
+        let current_display = self.current_display()?;
+        let target_display = &self.display_by_number(number)?;
+
+        if target_display.bounds == current_display.bounds {
+            return self.snap_window(&window, position, target_display);
+        }
+
+        // Calculate from the scaled frame so positions that keep part of the
+        // current frame (e.g. Maximize Height) land on the target display
+        let scaled = window
+            .frame
+            .scaled_between(&current_display.work_area, &target_display.work_area);
+        let frame = position.calculate_frame(&scaled, &target_display.work_area, &self.options);
+
+        self.record_snap(&window, position, frame, target_display);
+        self.apply_frame(&window, frame)?;
+
+        if self.cursor_follows_window {
+            let (center_x, center_y) = frame.center();
+            self.inner.move_cursor(center_x, center_y)?;
+        }
+
+        Ok(())
+    }
+
+    /// Move a window to another display. If the window is still where it was last
+    /// snapped, it is snapped to the same position on the target display;
+    /// otherwise its frame is scaled proportionally to the target work area.
+    fn move_window_to(&self, window: &Window, current: &Display, target: &Display) -> Result<()> {
+        let scaled = window
+            .frame
+            .scaled_between(&current.work_area, &target.work_area);
+
+        let position = LAST_POSITION
+            .lock()
+            .unwrap()
+            .get(&window.handle)
+            .map(|(position, _)| *position);
+        let snapped = position.filter(|position| {
+            let snapped = position.calculate_frame(&window.frame, &current.work_area, &self.options);
+            window.frame.is_close_to(&snapped, FRAME_TOLERANCE)
+        });
+
+        let frame = match snapped {
+            // Calculate from the scaled frame so positions that keep part of
+            // the current frame (e.g. Maximize Height) land on the target display
+            Some(position) => {
+                let frame = position.calculate_frame(&scaled, &target.work_area, &self.options);
+                self.record_snap(window, position, frame, target);
+                frame
+            }
+            None => scaled,
+        };
+
+        self.apply_frame(window, frame)?;
+
+        if self.cursor_follows_window {
+            let (center_x, center_y) = frame.center();
+            self.inner.move_cursor(center_x, center_y)?;
+        }
+
+        Ok(())
+    }
+
+    /// Re-apply the snap position of every snapped window after the display
+    /// configuration changed, using the work area of the display each window is
+    /// on now. Windows the user has moved since snapping are left alone, unless
+    /// the display they were snapped on has gone away.
+    pub fn resnap_windows(&self) -> Result<()> {
+        // Windows on excluded displays are re-snapped in place like any other
+        let displays: Vec<Display> = self
+            .arranged_displays()?
+            .into_iter()
+            .map(|(display, _)| display)
+            .collect();
+        let Some(primary) = displays.iter().find(|d| d.is_primary).or(displays.first()) else {
+            return Err(WindowManagerError::DisplayError);
+        };
+
+        let snapped = LAST_POSITION.lock().unwrap().clone();
+
+        // Keep going if one window refuses to move, but report the first failure
+        let mut first_error = None;
+        for window in self.inner.get_visible_windows()? {
+            let Some((position, snapped_frame)) = snapped.get(&window.handle).copied() else {
+                continue;
+            };
+            if !self.is_snappable(&window) {
+                continue;
+            }
+
+            let (snapped_x, snapped_y) = snapped_frame.center();
+            let display_gone = !displays.iter().any(|d| d.bounds.contains(snapped_x, snapped_y));
+            if !display_gone && !window.frame.is_close_to(&snapped_frame, FRAME_TOLERANCE) {
+                continue;
+            }
+
+            let display = Display::for_frame(&displays, &window.frame).unwrap_or(primary);
+
+            let frame = position.calculate_frame(&window.frame, &display.work_area, &self.options);
+            LAST_POSITION
+                .lock()
+                .unwrap()
+                .insert(window.handle, (position, frame));
+
+            if frame == window.frame {
+                continue;
+            }
+            // Not a user action, so leave the restore point alone
+            if let Err(e) = self.inner.set_window_frame(&window, frame) {
+                first_error.get_or_insert(e);
+            }
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Write the displays windows were snapped on to disk, if any snaps were
+    /// recorded since the last write.
+    pub fn save_display_affinity() {
+        affinity::flush();
+    }
+
+    /// Move windows back to displays that have just been reconnected, at the
+    /// position they were last snapped to there. `previous` holds the fingerprints
+    /// of the displays connected before the change.
+    pub fn restore_display_affinity(&self, previous: &[String]) -> Result<()> {
+        if !self.restore_on_reconnect {
+            return Ok(());
+        }
+
+        let reconnected: Vec<Display> = self
+            .numbered_displays()?
+            .into_iter()
+            .filter(|d| !previous.contains(&d.fingerprint()))
+            .collect();
+        if reconnected.is_empty() {
+            return Ok(());
+        }
+
+        let store = AffinityStore::shared();
+
+        // Keep going if one window refuses to move, but report the first failure
+        let mut first_error = None;
+        for window in self.visible_windows()? {
+            let Some(affinity) = store.find(&window) else {
+                continue;
+            };
+            let Some(display) = reconnected.iter().find(|d| d.fingerprint() == affinity.display)
+            else {
+                continue;
+            };
+
+            let frame = affinity
+                .position
+                .calculate_frame(&window.frame, &display.work_area, &self.options);
+            LAST_POSITION
+                .lock()
+                .unwrap()
+                .insert(window.handle, (affinity.position, frame));
+
+            // Not a user action, so leave the restore point alone
+            if let Err(e) = self.inner.set_window_frame(&window, frame) {
+                first_error.get_or_insert(e);
+            }
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Put windows back at the position they were last snapped to, on the
+    /// display they were on, e.g. after a reboot. Windows in `done` are left
+    /// alone, and every window looked at is added to it, so this can run
+    /// again as apps launch.
+    pub fn restore_layout(&self, done: &mut HashSet<WindowHandle>) -> Result<()> {
+        let store = AffinityStore::shared();
+        let displays = self.numbered_displays()?;
+
+        // Keep going if one window refuses to move, but report the first failure
+        let mut first_error = None;
+        for window in self.visible_windows()? {
+            if !done.insert(window.handle) {
+                continue;
+            }
+            let Some(affinity) = store.find(&window) else {
+                continue;
+            };
+            let Some(display) = displays.iter().find(|d| d.fingerprint() == affinity.display)
+            else {
+                continue;
+            };
+
+            let frame = affinity
+                .position
+                .calculate_frame(&window.frame, &display.work_area, &self.options);
+            LAST_POSITION
+                .lock()
+                .unwrap()
+                .insert(window.handle, (affinity.position, frame));
+
+            // Not a user action, so leave the restore point alone
+            if let Err(e) = self.inner.set_window_frame(&window, frame) {
+                first_error.get_or_insert(e);
+            }
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Swap the focused window's display with the next one in left-to-right order
+    /// (or the previous one if it is the last display).
+    pub fn swap_with_adjacent_display(&self) -> Result<()> {
+        let current_display = self.current_display()?;
+        let (displays, current_idx) = self.sorted_displays(&current_display)?;
+
+        let neighbor_idx = if current_idx + 1 < displays.len() {
+            current_idx + 1
+        } else {
+            current_idx - 1
+        };
+
+        self.swap_displays(current_idx + 1, neighbor_idx + 1)
+    }
+
+    /// Exchange all windows between two displays (by 1-based number), scaling each
+    /// frame proportionally to its new work area.
+    pub fn swap_displays(&self, a: usize, b: usize) -> Result<()> {
+        let displays = self.numbered_displays()?;
+        let display_a = &self.display_by_number(a)?;
+        let display_b = &self.display_by_number(b)?;
+
+        // Work out every move before touching any window, so windows moved onto a
+        // display aren't picked up again as belonging to it
+        let moves: Vec<(Window, Rect)> = self
+            .visible_windows()?
+            .into_iter()
+            .filter_map(|window| {
+                // A window straddling displays belongs to the one showing most of it
+                let owner = Display::for_frame(&displays, &window.frame)?;
+                let (from, to) = if owner.bounds == display_a.bounds {
+                    (display_a, display_b)
+                } else if owner.bounds == display_b.bounds {
+                    (display_b, display_a)
                 } else {
-                    current_idx - 1
-                }
+                    return None;
+                };
+
+                let frame = window.frame.scaled_between(&from.work_area, &to.work_area);
+                Some((window, frame))
+            })
+            .collect();
+
+        // Keep going if one window refuses to move, but report the first failure
+        let mut first_error = None;
+        for (window, frame) in &moves {
+            if let Err(e) = self.apply_frame(window, *frame) {
+                first_error.get_or_insert(e);
             }
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Maximize the focused window across its display and the adjacent one.
+    /// Uses the next display in left-to-right order, or the previous one if the
+    /// window is on the last display.
+    pub fn span_displays(&self) -> Result<()> {
+        let window = self.focused_window()?;
+        let current_display = self.current_display()?;
+        let (displays, current_idx) = self.sorted_displays(&current_display)?;
+
+        let neighbor_idx = if current_idx + 1 < displays.len() {
+            current_idx + 1
+        } else {
+            current_idx - 1
         };
 
-        let target_display = &displays[target_idx];
+        let frame = displays[current_idx]
+            .work_area
+            .union(&displays[neighbor_idx].work_area);
 
-        // Maximize window on target display
-        let new_frame = Rect::new(
-            target_display.work_area.x,
-            target_display.work_area.y,
-            target_display.work_area.width,
-            target_display.work_area.height,
-        );
+        self.apply_frame(&window, frame)
+    }
+
+    /// Move the focused window to the neighboring display in the given direction,
+    /// keeping its snap position.
+    pub fn throw_to_display(&self, direction: ThrowDirection) -> Result<()> {
+        let window = self.focused_window()?;
+        let current_display = self.current_display()?;
+        let displays = self.numbered_displays()?;
+
+        let target_idx = direction
+            .find_display(&current_display, &displays)
+            .ok_or(WindowManagerError::NoAdjacentDisplay)?;
+
+        self.move_window_to(&window, &current_display, &displays[target_idx])
+    }
+
+    /// Move the focused window to the next or previous display.
+    pub fn move_to_display(&self, direction: DisplayDirection) -> Result<()> {
+        let window = self.focused_window()?;
+        let current_display = self.current_display()?;
 
-        self.inner.set_window_frame(&window, new_frame)
+        // Step from the current display's place among all displays, so this
+        // also works from an excluded one, skipping excluded ones (wrap around)
+        let arranged = self.arranged_displays()?;
+        let current_idx = Self::display_index(&arranged, &current_display)?;
+        let count = arranged.len();
+        let target = (1..count)
+            .map(|step| match direction {
+                DisplayDirection::Next => (current_idx + step) % count,
+                DisplayDirection::Previous => (current_idx + count - step) % count,
+            })
+            .map(|i| &arranged[i])
+            .find(|(_, excluded)| !excluded)
+            .map(|(display, _)| display)
+            .ok_or(WindowManagerError::NoAdjacentDisplay)?;
+
+        self.move_window_to(&window, &current_display, target)
     }
+
 }
 
 impl Default for WindowManager {
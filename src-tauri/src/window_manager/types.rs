@@ -1,7 +1,8 @@
+use crate::i18n::tr_args;
 use serde::{Deserialize, Serialize};
 
 /// Represents a rectangle with position and size.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Rect {
     pub x: i32,
     pub y: i32,
@@ -13,41 +14,349 @@ impl Rect {
     pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
         Self { x, y, width, height }
     }
+
+    /// The x coordinate just past the right edge.
+    pub fn right(&self) -> i32 {
+        self.x + self.width as i32
+    }
+
+    /// The y coordinate just past the bottom edge.
+    pub fn bottom(&self) -> i32 {
+        self.y + self.height as i32
+    }
+
+    /// The center point of the rectangle.
+    pub fn center(&self) -> (i32, i32) {
+        (self.x + (self.width / 2) as i32, self.y + (self.height / 2) as i32)
+    }
+
+    /// Whether the point lies inside the rectangle.
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    /// The overlapping part of both rectangles, if they overlap at all.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let left = self.x.max(other.x);
+        let top = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+
+        if right <= left || bottom <= top {
+            return None;
+        }
+
+        Some(Rect::new(left, top, (right - left) as u32, (bottom - top) as u32))
+    }
+
+    /// The smallest rectangle containing both rectangles.
+    pub fn union(&self, other: &Rect) -> Rect {
+        let left = self.x.min(other.x);
+        let top = self.y.min(other.y);
+        let right = self.right().max(other.right());
+        let bottom = self.bottom().max(other.bottom());
+        Rect::new(left, top, (right - left) as u32, (bottom - top) as u32)
+    }
+
+    /// Whether every edge is within `tolerance` pixels of the other rectangle's.
+    pub fn is_close_to(&self, other: &Rect, tolerance: i32) -> bool {
+        (self.x - other.x).abs() <= tolerance
+            && (self.y - other.y).abs() <= tolerance
+            && (self.right() - other.right()).abs() <= tolerance
+            && (self.bottom() - other.bottom()).abs() <= tolerance
+    }
+
+    /// Map this rectangle from one area to another, keeping its position and size
+    /// relative to the area. The result is kept inside `to`.
+    pub fn scaled_between(&self, from: &Rect, to: &Rect) -> Rect {
+        let scale_x = to.width as f64 / from.width.max(1) as f64;
+        let scale_y = to.height as f64 / from.height.max(1) as f64;
+
+        let width = ((self.width as f64 * scale_x).round() as u32).min(to.width);
+        let height = ((self.height as f64 * scale_y).round() as u32).min(to.height);
+        let x = to.x + ((self.x - from.x) as f64 * scale_x).round() as i32;
+        let y = to.y + ((self.y - from.y) as f64 * scale_y).round() as i32;
+
+        Rect::new(
+            x.clamp(to.x, to.right() - width as i32),
+            y.clamp(to.y, to.bottom() - height as i32),
+            width,
+            height,
+        )
+    }
+
+    /// Shrink the rectangle by the given amount on each side.
+    pub fn inset_by(&self, insets: &Insets) -> Rect {
+        Rect::new(
+            self.x + insets.left as i32,
+            self.y + insets.top as i32,
+            self.width.saturating_sub(insets.left + insets.right),
+            self.height.saturating_sub(insets.top + insets.bottom),
+        )
+    }
+
+    /// Grow the rectangle by the given amount on each side.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub fn outset_by(&self, insets: &Insets) -> Rect {
+        Rect::new(
+            self.x - insets.left as i32,
+            self.y - insets.top as i32,
+            self.width + insets.left + insets.right,
+            self.height + insets.top + insets.bottom,
+        )
+    }
+
+    /// Multiply every coordinate by `factor`, e.g. to convert between logical
+    /// and device pixels.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub fn scaled(&self, factor: f64) -> Rect {
+        Rect::new(
+            (self.x as f64 * factor).round() as i32,
+            (self.y as f64 * factor).round() as i32,
+            (self.width as f64 * factor).round() as u32,
+            (self.height as f64 * factor).round() as u32,
+        )
+    }
+
+    /// Shrink the rectangle by `amount` on every side.
+    pub fn inset(&self, amount: u32) -> Rect {
+        Rect::new(
+            self.x + amount as i32,
+            self.y + amount as i32,
+            self.width.saturating_sub(amount * 2),
+            self.height.saturating_sub(amount * 2),
+        )
+    }
+}
+
+/// Per-side margins, in pixels.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Insets {
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32,
+    pub left: u32,
+}
+
+/// Find the largest rectangle inside `area` that doesn't overlap any of `obstacles`.
+pub fn largest_free_rect(area: &Rect, obstacles: &[Rect]) -> Option<Rect> {
+    let obstacles: Vec<Rect> = obstacles
+        .iter()
+        .filter_map(|o| o.intersection(area))
+        .collect();
+
+    // Every edge of the largest free rectangle lies on an edge of the area or of an
+    // obstacle, so only those coordinates need to be considered
+    let mut xs = vec![area.x, area.right()];
+    let mut ys = vec![area.y, area.bottom()];
+    for o in &obstacles {
+        xs.extend([o.x, o.right()]);
+        ys.extend([o.y, o.bottom()]);
+    }
+    xs.sort_unstable();
+    xs.dedup();
+    ys.sort_unstable();
+    ys.dedup();
+
+    let cols = xs.len() - 1;
+    let rows = ys.len() - 1;
+
+    // Prefix sums over the compressed grid of which cells are covered by an obstacle
+    let mut covered = vec![vec![0u32; cols + 1]; rows + 1];
+    for r in 0..rows {
+        for c in 0..cols {
+            let cell = Rect::new(
+                xs[c],
+                ys[r],
+                (xs[c + 1] - xs[c]) as u32,
+                (ys[r + 1] - ys[r]) as u32,
+            );
+            let hit = obstacles.iter().any(|o| o.intersection(&cell).is_some()) as u32;
+            covered[r + 1][c + 1] = hit + covered[r][c + 1] + covered[r + 1][c] - covered[r][c];
+        }
+    }
+
+    let mut best: Option<(u64, Rect)> = None;
+    for c1 in 0..cols {
+        for c2 in c1 + 1..=cols {
+            for r1 in 0..rows {
+                for r2 in r1 + 1..=rows {
+                    let hits = covered[r2][c2] + covered[r1][c1] - covered[r1][c2] - covered[r2][c1];
+                    if hits != 0 {
+                        // Extending further down only adds more cells
+                        break;
+                    }
+
+                    let width = (xs[c2] - xs[c1]) as u32;
+                    let height = (ys[r2] - ys[r1]) as u32;
+                    let size = width as u64 * height as u64;
+                    if best.is_none_or(|(best_size, _)| size > best_size) {
+                        best = Some((size, Rect::new(xs[c1], ys[r1], width, height)));
+                    }
+                }
+            }
+        }
+    }
+
+    best.map(|(_, rect)| rect)
 }
 
 /// Represents a window with a platform-specific handle.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Window {
     /// Platform-specific window handle.
     pub handle: WindowHandle,
-    #[allow(dead_code)]
+    /// Name of the application that owns the window (e.g. "Safari" or "chrome").
+    pub app: String,
     pub title: String,
     pub frame: Rect,
 }
 
 /// Platform-specific window handle.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum WindowHandle {
     #[cfg(target_os = "windows")]
     Windows(isize),
 
+    /// Owning process ID and CGWindowID (0 if the window ID couldn't be read).
     #[cfg(target_os = "macos")]
-    MacOS(u32),
+    MacOS { pid: u32, window_id: u32 },
 
     #[cfg(target_os = "linux")]
     Linux(u64),
 }
 
 /// Represents a display/monitor.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Display {
-    #[allow(dead_code)]
+    /// The monitor's name as reported by the OS (e.g. "DELL U2720Q").
     pub name: String,
     /// The full bounds of the display.
     pub bounds: Rect,
     /// The usable work area (excluding taskbar/dock/menubar).
     pub work_area: Rect,
     pub is_primary: bool,
+    /// Ratio of physical pixels to logical pixels (e.g. 2.0 for Retina, 1.5 for 150%).
+    pub scale_factor: f64,
+    /// Parts of the display obscured by hardware, such as the camera notch on
+    /// MacBooks, measured from the display bounds.
+    pub safe_area: Insets,
+}
+
+impl Display {
+    /// Identifies this kind of display across reconnects, as `"name@widthxheight"`.
+    pub fn fingerprint(&self) -> String {
+        format!("{}@{}x{}", self.name, self.bounds.width, self.bounds.height)
+    }
+
+    /// Find the display a window with this frame is on: the one it overlaps the
+    /// most, or if it is entirely off-screen, the one closest to its center.
+    /// A window straddling two displays often has its center on the one
+    /// holding less of it, so the center alone isn't enough.
+    pub fn for_frame<'a>(displays: &'a [Display], frame: &Rect) -> Option<&'a Display> {
+        let overlapping = displays
+            .iter()
+            .filter_map(|d| {
+                let overlap = d.bounds.intersection(frame)?;
+                Some((d, overlap.width as u64 * overlap.height as u64))
+            })
+            .max_by_key(|(_, area)| *area)
+            .map(|(d, _)| d);
+
+        overlapping.or_else(|| {
+            let (center_x, center_y) = frame.center();
+            displays.iter().min_by_key(|d| {
+                let dx = (d.bounds.x - center_x).max(center_x - d.bounds.right() + 1).max(0) as i64;
+                let dy = (d.bounds.y - center_y).max(center_y - d.bounds.bottom() + 1).max(0) as i64;
+                dx * dx + dy * dy
+            })
+        })
+    }
+
+    /// Split the display side by side into virtual displays at the given
+    /// boundaries, each a fraction of the work area's width (e.g. `[0.25, 0.75]`
+    /// for a wide center segment). Boundaries outside 0..1 are ignored.
+    pub fn split(&self, boundaries: &[f64]) -> Vec<Display> {
+        let area = self.work_area;
+
+        let mut xs: Vec<i32> = boundaries
+            .iter()
+            .filter(|b| **b > 0.0 && **b < 1.0)
+            .map(|b| area.x + (area.width as f64 * b).round() as i32)
+            .collect();
+        xs.sort_unstable();
+        xs.dedup();
+        xs.insert(0, area.x);
+        xs.push(area.right());
+
+        let count = xs.len() - 1;
+        if count < 2 {
+            return vec![self.clone()];
+        }
+
+        (0..count)
+            .map(|i| {
+                // The outer segments keep the display's edges beyond the work area
+                let left = if i == 0 { self.bounds.x } else { xs[i] };
+                let right = if i + 1 == count { self.bounds.right() } else { xs[i + 1] };
+
+                Display {
+                    name: format!("{} ({}/{})", self.name, i + 1, count),
+                    bounds: Rect::new(left, self.bounds.y, (right - left) as u32, self.bounds.height),
+                    work_area: Rect::new(xs[i], area.y, (xs[i + 1] - xs[i]) as u32, area.height),
+                    is_primary: self.is_primary && i == 0,
+                    scale_factor: self.scale_factor,
+                    safe_area: self.safe_area,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Identifies a display either by its name or by its 1-based number
+/// (left-to-right, top-to-bottom).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DisplaySelector {
+    Number(usize),
+    Name(String),
+}
+
+impl DisplaySelector {
+    /// Whether this selector refers to `display`, which has the given number.
+    pub fn matches(&self, display: &Display, number: usize) -> bool {
+        match self {
+            DisplaySelector::Number(n) => *n == number,
+            DisplaySelector::Name(name) => *name == display.name,
+        }
+    }
+}
+
+/// Identifies one window of an app either by its title or by its 1-based
+/// number in front-to-back order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum WindowSelector {
+    Number(usize),
+    Title(String),
+}
+
+impl WindowSelector {
+    /// Pick the selected window from an app's windows, front to back. Titles
+    /// match exactly, or failing that, case-insensitively by substring.
+    pub fn select<'a>(&self, windows: &'a [Window]) -> Option<&'a Window> {
+        match self {
+            WindowSelector::Number(n) => n.checked_sub(1).and_then(|i| windows.get(i)),
+            WindowSelector::Title(title) => {
+                let lower = title.to_lowercase();
+                windows
+                    .iter()
+                    .find(|w| w.title == *title)
+                    .or_else(|| windows.iter().find(|w| w.title.to_lowercase().contains(&lower)))
+            }
+        }
+    }
 }
 
 /// Direction for moving windows between displays.
@@ -58,9 +367,291 @@ pub enum DisplayDirection {
     Previous,
 }
 
-/// The snap positions supported by the application.
+/// Direction for throwing a window to the neighboring display.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
+pub enum ThrowDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl ThrowDirection {
+    /// Find the index of the display next to `current` in this direction.
+    ///
+    /// Displays that line up with `current` (overlap it across the direction of
+    /// travel) are preferred; among those, the closest one by center distance wins.
+    pub fn find_display(&self, current: &Display, displays: &[Display]) -> Option<usize> {
+        let (current_x, current_y) = current.bounds.center();
+        let from = current.bounds;
+
+        displays
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| d.bounds != from)
+            .filter_map(|(i, d)| {
+                let (x, y) = d.bounds.center();
+                let (dx, dy) = ((x - current_x) as i64, (y - current_y) as i64);
+
+                let rows_overlap = ranges_overlap(from.y, from.bottom(), d.bounds.y, d.bounds.bottom());
+                let columns_overlap = ranges_overlap(from.x, from.right(), d.bounds.x, d.bounds.right());
+
+                let (ahead, across, aligned) = match self {
+                    ThrowDirection::Left => (-dx, dy, rows_overlap),
+                    ThrowDirection::Right => (dx, dy, rows_overlap),
+                    ThrowDirection::Up => (-dy, dx, columns_overlap),
+                    ThrowDirection::Down => (dy, dx, columns_overlap),
+                };
+
+                (ahead > 0).then_some((i, !aligned, ahead + across.abs()))
+            })
+            .min_by_key(|(_, unaligned, distance)| (*unaligned, *distance))
+            .map(|(i, _, _)| i)
+    }
+}
+
+/// Whether the ranges `a_start..a_end` and `b_start..b_end` overlap.
+fn ranges_overlap(a_start: i32, a_end: i32, b_start: i32, b_end: i32) -> bool {
+    a_start < b_end && b_start < a_end
+}
+
+/// Where a fixed-size preset is placed within the work area.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresetAnchor {
+    #[default]
+    Center,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// A pixel-exact window size, e.g. 1280×800 centered.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PresetSize {
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub anchor: PresetAnchor,
+}
+
+impl PresetSize {
+    /// Calculate the frame for this preset within the given work area.
+    /// The size is clamped so the window never extends past the work area.
+    pub fn calculate_frame(&self, work_area: &Rect) -> Rect {
+        let w = self.width.min(work_area.width);
+        let h = self.height.min(work_area.height);
+        let left = work_area.x;
+        let top = work_area.y;
+        let right = work_area.right() - w as i32;
+        let bottom = work_area.bottom() - h as i32;
+
+        match self.anchor {
+            PresetAnchor::Center => Rect::new(
+                left + ((work_area.width - w) / 2) as i32,
+                top + ((work_area.height - h) / 2) as i32,
+                w,
+                h,
+            ),
+            PresetAnchor::TopLeft => Rect::new(left, top, w, h),
+            PresetAnchor::TopRight => Rect::new(right, top, w, h),
+            PresetAnchor::BottomLeft => Rect::new(left, bottom, w, h),
+            PresetAnchor::BottomRight => Rect::new(right, bottom, w, h),
+        }
+    }
+}
+
+/// A user-defined position as fractions of the work area, e.g.
+/// `{ x: 0.0, y: 0.0, width: 0.7, height: 1.0 }` for the left 70%.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CustomFrame {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl CustomFrame {
+    /// The snap position for this frame, so it gets the same margins and gaps
+    /// as the built-in positions.
+    pub fn position(&self) -> SnapPosition {
+        SnapPosition::Custom {
+            x: self.x,
+            y: self.y,
+            w: self.width,
+            h: self.height,
+        }
+    }
+}
+
+/// Incremental resize actions applied to the window's current frame.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResizeAction {
+    Grow,
+    Shrink,
+    GrowWidth,
+    ShrinkWidth,
+    GrowHeight,
+    ShrinkHeight,
+}
+
+/// How much a single resize action changes the window by.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResizeStep {
+    /// A fixed number of pixels.
+    Pixels(u32),
+    /// A percentage of the work area's width or height.
+    Percent(f64),
+}
+
+impl Default for ResizeStep {
+    fn default() -> Self {
+        ResizeStep::Pixels(30)
+    }
+}
+
+impl ResizeStep {
+    fn amount(&self, extent: u32) -> i32 {
+        match self {
+            ResizeStep::Pixels(px) => *px as i32,
+            ResizeStep::Percent(pct) => (extent as f64 * pct / 100.0).round() as i32,
+        }
+    }
+}
+
+/// Which point of the window stays put while resizing.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResizeAnchor {
+    /// Grow and shrink evenly around the window's center.
+    #[default]
+    Center,
+    /// Keep the window edge closest to the screen edge fixed.
+    NearestEdge,
+}
+
+// Windows are never shrunk below this size
+const MIN_RESIZE_EXTENT: i32 = 100;
+
+impl ResizeAction {
+    /// Calculate the resized frame, kept within the given work area.
+    pub fn calculate_frame(
+        &self,
+        window_frame: &Rect,
+        work_area: &Rect,
+        step: ResizeStep,
+        anchor: ResizeAnchor,
+    ) -> Rect {
+        let dw = step.amount(work_area.width);
+        let dh = step.amount(work_area.height);
+
+        let (dw, dh) = match self {
+            ResizeAction::Grow => (dw, dh),
+            ResizeAction::Shrink => (-dw, -dh),
+            ResizeAction::GrowWidth => (dw, 0),
+            ResizeAction::ShrinkWidth => (-dw, 0),
+            ResizeAction::GrowHeight => (0, dh),
+            ResizeAction::ShrinkHeight => (0, -dh),
+        };
+
+        let (x, width) = resize_span(
+            window_frame.x,
+            window_frame.width,
+            dw,
+            work_area.x,
+            work_area.width,
+            anchor,
+        );
+        let (y, height) = resize_span(
+            window_frame.y,
+            window_frame.height,
+            dh,
+            work_area.y,
+            work_area.height,
+            anchor,
+        );
+
+        Rect::new(x, y, width, height)
+    }
+}
+
+/// Resize one axis of a window by `delta`, returning the new start and length.
+fn resize_span(
+    start: i32,
+    len: u32,
+    delta: i32,
+    area_start: i32,
+    area_len: u32,
+    anchor: ResizeAnchor,
+) -> (i32, u32) {
+    let area_end = area_start + area_len as i32;
+    let end = start + len as i32;
+    // Never shrink below the minimum, unless the window was already smaller
+    let min_len = MIN_RESIZE_EXTENT.min(len as i32).min(area_len as i32);
+    let new_len = (len as i32 + delta).clamp(min_len, area_len as i32);
+    let applied = new_len - len as i32;
+
+    let new_start = match anchor {
+        ResizeAnchor::Center => start - applied / 2,
+        ResizeAnchor::NearestEdge => {
+            if start - area_start <= area_end - end {
+                start
+            } else {
+                end - new_len
+            }
+        }
+    };
+
+    // Keep the window inside the work area
+    let new_start = new_start.clamp(area_start, area_end - new_len);
+    (new_start, new_len as u32)
+}
+
+/// Options that tweak how snap positions are laid out within a work area.
+#[derive(Debug, Clone, Copy)]
+pub struct LayoutOptions {
+    /// Fraction of the work area given to the left/top half (0.5 is an even split).
+    pub split_ratio: f64,
+    /// Space kept between snapped windows and the edges of the work area.
+    pub outer_margin: u32,
+    /// Extra space kept at individual edges of the work area, on top of
+    /// `outer_margin` (e.g. room for a dock that isn't in the work area).
+    pub edge_margins: Insets,
+    /// Space kept between adjacent snapped windows.
+    pub inner_gap: u32,
+    /// How much grow/shrink actions change the window by.
+    pub resize_step: ResizeStep,
+    /// Which point of the window stays put when growing/shrinking.
+    pub resize_anchor: ResizeAnchor,
+}
+
+impl Default for LayoutOptions {
+    fn default() -> Self {
+        Self {
+            split_ratio: 0.5,
+            outer_margin: 0,
+            edge_margins: Insets::default(),
+            inner_gap: 0,
+            resize_step: ResizeStep::default(),
+            resize_anchor: ResizeAnchor::default(),
+        }
+    }
+}
+
+impl LayoutOptions {
+    /// The part of `work_area` inside the outer and per-edge margins.
+    pub fn margin_area(&self, work_area: &Rect) -> Rect {
+        work_area.inset(self.outer_margin).inset_by(&self.edge_margins)
+    }
+}
+
+/// The snap positions supported by the application.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SnapPosition {
     LeftHalf,
     RightHalf,
@@ -71,34 +662,102 @@ pub enum SnapPosition {
     BottomLeft,
     BottomRight,
     Center,
+    CenterHalf,
     Maximize,
+    MaximizeHeight,
+    MaximizeWidth,
     LeftThird,
     CenterThird,
     RightThird,
     LeftTwoThirds,
     RightTwoThirds,
+    TopThird,
+    MiddleThird,
+    BottomThird,
+    TopTwoThirds,
+    BottomTwoThirds,
+    FirstFourth,
+    SecondFourth,
+    ThirdFourth,
+    LastFourth,
+    FirstThreeFourths,
+    LastThreeFourths,
+    TopLeftTwoThirds,
+    TopRightTwoThirds,
+    BottomLeftTwoThirds,
+    BottomRightTwoThirds,
+    TopLeftSixth,
+    TopCenterSixth,
+    TopRightSixth,
+    BottomLeftSixth,
+    BottomCenterSixth,
+    BottomRightSixth,
+    TopLeftNinth,
+    TopCenterNinth,
+    TopRightNinth,
+    MiddleLeftNinth,
+    MiddleCenterNinth,
+    MiddleRightNinth,
+    BottomLeftNinth,
+    BottomCenterNinth,
+    BottomRightNinth,
+    /// An arbitrary rectangle given as fractions (0.0–1.0) of the work area.
+    Custom { x: f64, y: f64, w: f64, h: f64 },
+}
+
+impl std::str::FromStr for SnapPosition {
+    type Err = String;
+
+    /// Parse a named position by its config name, e.g. `"left_half"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_value(serde_json::Value::String(s.to_string()))
+            .map_err(|_| tr_args("error.unknown_position", &[&s]))
+    }
 }
 
 impl SnapPosition {
     /// Calculate the frame for this snap position within the given work area.
-    pub fn calculate_frame(&self, work_area: &Rect) -> Rect {
+    ///
+    /// `window_frame` is the window's current frame; positions that only change one
+    /// dimension (e.g. `MaximizeHeight`) keep the other from it.
+    pub fn calculate_frame(
+        &self,
+        window_frame: &Rect,
+        work_area: &Rect,
+        options: &LayoutOptions,
+    ) -> Rect {
+        let area = options.margin_area(work_area);
+        let frame = self.base_frame(window_frame, &area, options);
+
+        match self {
+            // These keep one dimension from the current window, so insetting it
+            // again would shrink the window on every press
+            SnapPosition::MaximizeHeight | SnapPosition::MaximizeWidth => frame,
+            _ => apply_inner_gap(frame, &area, options.inner_gap),
+        }
+    }
+
+    /// The frame for this position before any inner gap is applied.
+    fn base_frame(&self, window_frame: &Rect, work_area: &Rect, options: &LayoutOptions) -> Rect {
         let x = work_area.x;
         let y = work_area.y;
         let w = work_area.width;
         let h = work_area.height;
 
+        // Halves honor the configured split ratio instead of a fixed 50%
+        let split_w = (w as f64 * options.split_ratio).round() as u32;
+        let split_h = (h as f64 * options.split_ratio).round() as u32;
+
         match self {
-            SnapPosition::LeftHalf => Rect::new(x, y, w / 2, h),
-            SnapPosition::RightHalf => Rect::new(x + (w / 2) as i32, y, w / 2, h),
-            SnapPosition::TopHalf => Rect::new(x, y, w, h / 2),
-            SnapPosition::BottomHalf => Rect::new(x, y + (h / 2) as i32, w, h / 2),
-
-            SnapPosition::TopLeft => Rect::new(x, y, w / 2, h / 2),
-            SnapPosition::TopRight => Rect::new(x + (w / 2) as i32, y, w / 2, h / 2),
-            SnapPosition::BottomLeft => Rect::new(x, y + (h / 2) as i32, w / 2, h / 2),
-            SnapPosition::BottomRight => {
-                Rect::new(x + (w / 2) as i32, y + (h / 2) as i32, w / 2, h / 2)
-            }
+            SnapPosition::LeftHalf => Rect::new(x, y, split_w, h),
+            SnapPosition::RightHalf => Rect::new(x + split_w as i32, y, w - split_w, h),
+            SnapPosition::TopHalf => Rect::new(x, y, w, split_h),
+            SnapPosition::BottomHalf => Rect::new(x, y + split_h as i32, w, h - split_h),
+
+            SnapPosition::TopLeft => grid_cell(work_area, (0, 1, 2), (0, 1, 2)),
+            SnapPosition::TopRight => grid_cell(work_area, (1, 1, 2), (0, 1, 2)),
+            SnapPosition::BottomLeft => grid_cell(work_area, (0, 1, 2), (1, 1, 2)),
+            SnapPosition::BottomRight => grid_cell(work_area, (1, 1, 2), (1, 1, 2)),
 
             SnapPosition::Center => {
                 let center_w = w * 2 / 3;
@@ -111,13 +770,251 @@ impl SnapPosition {
                 )
             }
 
+            SnapPosition::CenterHalf => Rect::new(x + ((w - w / 2) / 2) as i32, y, w / 2, h),
+
             SnapPosition::Maximize => Rect::new(x, y, w, h),
+            SnapPosition::MaximizeHeight => Rect::new(window_frame.x, y, window_frame.width, h),
+            SnapPosition::MaximizeWidth => Rect::new(x, window_frame.y, w, window_frame.height),
+
+            SnapPosition::LeftThird => grid_cell(work_area, (0, 1, 3), FULL),
+            SnapPosition::CenterThird => grid_cell(work_area, (1, 1, 3), FULL),
+            SnapPosition::RightThird => grid_cell(work_area, (2, 1, 3), FULL),
+            SnapPosition::LeftTwoThirds => grid_cell(work_area, (0, 2, 3), FULL),
+            SnapPosition::RightTwoThirds => grid_cell(work_area, (1, 2, 3), FULL),
+
+            SnapPosition::TopThird => grid_cell(work_area, FULL, (0, 1, 3)),
+            SnapPosition::MiddleThird => grid_cell(work_area, FULL, (1, 1, 3)),
+            SnapPosition::BottomThird => grid_cell(work_area, FULL, (2, 1, 3)),
+            SnapPosition::TopTwoThirds => grid_cell(work_area, FULL, (0, 2, 3)),
+            SnapPosition::BottomTwoThirds => grid_cell(work_area, FULL, (1, 2, 3)),
+
+            SnapPosition::FirstFourth => grid_cell(work_area, (0, 1, 4), FULL),
+            SnapPosition::SecondFourth => grid_cell(work_area, (1, 1, 4), FULL),
+            SnapPosition::ThirdFourth => grid_cell(work_area, (2, 1, 4), FULL),
+            SnapPosition::LastFourth => grid_cell(work_area, (3, 1, 4), FULL),
+            SnapPosition::FirstThreeFourths => grid_cell(work_area, (0, 3, 4), FULL),
+            SnapPosition::LastThreeFourths => grid_cell(work_area, (1, 3, 4), FULL),
 
-            SnapPosition::LeftThird => Rect::new(x, y, w / 3, h),
-            SnapPosition::CenterThird => Rect::new(x + (w / 3) as i32, y, w / 3, h),
-            SnapPosition::RightThird => Rect::new(x + (w * 2 / 3) as i32, y, w / 3, h),
-            SnapPosition::LeftTwoThirds => Rect::new(x, y, w * 2 / 3, h),
-            SnapPosition::RightTwoThirds => Rect::new(x + (w / 3) as i32, y, w * 2 / 3, h),
+            SnapPosition::TopLeftTwoThirds => grid_cell(work_area, (0, 2, 3), (0, 1, 2)),
+            SnapPosition::TopRightTwoThirds => grid_cell(work_area, (1, 2, 3), (0, 1, 2)),
+            SnapPosition::BottomLeftTwoThirds => grid_cell(work_area, (0, 2, 3), (1, 1, 2)),
+            SnapPosition::BottomRightTwoThirds => grid_cell(work_area, (1, 2, 3), (1, 1, 2)),
+
+            SnapPosition::TopLeftSixth => grid_cell(work_area, (0, 1, 3), (0, 1, 2)),
+            SnapPosition::TopCenterSixth => grid_cell(work_area, (1, 1, 3), (0, 1, 2)),
+            SnapPosition::TopRightSixth => grid_cell(work_area, (2, 1, 3), (0, 1, 2)),
+            SnapPosition::BottomLeftSixth => grid_cell(work_area, (0, 1, 3), (1, 1, 2)),
+            SnapPosition::BottomCenterSixth => grid_cell(work_area, (1, 1, 3), (1, 1, 2)),
+            SnapPosition::BottomRightSixth => grid_cell(work_area, (2, 1, 3), (1, 1, 2)),
+
+            SnapPosition::TopLeftNinth => grid_cell(work_area, (0, 1, 3), (0, 1, 3)),
+            SnapPosition::TopCenterNinth => grid_cell(work_area, (1, 1, 3), (0, 1, 3)),
+            SnapPosition::TopRightNinth => grid_cell(work_area, (2, 1, 3), (0, 1, 3)),
+            SnapPosition::MiddleLeftNinth => grid_cell(work_area, (0, 1, 3), (1, 1, 3)),
+            SnapPosition::MiddleCenterNinth => grid_cell(work_area, (1, 1, 3), (1, 1, 3)),
+            SnapPosition::MiddleRightNinth => grid_cell(work_area, (2, 1, 3), (1, 1, 3)),
+            SnapPosition::BottomLeftNinth => grid_cell(work_area, (0, 1, 3), (2, 1, 3)),
+            SnapPosition::BottomCenterNinth => grid_cell(work_area, (1, 1, 3), (2, 1, 3)),
+            SnapPosition::BottomRightNinth => grid_cell(work_area, (2, 1, 3), (2, 1, 3)),
+
+            SnapPosition::Custom {
+                x: fx,
+                y: fy,
+                w: fw,
+                h: fh,
+            } => {
+                let (dx, width) = fractional_span(w, *fx, *fw);
+                let (dy, height) = fractional_span(h, *fy, *fh);
+                Rect::new(x + dx, y + dy, width, height)
+            }
+        }
+    }
+}
+
+/// A span along one axis of a grid: (first cell, number of cells, total cells).
+type GridSpan = (u32, u32, u32);
+
+/// The whole axis.
+const FULL: GridSpan = (0, 1, 1);
+
+/// Pixel offset and length of a grid span along an axis of length `len`.
+///
+/// Both edges are computed from the cell boundaries rather than a fixed cell
+/// size, so complementary spans tile exactly and the last cell absorbs any
+/// remainder from integer division.
+fn span(len: u32, (start, count, total): GridSpan) -> (i32, u32) {
+    let len = len as u64;
+    let begin = len * start as u64 / total as u64;
+    let end = len * (start + count) as u64 / total as u64;
+    (begin as i32, (end - begin) as u32)
+}
+
+/// Pixel offset and length of a fractional span along an axis of length `len`,
+/// clamped to the axis.
+fn fractional_span(len: u32, start: f64, size: f64) -> (i32, u32) {
+    let len = len as f64;
+    let begin = (start.clamp(0.0, 1.0) * len).round();
+    let end = ((start + size).clamp(0.0, 1.0) * len).round();
+    (begin as i32, (end - begin).max(0.0) as u32)
+}
+
+/// The rectangle covering the given column and row spans of the work area.
+fn grid_cell(work_area: &Rect, cols: GridSpan, rows: GridSpan) -> Rect {
+    let (dx, width) = span(work_area.width, cols);
+    let (dy, height) = span(work_area.height, rows);
+    Rect::new(work_area.x + dx, work_area.y + dy, width, height)
+}
+
+/// Pull in every edge of `frame` that doesn't touch the edge of `area` by half the
+/// gap, so two adjacent windows end up exactly `gap` apart.
+fn apply_inner_gap(frame: Rect, area: &Rect, gap: u32) -> Rect {
+    if gap == 0 {
+        return frame;
+    }
+
+    let leading = (gap / 2) as i32;
+    let trailing = (gap - gap / 2) as i32;
+
+    let mut left = frame.x;
+    let mut top = frame.y;
+    let mut right = frame.right();
+    let mut bottom = frame.bottom();
+
+    if left > area.x {
+        left += leading;
+    }
+    if top > area.y {
+        top += leading;
+    }
+    if right < area.right() {
+        right -= trailing;
+    }
+    if bottom < area.bottom() {
+        bottom -= trailing;
+    }
+
+    Rect::new(
+        left,
+        top,
+        (right - left).max(0) as u32,
+        (bottom - top).max(0) as u32,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use SnapPosition::*;
+
+    // Work areas with even and odd sizes, including ones left of and above
+    // the primary display
+    fn work_areas() -> [Rect; 5] {
+        [
+            Rect::new(0, 0, 1920, 1080),
+            Rect::new(0, 25, 1441, 875),
+            Rect::new(-1280, -7, 1279, 1023),
+            Rect::new(1920, -360, 1366, 767),
+            Rect::new(3, 5, 7, 11),
+        ]
+    }
+
+    // Positions that together should cover the work area exactly once
+    const COMPLEMENTARY_SETS: &[&[SnapPosition]] = &[
+        &[LeftHalf, RightHalf],
+        &[TopHalf, BottomHalf],
+        &[TopLeft, TopRight, BottomLeft, BottomRight],
+        &[LeftThird, CenterThird, RightThird],
+        &[LeftTwoThirds, RightThird],
+        &[LeftThird, RightTwoThirds],
+        &[TopThird, MiddleThird, BottomThird],
+        &[TopTwoThirds, BottomThird],
+        &[TopThird, BottomTwoThirds],
+        &[FirstFourth, SecondFourth, ThirdFourth, LastFourth],
+        &[FirstThreeFourths, LastFourth],
+        &[FirstFourth, LastThreeFourths],
+        &[TopLeftTwoThirds, TopRightSixth, BottomLeftTwoThirds, BottomRightSixth],
+        &[TopLeftSixth, TopRightTwoThirds, BottomLeftSixth, BottomRightTwoThirds],
+        &[
+            TopLeftSixth,
+            TopCenterSixth,
+            TopRightSixth,
+            BottomLeftSixth,
+            BottomCenterSixth,
+            BottomRightSixth,
+        ],
+        &[
+            TopLeftNinth,
+            TopCenterNinth,
+            TopRightNinth,
+            MiddleLeftNinth,
+            MiddleCenterNinth,
+            MiddleRightNinth,
+            BottomLeftNinth,
+            BottomCenterNinth,
+            BottomRightNinth,
+        ],
+    ];
+
+    fn area(rect: &Rect) -> u64 {
+        rect.width as u64 * rect.height as u64
+    }
+
+    #[test]
+    fn complementary_positions_tile_the_work_area() {
+        let options = LayoutOptions::default();
+        for work_area in &work_areas() {
+            for set in COMPLEMENTARY_SETS {
+                let frames: Vec<_> = set
+                    .iter()
+                    .map(|p| p.calculate_frame(work_area, work_area, &options))
+                    .collect();
+
+                for (position, frame) in set.iter().zip(&frames) {
+                    assert_eq!(
+                        frame.intersection(work_area),
+                        Some(*frame),
+                        "{:?} sticks out of {:?}",
+                        position,
+                        work_area
+                    );
+                }
+                for (i, a) in frames.iter().enumerate() {
+                    for (j, b) in frames.iter().enumerate().skip(i + 1) {
+                        assert_eq!(
+                            a.intersection(b),
+                            None,
+                            "{:?} and {:?} overlap in {:?}",
+                            set[i],
+                            set[j],
+                            work_area
+                        );
+                    }
+                }
+                // Inside the work area and without overlap, so the same total
+                // area means there is no gap either
+                assert_eq!(
+                    frames.iter().map(area).sum::<u64>(),
+                    area(work_area),
+                    "{:?} leave a gap in {:?}",
+                    set,
+                    work_area
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn span_covers_the_axis_in_order() {
+        for len in [0, 1, 7, 1279, 1920] {
+            for total in 1..=4 {
+                let mut next = 0;
+                for start in 0..total {
+                    let (offset, size) = span(len, (start, 1, total));
+                    assert_eq!(offset, next, "cell {} of {} along {}", start, total, len);
+                    next = offset + size as i32;
+                }
+                assert_eq!(next, len as i32, "{} cells along {}", total, len);
+            }
         }
     }
 }
@@ -1,23 +1,74 @@
 #![cfg(target_os = "windows")]
 
-use super::{Display, Rect, Result, Window, WindowHandle, WindowManagerError, WindowManagerTrait};
+use super::{Display, Insets, Rect, Result, Window, WindowHandle, WindowManagerError, WindowManagerTrait};
+use std::ffi::c_void;
 use std::mem;
 use std::ptr;
-use windows::Win32::Foundation::{BOOL, HWND, LPARAM, RECT, TRUE};
+use windows::Win32::Foundation::{BOOL, HWND, LPARAM, POINT, RECT, TRUE, WPARAM};
+use windows::core::{w, PCWSTR};
+use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_CLOAKED, DWMWA_EXTENDED_FRAME_BOUNDS};
 use windows::Win32::Graphics::Gdi::{
-    EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFOEXW,
+    EnumDisplayMonitors, GetMonitorInfoW, MonitorFromRect, HDC, HMONITOR, MONITORINFOEXW,
+    MONITOR_DEFAULTTONEAREST,
+};
+use windows::Win32::UI::HiDpi::{
+    GetAwarenessFromDpiAwarenessContext, GetDpiForMonitor, GetDpiForWindow,
+    GetWindowDpiAwarenessContext, SetThreadDpiAwarenessContext, DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2,
+    DPI_AWARENESS_PER_MONITOR_AWARE, MDT_EFFECTIVE_DPI,
 };
 use windows::Win32::UI::WindowsAndMessaging::{
-    GetForegroundWindow, GetWindowRect, GetWindowTextLengthW, GetWindowTextW, IsIconic,
-    IsWindowVisible, IsZoomed, SetWindowPos, ShowWindow, HWND_TOP, SET_WINDOW_POS_FLAGS,
-    SWP_NOACTIVATE, SWP_NOZORDER, SW_RESTORE,
+    EnumWindows, FindWindowExW, GetAncestor, GetClassNameW, GetWindow, GA_ROOT, GWL_STYLE, GW_OWNER,
+    WS_CAPTION, WS_POPUP, WS_THICKFRAME, GetForegroundWindow, GetSystemMetrics, GetWindowLongPtrW,
+    GetWindowPlacement, SendMessageTimeoutW, MINMAXINFO, SMTO_ABORTIFHUNG, SM_CXMAXTRACK, SM_CXMINTRACK,
+    SM_CYMAXTRACK, SM_CYMINTRACK, WM_GETMINMAXINFO,
+    GetWindowThreadProcessId, GetWindowRect, GetWindowTextLengthW, GetWindowTextW, IsIconic, SetForegroundWindow,
+    IsWindowVisible, IsZoomed, SetCursorPos, SetWindowPlacement, SetWindowPos, GWL_EXSTYLE, HWND_TOP,
+    SET_WINDOW_POS_FLAGS, SHOW_WINDOW_CMD, SWP_NOACTIVATE, SWP_NOZORDER, SW_SHOWNOACTIVATE, SW_SHOWNORMAL, WINDOWPLACEMENT,
+    WS_EX_TOOLWINDOW,
 };
 
-pub struct WindowsManager;
+pub struct WindowsManager {
+    // Bring windows to the front and focus them once snapped
+    activate_on_snap: bool,
+    // Take windows out of Windows 11 snap groups when snapping them
+    leave_os_snap_groups: bool,
+}
+
+// DPI at which Windows scales nothing (100%)
+const BASE_DPI: u32 = 96;
+
+// How many times to reapply a frame a UWP app didn't accept, and how long to
+// give it between attempts
+const FRAME_RETRIES: u32 = 3;
+const RETRY_DELAY_MS: u64 = 50;
+
+// How far (in pixels) a window may land from the requested frame
+const FRAME_TOLERANCE: i32 = 1;
 
 impl WindowsManager {
     pub fn new() -> Self {
-        Self
+        // Work in physical pixels on every monitor, so window and monitor rects
+        // share one coordinate space regardless of each monitor's scaling
+        unsafe {
+            SetThreadDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2);
+        }
+
+        Self {
+            activate_on_snap: false,
+            leave_os_snap_groups: true,
+        }
+    }
+
+    /// Set whether snapped windows are brought to the front and focused.
+    pub fn activate_on_snap(mut self, activate: bool) -> Self {
+        self.activate_on_snap = activate;
+        self
+    }
+
+    /// Set whether snapping takes windows out of the OS's snap groups.
+    pub fn leave_os_snap_groups(mut self, leave: bool) -> Self {
+        self.leave_os_snap_groups = leave;
+        self
     }
 
     /// Get the window title
@@ -49,6 +100,225 @@ impl WindowsManager {
         }
     }
 
+    /// Get the visible frame of a window. GetWindowRect includes the invisible
+    /// resize borders Windows 10+ draws around most windows, which would leave
+    /// gaps between snapped windows.
+    fn get_window_frame(&self, hwnd: HWND) -> Result<Rect> {
+        let mut rect = RECT::default();
+        let result = unsafe {
+            DwmGetWindowAttribute(
+                hwnd,
+                DWMWA_EXTENDED_FRAME_BOUNDS,
+                &mut rect as *mut RECT as *mut c_void,
+                mem::size_of::<RECT>() as u32,
+            )
+        };
+
+        match result {
+            Ok(()) => Ok(self.rect_from_win32(&rect)),
+            Err(_) => Ok(self.rect_from_win32(&self.get_window_rect(hwnd)?)),
+        }
+    }
+
+    /// Get the size of the invisible borders around a window at its current DPI,
+    /// as the amount the window rect extends past the visible frame on each side.
+    fn get_invisible_borders(&self, hwnd: HWND) -> Result<RECT> {
+        let outer = self.get_window_rect(hwnd)?;
+        let visible = self.get_window_frame(hwnd)?;
+
+        Ok(RECT {
+            left: visible.x - outer.left,
+            top: visible.y - outer.top,
+            right: outer.right - visible.right(),
+            bottom: outer.bottom - visible.bottom(),
+        })
+    }
+
+    /// Check if DWM is hiding the window even though it is "visible": windows on
+    /// other virtual desktops, and UWP windows that are suspended or closing
+    fn is_cloaked(&self, hwnd: HWND) -> bool {
+        let mut cloaked: u32 = 0;
+        let result = unsafe {
+            DwmGetWindowAttribute(
+                hwnd,
+                DWMWA_CLOAKED,
+                &mut cloaked as *mut u32 as *mut c_void,
+                mem::size_of::<u32>() as u32,
+            )
+        };
+
+        result.is_ok() && cloaked != 0
+    }
+
+    /// Get the window class name
+    fn get_class_name(&self, hwnd: HWND) -> String {
+        let mut buffer = [0u16; 256];
+        let len = unsafe { GetClassNameW(hwnd, &mut buffer) };
+        String::from_utf16_lossy(&buffer[..len.max(0) as usize])
+    }
+
+    /// Check if a window is a floating tool window or palette, which isn't
+    /// shown in the taskbar or Alt+Tab
+    fn is_tool_window(&self, hwnd: HWND) -> bool {
+        let ex_style = unsafe { GetWindowLongPtrW(hwnd, GWL_EXSTYLE) } as u32;
+        ex_style & WS_EX_TOOLWINDOW.0 != 0
+    }
+
+    /// Check if a window is a regular app window rather than a tool window,
+    /// menu, tooltip or other captionless popup
+    fn is_app_window(&self, hwnd: HWND) -> bool {
+        let style = unsafe { GetWindowLongPtrW(hwnd, GWL_STYLE) } as u32;
+        let popup = style & WS_POPUP.0 != 0;
+        let framed = style & (WS_CAPTION.0 | WS_THICKFRAME.0) != 0;
+
+        !self.is_tool_window(hwnd) && (!popup || framed)
+    }
+
+    /// Get the app window a window belongs to: its top-level window, and from
+    /// there up the chain of owners past tool palettes and popups (e.g. a
+    /// floating toolbar of an image editor belongs to the editor's window)
+    fn get_owning_app_window(&self, hwnd: HWND) -> HWND {
+        let mut hwnd = unsafe { GetAncestor(hwnd, GA_ROOT) };
+
+        while !self.is_app_window(hwnd) {
+            match unsafe { GetWindow(hwnd, GW_OWNER) } {
+                Ok(owner) if !owner.is_invalid() => hwnd = owner,
+                _ => break,
+            }
+        }
+
+        hwnd
+    }
+
+    /// Check if a window is the taskbar on any monitor
+    fn is_taskbar(&self, hwnd: HWND) -> bool {
+        matches!(
+            self.get_class_name(hwnd).as_str(),
+            "Shell_TrayWnd" | "Shell_SecondaryTrayWnd"
+        )
+    }
+
+    /// Check if a window is the ApplicationFrameHost frame UWP apps are shown in
+    fn is_app_frame(&self, hwnd: HWND) -> bool {
+        self.get_class_name(hwnd) == "ApplicationFrameWindow"
+    }
+
+    /// Get the UWP app's own window inside an ApplicationFrameHost frame. The
+    /// frame belongs to ApplicationFrameHost.exe; this belongs to the app.
+    fn get_core_window(&self, hwnd: HWND) -> Option<HWND> {
+        if !self.is_app_frame(hwnd) {
+            return None;
+        }
+
+        let core_window =
+            unsafe { FindWindowExW(hwnd, HWND::default(), w!("Windows.UI.Core.CoreWindow"), PCWSTR::null()) };
+        core_window.ok().filter(|child| !child.is_invalid())
+    }
+
+    /// Check if a window is an ApplicationFrameHost frame with no UWP app in it.
+    /// Suspended apps leave these empty frames behind, and moving them does nothing
+    fn is_empty_app_frame(&self, hwnd: HWND) -> bool {
+        self.is_app_frame(hwnd) && self.get_core_window(hwnd).is_none()
+    }
+
+    /// Check if a window is visible but can't actually be seen or moved
+    fn is_ghost(&self, hwnd: HWND) -> bool {
+        self.is_cloaked(hwnd) || self.is_empty_app_frame(hwnd)
+    }
+
+    /// Move and resize a window without changing its z-order or activating it
+    fn set_window_pos(&self, hwnd: HWND, x: i32, y: i32, width: i32, height: i32) -> Result<()> {
+        let flags: SET_WINDOW_POS_FLAGS = SWP_NOZORDER | SWP_NOACTIVATE;
+
+        unsafe {
+            SetWindowPos(hwnd, HWND_TOP, x, y, width, height, flags)
+                .map_err(|e| WindowManagerError::MoveError(format!("SetWindowPos failed: {}", e)))
+        }
+    }
+
+    /// Move a window so its visible frame, without the invisible borders, lands
+    /// on `frame`
+    fn set_visible_frame(&self, hwnd: HWND, frame: Rect) -> Result<()> {
+        let borders = self.get_invisible_borders(hwnd)?;
+        self.set_window_pos(
+            hwnd,
+            frame.x - borders.left,
+            frame.y - borders.top,
+            frame.width as i32 + borders.left + borders.right,
+            frame.height as i32 + borders.top + borders.bottom,
+        )
+    }
+
+    /// Get a window's minimum and maximum tracking size (as width, height pairs
+    /// of the whole window rect), by asking it the way Windows does when the
+    /// user resizes it
+    fn get_size_limits(&self, hwnd: HWND) -> Option<((i32, i32), (i32, i32))> {
+        unsafe {
+            // Windows fills in its defaults before the window adjusts them
+            let mut info = MINMAXINFO {
+                ptMinTrackSize: POINT {
+                    x: GetSystemMetrics(SM_CXMINTRACK),
+                    y: GetSystemMetrics(SM_CYMINTRACK),
+                },
+                ptMaxTrackSize: POINT {
+                    x: GetSystemMetrics(SM_CXMAXTRACK),
+                    y: GetSystemMetrics(SM_CYMAXTRACK),
+                },
+                ..Default::default()
+            };
+
+            // A hung window would otherwise block us forever
+            let result = SendMessageTimeoutW(
+                hwnd,
+                WM_GETMINMAXINFO,
+                WPARAM(0),
+                LPARAM(&mut info as *mut MINMAXINFO as isize),
+                SMTO_ABORTIFHUNG,
+                100,
+                None,
+            );
+            if result.0 == 0 {
+                return None;
+            }
+
+            Some((
+                (info.ptMinTrackSize.x, info.ptMinTrackSize.y),
+                (info.ptMaxTrackSize.x, info.ptMaxTrackSize.y),
+            ))
+        }
+    }
+
+    /// Fit `frame` to the window's minimum and maximum size, keeping it
+    /// centered on the requested frame, as Windows would otherwise clamp the
+    /// size and leave the window stuck to the frame's top-left corner
+    fn fit_size_limits(&self, hwnd: HWND, frame: Rect) -> Result<Rect> {
+        let Some(((min_width, min_height), (max_width, max_height))) = self.get_size_limits(hwnd) else {
+            return Ok(frame);
+        };
+
+        // The limits include the invisible borders, the frame doesn't
+        let borders = self.get_invisible_borders(hwnd)?;
+        let border_width = borders.left + borders.right;
+        let border_height = borders.top + borders.bottom;
+
+        let fit = |start: i32, length: u32, min: i32, max: i32| {
+            let fitted = (length as i32).clamp(min.max(1), max.max(min).max(1));
+            (start + (length as i32 - fitted) / 2, fitted as u32)
+        };
+        let (x, width) = fit(frame.x, frame.width, min_width - border_width, max_width - border_width);
+        let (y, height) = fit(frame.y, frame.height, min_height - border_height, max_height - border_height);
+
+        let fitted = Rect::new(x, y, width, height);
+        if fitted != frame {
+            eprintln!(
+                "Window can't be {}x{}, using {}x{} centered on the target frame",
+                frame.width, frame.height, width, height
+            );
+        }
+
+        Ok(fitted)
+    }
+
     /// Check if window is maximized
     fn is_maximized(&self, hwnd: HWND) -> bool {
         unsafe { IsZoomed(hwnd).as_bool() }
@@ -59,12 +329,318 @@ impl WindowsManager {
         unsafe { IsIconic(hwnd).as_bool() }
     }
 
-    /// Restore window if minimized or maximized
-    fn restore_window(&self, hwnd: HWND) {
+    /// Restore a minimized or maximized window straight to `frame`. Restoring
+    /// with ShowWindow would first bring it back to its old restore rect.
+    fn restore_window(&self, hwnd: HWND, frame: Rect) -> Result<()> {
+        if !self.is_minimized(hwnd) && !self.is_maximized(hwnd) {
+            return Ok(());
+        }
+
+        let rect = RECT {
+            left: frame.x,
+            top: frame.y,
+            right: frame.right(),
+            bottom: frame.bottom(),
+        };
+        self.set_restore_rect(hwnd, rect, SW_SHOWNORMAL)
+    }
+
+    /// Check if the OS has snapped a window: shown normally, but not at the
+    /// rect it would be restored to
+    fn is_arranged(&self, hwnd: HWND) -> bool {
+        if self.is_minimized(hwnd) || self.is_maximized(hwnd) {
+            return false;
+        }
+
+        let mut placement = WINDOWPLACEMENT {
+            length: mem::size_of::<WINDOWPLACEMENT>() as u32,
+            ..Default::default()
+        };
+        if unsafe { GetWindowPlacement(hwnd, &mut placement) }.is_err() {
+            return false;
+        }
+
+        let Ok(rect) = self.get_window_rect(hwnd) else {
+            return false;
+        };
+        let current = self.screen_to_workspace(hwnd, rect);
+        let normal = placement.rcNormalPosition;
+
+        (current.left, current.top, current.right, current.bottom)
+            != (normal.left, normal.top, normal.right, normal.bottom)
+    }
+
+    /// Make the window's current rect the one it returns to when un-maximized.
+    /// Windows keeps the restore rect from before a maximize or an Aero Snap,
+    /// which SetWindowPos doesn't touch.
+    fn sync_restore_rect(&self, hwnd: HWND) -> Result<()> {
+        let rect = self.get_window_rect(hwnd)?;
+        self.set_restore_rect(hwnd, rect, SW_SHOWNOACTIVATE)
+    }
+
+    /// Set the window's restore rect (in screen coordinates) and show state
+    fn set_restore_rect(&self, hwnd: HWND, rect: RECT, show: SHOW_WINDOW_CMD) -> Result<()> {
+        let mut placement = WINDOWPLACEMENT {
+            length: mem::size_of::<WINDOWPLACEMENT>() as u32,
+            ..Default::default()
+        };
+
+        unsafe {
+            GetWindowPlacement(hwnd, &mut placement).map_err(|e| {
+                WindowManagerError::MoveError(format!("GetWindowPlacement failed: {}", e))
+            })?;
+        }
+
+        placement.rcNormalPosition = self.screen_to_workspace(hwnd, rect);
+        placement.showCmd = show.0 as u32;
+
+        unsafe {
+            SetWindowPlacement(hwnd, &placement).map_err(|e| {
+                WindowManagerError::MoveError(format!("SetWindowPlacement failed: {}", e))
+            })
+        }
+    }
+
+    /// Convert a screen rect to the workspace coordinates WINDOWPLACEMENT uses,
+    /// which are relative to the monitor's work area unless the window is a
+    /// tool window
+    fn screen_to_workspace(&self, hwnd: HWND, rect: RECT) -> RECT {
+        if self.is_tool_window(hwnd) {
+            return rect;
+        }
+
+        let hmonitor = unsafe { MonitorFromRect(&rect, MONITOR_DEFAULTTONEAREST) };
+        let Ok(info) = self.get_monitor_info(hmonitor) else {
+            return rect;
+        };
+
+        let dx = info.monitorInfo.rcWork.left - info.monitorInfo.rcMonitor.left;
+        let dy = info.monitorInfo.rcWork.top - info.monitorInfo.rcMonitor.top;
+        RECT {
+            left: rect.left - dx,
+            top: rect.top - dy,
+            right: rect.right - dx,
+            bottom: rect.bottom - dy,
+        }
+    }
+
+    /// Build a Display from an HMONITOR
+    fn display_from_monitor(&self, hmonitor: HMONITOR) -> Result<Display> {
+        let info = self.get_monitor_info(hmonitor)?;
+
+        let is_primary = (info.monitorInfo.dwFlags & 1) != 0; // MONITORINFOF_PRIMARY = 1
+        let device = utf16_until_nul(&info.szDevice);
+
+        // Prefer the monitor's own name (from its EDID) over the GDI device name
+        let name = self.get_monitor_friendly_name(&device).unwrap_or(device);
+
+        Ok(Display {
+            name,
+            bounds: self.rect_from_win32(&info.monitorInfo.rcMonitor),
+            work_area: self.rect_from_win32(&info.monitorInfo.rcWork),
+            is_primary,
+            scale_factor: self.get_monitor_scale(hmonitor),
+            safe_area: Insets::default(),
+        })
+    }
+
+    /// Get the friendly name of the monitor attached to a GDI display device
+    /// (e.g. "DELL U2720Q" for "\\.\DISPLAY1") using the display configuration API.
+    fn get_monitor_friendly_name(&self, device: &str) -> Option<String> {
+        use windows::Win32::Devices::Display::{
+            DisplayConfigGetDeviceInfo, GetDisplayConfigBufferSizes, QueryDisplayConfig,
+            DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME, DISPLAYCONFIG_DEVICE_INFO_GET_TARGET_NAME,
+            DISPLAYCONFIG_MODE_INFO, DISPLAYCONFIG_PATH_INFO, DISPLAYCONFIG_SOURCE_DEVICE_NAME,
+            DISPLAYCONFIG_TARGET_DEVICE_NAME, QDC_ONLY_ACTIVE_PATHS,
+        };
+        use windows::Win32::Foundation::ERROR_SUCCESS;
+
+        unsafe {
+            let mut path_count: u32 = 0;
+            let mut mode_count: u32 = 0;
+            if GetDisplayConfigBufferSizes(QDC_ONLY_ACTIVE_PATHS, &mut path_count, &mut mode_count)
+                != ERROR_SUCCESS
+            {
+                return None;
+            }
+
+            let mut paths = vec![DISPLAYCONFIG_PATH_INFO::default(); path_count as usize];
+            let mut modes = vec![DISPLAYCONFIG_MODE_INFO::default(); mode_count as usize];
+            if QueryDisplayConfig(
+                QDC_ONLY_ACTIVE_PATHS,
+                &mut path_count,
+                paths.as_mut_ptr(),
+                &mut mode_count,
+                modes.as_mut_ptr(),
+                None,
+            ) != ERROR_SUCCESS
+            {
+                return None;
+            }
+            paths.truncate(path_count as usize);
+
+            for path in &paths {
+                let mut source: DISPLAYCONFIG_SOURCE_DEVICE_NAME = mem::zeroed();
+                source.header.r#type = DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME;
+                source.header.size = mem::size_of::<DISPLAYCONFIG_SOURCE_DEVICE_NAME>() as u32;
+                source.header.adapterId = path.sourceInfo.adapterId;
+                source.header.id = path.sourceInfo.id;
+
+                if DisplayConfigGetDeviceInfo(&mut source.header) != 0
+                    || utf16_until_nul(&source.viewGdiDeviceName) != device
+                {
+                    continue;
+                }
+
+                let mut target: DISPLAYCONFIG_TARGET_DEVICE_NAME = mem::zeroed();
+                target.header.r#type = DISPLAYCONFIG_DEVICE_INFO_GET_TARGET_NAME;
+                target.header.size = mem::size_of::<DISPLAYCONFIG_TARGET_DEVICE_NAME>() as u32;
+                target.header.adapterId = path.targetInfo.adapterId;
+                target.header.id = path.targetInfo.id;
+
+                if DisplayConfigGetDeviceInfo(&mut target.header) != 0 {
+                    return None;
+                }
+
+                // Built-in panels often have no EDID name
+                let name = utf16_until_nul(&target.monitorFriendlyDeviceName);
+                return (!name.is_empty()).then_some(name);
+            }
+
+            None
+        }
+    }
+
+    /// Get the scale factor of a monitor from its effective DPI (96 DPI = 100%)
+    fn get_monitor_scale(&self, hmonitor: HMONITOR) -> f64 {
+        self.get_monitor_dpi(hmonitor) as f64 / BASE_DPI as f64
+    }
+
+    /// Get the effective DPI of a monitor
+    fn get_monitor_dpi(&self, hmonitor: HMONITOR) -> u32 {
+        let mut dpi_x: u32 = BASE_DPI;
+        let mut dpi_y: u32 = BASE_DPI;
+
+        unsafe {
+            if GetDpiForMonitor(hmonitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y).is_err() {
+                return BASE_DPI;
+            }
+        }
+
+        dpi_x
+    }
+
+    /// Get the DPI a window is currently scaled for
+    fn get_window_dpi(&self, hwnd: HWND) -> u32 {
+        match unsafe { GetDpiForWindow(hwnd) } {
+            0 => BASE_DPI,
+            dpi => dpi,
+        }
+    }
+
+    /// Check if a window rescales itself when it moves to a monitor with a
+    /// different DPI. Windows stretches the others itself, without telling them.
+    fn is_per_monitor_aware(&self, hwnd: HWND) -> bool {
+        unsafe {
+            let context = GetWindowDpiAwarenessContext(hwnd);
+            GetAwarenessFromDpiAwarenessContext(context) == DPI_AWARENESS_PER_MONITOR_AWARE
+        }
+    }
+
+    /// Get the ID of the process that owns a window
+    fn get_window_pid(&self, hwnd: HWND) -> u32 {
+        let mut pid: u32 = 0;
         unsafe {
-            if self.is_minimized(hwnd) || self.is_maximized(hwnd) {
-                let _ = ShowWindow(hwnd, SW_RESTORE);
+            GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        }
+        pid
+    }
+
+    /// Get the ID of the app a window belongs to, looking through the
+    /// ApplicationFrameHost frame of UWP apps
+    fn get_app_pid(&self, hwnd: HWND) -> u32 {
+        self.get_window_pid(self.get_core_window(hwnd).unwrap_or(hwnd))
+    }
+
+    /// Check if a process runs elevated (as administrator). Processes we can't
+    /// even query are treated as elevated, since that is the usual reason.
+    fn is_process_elevated(&self, pid: u32) -> bool {
+        use windows::Win32::Foundation::{CloseHandle, HANDLE};
+        use windows::Win32::Security::{GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY};
+        use windows::Win32::System::Threading::{
+            OpenProcess, OpenProcessToken, PROCESS_QUERY_LIMITED_INFORMATION,
+        };
+
+        unsafe {
+            let Ok(process) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) else {
+                return true;
+            };
+
+            let mut token = HANDLE::default();
+            let opened = OpenProcessToken(process, TOKEN_QUERY, &mut token);
+            let _ = CloseHandle(process);
+            if opened.is_err() {
+                return true;
             }
+
+            let mut elevation = TOKEN_ELEVATION::default();
+            let mut len: u32 = 0;
+            let result = GetTokenInformation(
+                token,
+                TokenElevation,
+                Some(&mut elevation as *mut TOKEN_ELEVATION as *mut c_void),
+                mem::size_of::<TOKEN_ELEVATION>() as u32,
+                &mut len,
+            );
+            let _ = CloseHandle(token);
+
+            result.is_ok() && elevation.TokenIsElevated != 0
+        }
+    }
+
+    /// Check if Windows will block us from moving a window because its process
+    /// runs at a higher integrity level (UIPI). SetWindowPos then fails without
+    /// an error, so this has to be checked up front.
+    fn is_blocked_by_elevation(&self, hwnd: HWND) -> bool {
+        self.is_process_elevated(self.get_window_pid(hwnd))
+            && !self.is_process_elevated(std::process::id())
+    }
+
+    /// Get the executable name (without extension) of the process that owns a window
+    fn get_app_name(&self, hwnd: HWND) -> String {
+        use windows::core::PWSTR;
+        use windows::Win32::Foundation::CloseHandle;
+        use windows::Win32::System::Threading::{
+            OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+            PROCESS_QUERY_LIMITED_INFORMATION,
+        };
+
+        let pid = self.get_app_pid(hwnd);
+
+        unsafe {
+            let Ok(process) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) else {
+                return String::new();
+            };
+
+            let mut buffer = [0u16; 260];
+            let mut len = buffer.len() as u32;
+            let result = QueryFullProcessImageNameW(
+                process,
+                PROCESS_NAME_WIN32,
+                PWSTR(buffer.as_mut_ptr()),
+                &mut len,
+            );
+            let _ = CloseHandle(process);
+
+            if result.is_err() {
+                return String::new();
+            }
+
+            let path = String::from_utf16_lossy(&buffer[..len as usize]);
+            std::path::Path::new(&path)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default()
         }
     }
 
@@ -95,27 +671,47 @@ impl WindowsManager {
     }
 }
 
+/// Decode a fixed-size, NUL-terminated UTF-16 buffer
+fn utf16_until_nul(buffer: &[u16]) -> String {
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    String::from_utf16_lossy(&buffer[..len])
+}
+
 impl WindowManagerTrait for WindowsManager {
     fn get_focused_window(&self) -> Result<Window> {
         unsafe {
-            let hwnd = GetForegroundWindow();
+            let foreground = GetForegroundWindow();
 
-            if hwnd.0 == ptr::null_mut() {
+            if foreground.0 == ptr::null_mut() {
                 return Err(WindowManagerError::NoFocusedWindow);
             }
 
+            let hwnd = self.get_owning_app_window(foreground);
+
+            // Clicking the taskbar (e.g. a jump list task) focuses it, and stray
+            // popups can hold focus without an app window behind them, so act on
+            // the window that was in front before
+            if self.is_taskbar(hwnd) || !self.is_app_window(hwnd) {
+                return self
+                    .get_visible_windows()?
+                    .into_iter()
+                    .next()
+                    .ok_or(WindowManagerError::NoFocusedWindow);
+            }
+
             // Check if window is visible
-            if !IsWindowVisible(hwnd).as_bool() {
+            if !IsWindowVisible(hwnd).as_bool() || self.is_ghost(hwnd) {
                 return Err(WindowManagerError::NoFocusedWindow);
             }
 
             let title = self.get_window_title(hwnd);
-            let rect = self.get_window_rect(hwnd)?;
+            let frame = self.get_window_frame(hwnd)?;
 
             Ok(Window {
                 handle: WindowHandle::Windows(hwnd.0 as isize),
+                app: self.get_app_name(hwnd),
                 title,
-                frame: self.rect_from_win32(&rect),
+                frame,
             })
         }
     }
@@ -125,23 +721,69 @@ impl WindowManagerTrait for WindowsManager {
             WindowHandle::Windows(h) => HWND(h as *mut _),
         };
 
-        // Restore window first if it's minimized or maximized
-        self.restore_window(hwnd);
+        if self.is_blocked_by_elevation(hwnd) {
+            return Err(WindowManagerError::Elevated);
+        }
 
-        unsafe {
-            // Use SetWindowPos to move and resize
-            let flags: SET_WINDOW_POS_FLAGS = SWP_NOZORDER | SWP_NOACTIVATE;
+        // Windows snapped by the OS (Snap Layouts, Win+Arrow) are "arranged":
+        // shown normally but away from their restore rect. That state is what
+        // ties them into a snap group.
+        let arranged = self.is_arranged(hwnd);
 
-            SetWindowPos(
+        // Restore window first if it's minimized or maximized
+        self.restore_window(hwnd, frame)?;
+
+        let target_rect = RECT {
+            left: frame.x,
+            top: frame.y,
+            right: frame.right(),
+            bottom: frame.bottom(),
+        };
+        let target_monitor = unsafe { MonitorFromRect(&target_rect, MONITOR_DEFAULTTONEAREST) };
+
+        // Moving onto a monitor with a different DPI makes the window rescale itself
+        // (WM_DPICHANGED), changing its size and border widths. Move it there first
+        // so the final size is applied after it has adapted. DPI-unaware and
+        // system-aware windows always report the same DPI and are scaled by
+        // Windows instead, so moving them twice would only flicker.
+        if self.is_per_monitor_aware(hwnd)
+            && self.get_window_dpi(hwnd) != self.get_monitor_dpi(target_monitor)
+        {
+            self.set_window_pos(
                 hwnd,
-                HWND_TOP,
                 frame.x,
                 frame.y,
                 frame.width as i32,
                 frame.height as i32,
-                flags,
-            )
-            .map_err(|e| WindowManagerError::MoveError(format!("SetWindowPos failed: {}", e)))?;
+            )?;
+        }
+
+        let frame = self.fit_size_limits(hwnd, frame)?;
+        self.set_visible_frame(hwnd, frame)?;
+
+        // UWP apps resize their content asynchronously and often push back on
+        // the first resize, so check where the frame ended up and apply it again
+        if self.get_core_window(hwnd).is_some() {
+            for _ in 0..FRAME_RETRIES {
+                std::thread::sleep(std::time::Duration::from_millis(RETRY_DELAY_MS));
+                if self.get_window_frame(hwnd)?.is_close_to(&frame, FRAME_TOLERANCE) {
+                    break;
+                }
+                self.set_visible_frame(hwnd, frame)?;
+            }
+        }
+
+        // So un-maximizing later comes back here rather than to a stale frame.
+        // This also ends the arranged state, taking the window out of its
+        // snap group, so it is skipped for arranged windows if that's unwanted
+        if self.leave_os_snap_groups || !arranged {
+            self.sync_restore_rect(hwnd)?;
+        }
+
+        // Snaps from the tray leave focus on the taskbar otherwise. Windows
+        // allows this since we just received the input that triggered the snap.
+        if self.activate_on_snap {
+            let _ = unsafe { SetForegroundWindow(hwnd) };
         }
 
         Ok(())
@@ -162,19 +804,7 @@ impl WindowManagerTrait for WindowsManager {
                 return Err(WindowManagerError::DisplayError);
             }
 
-            let info = self.get_monitor_info(hmonitor)?;
-
-            let is_primary = (info.monitorInfo.dwFlags & 1) != 0; // MONITORINFOF_PRIMARY = 1
-            let name = String::from_utf16_lossy(
-                &info.szDevice[..info.szDevice.iter().position(|&c| c == 0).unwrap_or(info.szDevice.len())]
-            );
-
-            Ok(Display {
-                name,
-                bounds: self.rect_from_win32(&info.monitorInfo.rcMonitor),
-                work_area: self.rect_from_win32(&info.monitorInfo.rcWork),
-                is_primary,
-            })
+            self.display_from_monitor(hmonitor)
         }
     }
 
@@ -219,22 +849,79 @@ impl WindowManagerTrait for WindowsManager {
         let mut displays = Vec::new();
 
         for hmonitor in collector.monitors {
-            let info = self.get_monitor_info(hmonitor)?;
+            displays.push(self.display_from_monitor(hmonitor)?);
+        }
 
-            let is_primary = (info.monitorInfo.dwFlags & 1) != 0;
-            let name = String::from_utf16_lossy(
-                &info.szDevice[..info.szDevice.iter().position(|&c| c == 0).unwrap_or(info.szDevice.len())]
-            );
+        Ok(displays)
+    }
 
-            displays.push(Display {
-                name,
-                bounds: self.rect_from_win32(&info.monitorInfo.rcMonitor),
-                work_area: self.rect_from_win32(&info.monitorInfo.rcWork),
-                is_primary,
+    fn get_visible_windows(&self) -> Result<Vec<Window>> {
+        unsafe extern "system" fn enum_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
+            unsafe {
+                let hwnds = &mut *(lparam.0 as *mut Vec<HWND>);
+                hwnds.push(hwnd);
+            }
+            TRUE
+        }
+
+        let mut hwnds: Vec<HWND> = Vec::new();
+
+        unsafe {
+            EnumWindows(Some(enum_callback), LPARAM(&mut hwnds as *mut _ as isize))
+                .map_err(|_| WindowManagerError::WindowListError)?;
+        }
+
+        let mut windows = Vec::new();
+
+        for hwnd in hwnds {
+            let visible = unsafe { IsWindowVisible(hwnd).as_bool() };
+            if !visible || self.is_minimized(hwnd) || self.is_ghost(hwnd) || self.is_tool_window(hwnd) {
+                continue;
+            }
+
+            // Untitled windows are mostly hidden helpers, not real app windows
+            let title = self.get_window_title(hwnd);
+            if title.is_empty() {
+                continue;
+            }
+
+            let Ok(frame) = self.get_window_frame(hwnd) else {
+                continue;
+            };
+
+            windows.push(Window {
+                handle: WindowHandle::Windows(hwnd.0 as isize),
+                app: self.get_app_name(hwnd),
+                title,
+                frame,
             });
         }
 
-        Ok(displays)
+        Ok(windows)
+    }
+
+    fn get_app_windows(&self, window: &Window) -> Result<Vec<Window>> {
+        let hwnd = match window.handle {
+            WindowHandle::Windows(h) => HWND(h as *mut _),
+        };
+        let pid = self.get_app_pid(hwnd);
+
+        let windows = self
+            .get_visible_windows()?
+            .into_iter()
+            .filter(|w| match w.handle {
+                WindowHandle::Windows(h) => self.get_app_pid(HWND(h as *mut _)) == pid,
+            })
+            .collect();
+
+        Ok(windows)
+    }
+
+    fn move_cursor(&self, x: i32, y: i32) -> Result<()> {
+        unsafe {
+            SetCursorPos(x, y)
+                .map_err(|e| WindowManagerError::MoveError(format!("SetCursorPos failed: {}", e)))
+        }
     }
 }
 
@@ -2,8 +2,6 @@ import { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { getVersion } from "@tauri-apps/api/app";
-import { check, Update } from "@tauri-apps/plugin-updater";
-import { relaunch } from "@tauri-apps/plugin-process";
 
 function Logo() {
   return (
@@ -163,6 +161,242 @@ const thirds: ShortcutItem[] = [
   { name: "Right ⅔", shortcut: "⌃⌥R", action: "right_two_thirds" },
 ];
 
+interface PickableWindow {
+  // Platform-specific; passed back to the backend as-is
+  handle: unknown;
+  app: string;
+  title: string;
+}
+
+const pickerPositions: ShortcutItem[] = [...halves, ...quarters, ...thirds, ...display];
+
+function WindowPicker() {
+  const [windows, setWindows] = useState<PickableWindow[]>([]);
+  const [position, setPosition] = useState<SnapPosition>("left_half");
+  const [error, setError] = useState<string | null>(null);
+
+  const refresh = async () => {
+    try {
+      setWindows(await invoke<PickableWindow[]>("get_visible_windows"));
+      setError(null);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const snap = async (window: PickableWindow) => {
+    try {
+      await invoke("snap_window_by_handle", { handle: window.handle, position });
+      setError(null);
+    } catch (e) {
+      setError(String(e));
+      refresh();
+    }
+  };
+
+  useEffect(() => {
+    refresh();
+  }, []);
+
+  return (
+    <div className="mt-4">
+      <div className="flex items-center gap-2 mb-2">
+        <h3 className="text-xs font-semibold text-gray-400 uppercase tracking-wider flex-1">
+          Windows
+        </h3>
+        <SnapPreview position={position} />
+        <select
+          value={position}
+          onChange={(e) => setPosition(e.target.value as SnapPosition)}
+          className="bg-gray-800 border border-gray-700 rounded text-xs text-gray-200 px-1.5 py-1"
+        >
+          {pickerPositions.map((item) => (
+            <option key={item.action} value={item.action}>
+              {item.name}
+            </option>
+          ))}
+        </select>
+        <button
+          onClick={refresh}
+          className="text-xs text-gray-400 hover:text-white transition-colors"
+        >
+          Refresh
+        </button>
+      </div>
+      <div className="flex flex-col max-h-40 overflow-y-auto">
+        {windows.map((window, i) => (
+          <button
+            key={i}
+            onClick={() => snap(window)}
+            className="flex items-center gap-2 py-1.5 px-2 rounded text-left hover:bg-gray-800 transition-colors"
+          >
+            <span className="text-sm text-gray-200 truncate">{window.title || window.app}</span>
+            <span className="text-xs text-gray-500 truncate flex-1 text-right">{window.app}</span>
+          </button>
+        ))}
+      </div>
+      {error && <p className="mt-1 text-xs text-red-300">{error}</p>}
+    </div>
+  );
+}
+
+// FancyZones is part of PowerToys, so its layouts only exist on Windows
+const isWindows = navigator.userAgent.includes("Windows");
+const isMac = navigator.userAgent.includes("Mac");
+
+function ImportFancyZones() {
+  const [status, setStatus] = useState<string | null>(null);
+
+  const importLayouts = async () => {
+    try {
+      const count = await invoke<number>("import_fancyzones_layouts", { path: null });
+      setStatus(`Imported ${count} zones`);
+    } catch (e) {
+      setStatus(String(e));
+    }
+  };
+
+  return (
+    <button
+      onClick={importLayouts}
+      className="text-gray-400 hover:text-white transition-colors"
+    >
+      {status ?? "Import FancyZones"}
+    </button>
+  );
+}
+
+function ImportMacShortcuts() {
+  const [status, setStatus] = useState<string | null>(null);
+
+  const importShortcuts = async () => {
+    try {
+      const count = await invoke<number>("import_mac_shortcuts", { path: null });
+      setStatus(`Imported ${count} shortcuts`);
+    } catch (e) {
+      setStatus(String(e));
+    }
+  };
+
+  return (
+    <button
+      onClick={importShortcuts}
+      className="text-gray-400 hover:text-white transition-colors"
+    >
+      {status ?? "Import Rectangle/Spectacle shortcuts"}
+    </button>
+  );
+}
+
+function SettingsTransfer() {
+  const [status, setStatus] = useState<string | null>(null);
+
+  const exportSettings = async () => {
+    try {
+      const path = await invoke<string>("export_settings", { path: null });
+      setStatus(`Exported to ${path}`);
+    } catch (e) {
+      setStatus(String(e));
+    }
+  };
+
+  const importSettings = async () => {
+    try {
+      await invoke("import_settings", { path: null });
+      setStatus("Settings imported");
+    } catch (e) {
+      setStatus(String(e));
+    }
+  };
+
+  if (status) {
+    return <span className="text-gray-400">{status}</span>;
+  }
+
+  return (
+    <>
+      <button
+        onClick={exportSettings}
+        className="text-gray-400 hover:text-white transition-colors"
+      >
+        Export settings
+      </button>
+      <button
+        onClick={importSettings}
+        className="text-gray-400 hover:text-white transition-colors"
+      >
+        Import settings
+      </button>
+    </>
+  );
+}
+
+interface ConfigIssue {
+  severity: "error" | "warning";
+  setting: string;
+  message: string;
+}
+
+// Problems in the config file, rechecked whenever the window is shown
+function ConfigProblems() {
+  const [issues, setIssues] = useState<ConfigIssue[]>([]);
+
+  useEffect(() => {
+    const validate = () => {
+      invoke<ConfigIssue[]>("validate_config").then(setIssues).catch(console.error);
+    };
+    validate();
+    window.addEventListener("focus", validate);
+    return () => window.removeEventListener("focus", validate);
+  }, []);
+
+  if (issues.length === 0) return null;
+
+  return (
+    <div className="mb-4 p-3 bg-red-900/30 border border-red-700 rounded-lg text-xs">
+      <h3 className="font-medium text-red-300 mb-1">Config problems</h3>
+      <ul className="space-y-0.5">
+        {issues.map((issue, i) => (
+          <li key={i} className={issue.severity === "error" ? "text-red-300" : "text-yellow-300"}>
+            {issue.setting && <span className="font-mono">{issue.setting}: </span>}
+            {issue.message}
+          </li>
+        ))}
+      </ul>
+    </div>
+  );
+}
+
+type UpdateChannel = "stable" | "beta";
+
+function UpdateChannelSelect() {
+  const [channel, setChannel] = useState<UpdateChannel | null>(null);
+
+  useEffect(() => {
+    invoke<{ update_channel: UpdateChannel }>("get_config")
+      .then((config) => setChannel(config.update_channel))
+      .catch(console.error);
+  }, []);
+
+  const changeChannel = (value: UpdateChannel) => {
+    setChannel(value);
+    invoke("set_update_channel", { channel: value }).catch(console.error);
+  };
+
+  if (!channel) return null;
+
+  return (
+    <select
+      value={channel}
+      onChange={(e) => changeChannel(e.target.value as UpdateChannel)}
+      className="bg-gray-900 text-gray-400 hover:text-white transition-colors"
+    >
+      <option value="stable">Stable</option>
+      <option value="beta">Beta</option>
+    </select>
+  );
+}
+
 function useUpdater() {
   const [checking, setChecking] = useState(false);
   const [available, setAvailable] = useState(false);
@@ -170,44 +404,32 @@ function useUpdater() {
   const [downloading, setDownloading] = useState(false);
   const [progress, setProgress] = useState(0);
   const [error, setError] = useState<string | null>(null);
-  const [update, setUpdate] = useState<Update | null>(null);
 
   // Listen for update events from backend (tray menu or periodic checks)
   useEffect(() => {
-    const unlisten = listen<string>("update-available", async (event) => {
+    const unlistenAvailable = listen<string>("update-available", (event) => {
       setVersion(event.payload);
       setAvailable(true);
-      // Fetch the update object so we can install it
-      try {
-        const result = await check();
-        if (result) {
-          setUpdate(result);
-        }
-      } catch (e) {
-        console.log("Failed to fetch update object:", e);
-      }
+    });
+    const unlistenProgress = listen<number>("update-progress", (event) => {
+      setProgress(event.payload);
     });
 
     return () => {
-      unlisten.then((fn) => fn());
+      unlistenAvailable.then((fn) => fn());
+      unlistenProgress.then((fn) => fn());
     };
   }, []);
 
-  const checkForUpdates = async () => {
+  // The backend checks the configured update channel and updates the tray.
+  // Checking by hand also offers a skipped version again
+  const checkForUpdates = async (includeSkipped: boolean) => {
     setChecking(true);
     setError(null);
     try {
-      const result = await check();
-      if (result) {
-        setAvailable(true);
-        setVersion(result.version);
-        setUpdate(result);
-        // Notify backend to update tray
-        invoke("set_update_available", { available: true, version: result.version }).catch(console.error);
-      } else {
-        setAvailable(false);
-        invoke("set_update_available", { available: false, version: null }).catch(console.error);
-      }
+      const result = await invoke<string | null>("check_for_update", { includeSkipped });
+      setAvailable(result !== null);
+      setVersion(result);
     } catch (e) {
       // Silently fail - update check failures (private repo, no releases, network issues)
       // shouldn't alarm the user
@@ -217,43 +439,37 @@ function useUpdater() {
     }
   };
 
+  // Progress arrives as update-progress events; the app restarts when done
   const downloadAndInstall = async () => {
-    if (!update) return;
     setDownloading(true);
     setProgress(0);
     setError(null);
 
     try {
-      let downloaded = 0;
-      let contentLength = 0;
-
-      await update.downloadAndInstall((event) => {
-        if (event.event === "Started") {
-          contentLength = event.data.contentLength || 0;
-        } else if (event.event === "Progress") {
-          downloaded += event.data.chunkLength;
-          if (contentLength > 0) {
-            setProgress(Math.round((downloaded / contentLength) * 100));
-          }
-        } else if (event.event === "Finished") {
-          setProgress(100);
-        }
-      });
-
-      await relaunch();
+      await invoke("install_update");
     } catch (e) {
       setError(e instanceof Error ? e.message : String(e));
       setDownloading(false);
     }
   };
 
-  return { checking, available, version, downloading, progress, error, checkForUpdates, downloadAndInstall };
+  const skipVersion = async () => {
+    try {
+      await invoke("skip_update");
+      setAvailable(false);
+      setVersion(null);
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    }
+  };
+
+  return { checking, available, version, downloading, progress, error, checkForUpdates, downloadAndInstall, skipVersion };
 }
 
 function App() {
   const [accessibilityEnabled, setAccessibilityEnabled] = useState<boolean | null>(null);
   const [appVersion, setAppVersion] = useState<string>("");
-  const { checking, available, version, downloading, progress, error, checkForUpdates, downloadAndInstall } = useUpdater();
+  const { checking, available, version, downloading, progress, error, checkForUpdates, downloadAndInstall, skipVersion } = useUpdater();
 
   useEffect(() => {
     getVersion().then(setAppVersion).catch(console.error);
@@ -270,12 +486,16 @@ function App() {
     }
   };
 
-  const openAccessibilitySettings = async () => {
+  const requestAccessibility = async () => {
     try {
-      await invoke("open_accessibility_settings");
-      setTimeout(checkAccessibility, 1000);
+      const granted = await invoke<boolean>("request_accessibility");
+      if (granted) {
+        checkAccessibility();
+      } else {
+        setTimeout(checkAccessibility, 1000);
+      }
     } catch (e) {
-      console.error("Failed to open settings:", e);
+      console.error("Failed to request accessibility:", e);
     }
   };
 
@@ -285,9 +505,15 @@ function App() {
     return () => clearInterval(interval);
   }, []);
 
-  // Check for updates on mount
+  // Check for updates on mount, unless automatic checks are turned off
   useEffect(() => {
-    checkForUpdates();
+    invoke<{ auto_update_check: boolean }>("get_config")
+      .then((config) => {
+        if (config.auto_update_check) {
+          checkForUpdates(false);
+        }
+      })
+      .catch(console.error);
   }, []);
 
   return (
@@ -317,10 +543,10 @@ function App() {
               <p className="text-xs text-yellow-200/80">Enable to move and resize windows.</p>
             </div>
             <button
-              onClick={openAccessibilitySettings}
+              onClick={requestAccessibility}
               className="px-2.5 py-1 bg-yellow-600 hover:bg-yellow-500 text-white rounded text-xs font-medium transition-colors flex-shrink-0"
             >
-              Open Settings
+              Grant Access
             </button>
           </div>
         </div>
@@ -336,6 +562,8 @@ function App() {
         </div>
       )}
 
+      <ConfigProblems />
+
       {/* 3-Column Shortcuts Grid */}
       <div className="grid grid-cols-3 gap-6">
         <div>
@@ -348,6 +576,9 @@ function App() {
         <ShortcutColumn title="Thirds" items={thirds} />
       </div>
 
+      {/* Snap any window without focusing it first */}
+      {accessibilityEnabled === true && <WindowPicker />}
+
       {/* Update Section */}
       {available && version && (
         <div className="mt-4 p-3 bg-blue-900/50 border border-blue-600 rounded-lg">
@@ -358,13 +589,23 @@ function App() {
               </svg>
               <span className="text-sm text-blue-200">Update available: v{version}</span>
             </div>
-            <button
-              onClick={downloadAndInstall}
-              disabled={downloading}
-              className="px-3 py-1 bg-blue-600 hover:bg-blue-500 disabled:opacity-50 text-white rounded text-xs font-medium transition-colors"
-            >
-              {downloading ? "Installing..." : "Install"}
-            </button>
+            <div className="flex items-center gap-2">
+              {!downloading && (
+                <button
+                  onClick={skipVersion}
+                  className="text-xs text-blue-300 hover:text-white transition-colors"
+                >
+                  Skip This Version
+                </button>
+              )}
+              <button
+                onClick={downloadAndInstall}
+                disabled={downloading}
+                className="px-3 py-1 bg-blue-600 hover:bg-blue-500 disabled:opacity-50 text-white rounded text-xs font-medium transition-colors"
+              >
+                {downloading ? "Installing..." : "Install"}
+              </button>
+            </div>
           </div>
           {downloading && (
             <div className="mt-2">
@@ -394,8 +635,12 @@ function App() {
             ctmakes
           </a>
         </span>
+        {isWindows && <ImportFancyZones />}
+        {isMac && <ImportMacShortcuts />}
+        <SettingsTransfer />
+        <UpdateChannelSelect />
         <button
-          onClick={checkForUpdates}
+          onClick={() => checkForUpdates(true)}
           disabled={checking}
           className="text-gray-400 hover:text-white disabled:opacity-50 transition-colors"
         >
@@ -0,0 +1,41 @@
+import { useEffect, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+
+export interface OsdState {
+  text: string;
+  size: "small" | "medium" | "large";
+}
+
+declare global {
+  interface Window {
+    __SNAPTOWINDOW_OSD__?: OsdState;
+  }
+}
+
+const TEXT_SIZES = {
+  small: "text-base",
+  medium: "text-xl",
+  large: "text-3xl",
+};
+
+// The HUD shown after a snap; the backend sends the position name for each snap
+function Osd({ initial }: { initial: OsdState }) {
+  const [state, setState] = useState(initial);
+
+  useEffect(() => {
+    const unlisten = listen<OsdState>("osd", (event) => setState(event.payload));
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  return (
+    <div
+      className={`h-screen w-screen flex items-center justify-center bg-gray-900 text-white font-semibold select-none ${TEXT_SIZES[state.size]}`}
+    >
+      {state.text}
+    </div>
+  );
+}
+
+export default Osd;
@@ -1,10 +1,11 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
 import App from "./App";
+import Osd from "./Osd";
 import "./index.css";
 
 ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
   <React.StrictMode>
-    <App />
+    {window.__SNAPTOWINDOW_OSD__ ? <Osd initial={window.__SNAPTOWINDOW_OSD__} /> : <App />}
   </React.StrictMode>
 );